@@ -0,0 +1,181 @@
+use crate::{EventFlag, FsEvent, ScanType};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Coalescing window used by [`crate::EventWatcher::spawn`].
+pub const DEFAULT_EVENT_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Collects event batches for a fixed window and keeps only the latest event per path.
+///
+/// The window starts with the first event after a flush, so a continuous flood of events is
+/// still delivered every `window`. Events that aren't about a single item (history done, root
+/// changed, must-scan-subdirs...) and rename halves are never merged, the former because
+/// they carry no per-path state and the latter because rename pairing relies on adjacency.
+pub(crate) struct EventDebouncer {
+    window: Duration,
+    deadline: Option<Instant>,
+    pending: Vec<Option<FsEvent>>,
+    latest_by_path: HashMap<PathBuf, usize>,
+}
+
+impl EventDebouncer {
+    pub(crate) fn new(window: Duration) -> Self {
+        Self {
+            window,
+            deadline: None,
+            pending: Vec::new(),
+            latest_by_path: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, events: Vec<FsEvent>, now: Instant) {
+        if events.is_empty() {
+            return;
+        }
+        self.deadline.get_or_insert(now + self.window);
+        for event in events {
+            if !is_coalescable(&event) {
+                self.pending.push(Some(event));
+                continue;
+            }
+            let slot = self.pending.len();
+            if let Some(previous) = self.latest_by_path.insert(event.path.clone(), slot) {
+                self.pending[previous] = None;
+            }
+            self.pending.push(Some(event));
+        }
+    }
+
+    /// Time left until the pending batch is due, `None` if nothing is pending.
+    pub(crate) fn time_until_due(&self, now: Instant) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(now))
+    }
+
+    /// Takes the coalesced batch if its window has elapsed.
+    pub(crate) fn take_due(&mut self, now: Instant) -> Option<Vec<FsEvent>> {
+        if self.deadline.is_none_or(|deadline| deadline > now) {
+            return None;
+        }
+        self.deadline = None;
+        self.latest_by_path.clear();
+        Some(self.pending.drain(..).flatten().collect())
+    }
+}
+
+fn is_coalescable(event: &FsEvent) -> bool {
+    const KEEP_AS_IS: EventFlag = EventFlag::MustScanSubDirs
+        .union(EventFlag::UserDropped)
+        .union(EventFlag::KernelDropped)
+        .union(EventFlag::Mount)
+        .union(EventFlag::Unmount)
+        .union(EventFlag::ItemRenamed);
+    !event.flag.intersects(KEEP_AS_IS)
+        && matches!(
+            event.flag.scan_type(),
+            ScanType::SingleNode | ScanType::Folder
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(path: &str, flag: EventFlag, id: u64) -> FsEvent {
+        FsEvent {
+            path: PathBuf::from(path),
+            flag,
+            id,
+        }
+    }
+
+    const WINDOW: Duration = Duration::from_millis(50);
+
+    #[test]
+    fn rapid_duplicates_coalesce_into_latest_event() {
+        let start = Instant::now();
+        let mut debouncer = EventDebouncer::new(WINDOW);
+        for id in 0..100 {
+            let flag = if id == 0 {
+                EventFlag::ItemCreated | EventFlag::ItemIsFile
+            } else {
+                EventFlag::ItemModified | EventFlag::ItemIsFile
+            };
+            debouncer.push(
+                vec![event("/repo/file.rs", flag, id)],
+                start + Duration::from_millis(id / 10),
+            );
+        }
+        assert!(debouncer.take_due(start + WINDOW / 2).is_none());
+
+        let batch = debouncer.take_due(start + WINDOW).unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].id, 99);
+        assert_eq!(
+            batch[0].flag,
+            EventFlag::ItemModified | EventFlag::ItemIsFile
+        );
+        assert!(debouncer.time_until_due(start + WINDOW).is_none());
+    }
+
+    #[test]
+    fn distinct_paths_keep_id_order() {
+        let start = Instant::now();
+        let mut debouncer = EventDebouncer::new(WINDOW);
+        let modified = EventFlag::ItemModified | EventFlag::ItemIsFile;
+        debouncer.push(
+            vec![
+                event("/repo/a", modified, 1),
+                event("/repo/b", modified, 2),
+                event("/repo/a", modified, 3),
+            ],
+            start,
+        );
+        let ids: Vec<u64> = debouncer
+            .take_due(start + WINDOW)
+            .unwrap()
+            .iter()
+            .map(|event| event.id)
+            .collect();
+        assert_eq!(ids, [2, 3]);
+    }
+
+    #[test]
+    fn control_and_rename_events_are_not_merged() {
+        let start = Instant::now();
+        let mut debouncer = EventDebouncer::new(WINDOW);
+        let renamed = EventFlag::ItemRenamed | EventFlag::ItemIsFile;
+        debouncer.push(
+            vec![
+                event("/repo", EventFlag::MustScanSubDirs, 1),
+                event("/repo", EventFlag::MustScanSubDirs, 2),
+                event("/repo/x", renamed, 3),
+                event("/repo/x", renamed, 4),
+                event("", EventFlag::HistoryDone, 5),
+            ],
+            start,
+        );
+        let batch = debouncer.take_due(start + WINDOW).unwrap();
+        assert_eq!(batch.len(), 5);
+    }
+
+    #[test]
+    fn window_starts_with_first_pending_event() {
+        let start = Instant::now();
+        let mut debouncer = EventDebouncer::new(WINDOW);
+        assert!(debouncer.time_until_due(start).is_none());
+        debouncer.push(vec![event("/repo/a", EventFlag::ItemIsFile, 1)], start);
+        debouncer.push(
+            vec![event("/repo/b", EventFlag::ItemIsFile, 2)],
+            start + Duration::from_millis(40),
+        );
+        assert_eq!(
+            debouncer.time_until_due(start + Duration::from_millis(40)),
+            Some(Duration::from_millis(10))
+        );
+        assert_eq!(debouncer.take_due(start + WINDOW).unwrap().len(), 2);
+    }
+}
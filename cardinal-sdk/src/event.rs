@@ -1,4 +1,4 @@
-use crate::{EventFlag, FSEventStreamEventId, ScanType};
+use crate::{EventFlag, EventType, FSEventStreamEventId, ScanType};
 use std::{
     ffi::{CStr, OsStr},
     os::unix::ffi::OsStrExt,
@@ -24,6 +24,27 @@ impl FsEvent {
         FsEvent { path, flag, id }
     }
 
+    /// What happened to the item: created, removed, renamed, modified, metadata changes...
+    /// See [`EventFlag::item_changes`].
+    pub fn changes(&self) -> EventFlag {
+        self.flag.item_changes()
+    }
+
+    /// Whether the item is a file, a folder, a symlink or a hard link.
+    pub fn item_type(&self) -> EventType {
+        self.flag.event_type()
+    }
+
+    /// The watched root this event belongs to, the deepest one if roots are nested.
+    /// Matching is per path component, so `/a/bc` doesn't belong to `/a/b`.
+    pub fn root_in<'a, P: AsRef<Path>>(&self, roots: &'a [P]) -> Option<&'a Path> {
+        roots
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|root| self.path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+    }
+
     pub fn should_rescan(&self, root: &Path) -> bool {
         match self.flag.scan_type() {
             ScanType::ReScan => true,
@@ -33,6 +54,31 @@ impl FsEvent {
     }
 }
 
+/// Drops events at or below any of `ignore_paths`. `Path::starts_with` compares whole
+/// components, so ignoring `/a/b` keeps `/a/bc`.
+pub(crate) fn retain_unignored(events: &mut Vec<FsEvent>, ignore_paths: &[PathBuf]) {
+    if ignore_paths.is_empty() {
+        return;
+    }
+    events.retain(|event| {
+        !ignore_paths
+            .iter()
+            .any(|ignored| event.path.starts_with(ignored))
+    });
+}
+
+/// Keeps the events of a historical replay: ids in `(since, until]`, without the
+/// `HistoryDone` marker FSEvents appends once the history has been delivered.
+pub(crate) fn retain_replayed(
+    events: &mut Vec<FsEvent>,
+    since: FSEventStreamEventId,
+    until: FSEventStreamEventId,
+) {
+    events.retain(|event| {
+        event.id > since && event.id <= until && !event.flag.contains(EventFlag::HistoryDone)
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -74,4 +120,142 @@ mod tests {
         };
         assert!(!event.should_rescan(root));
     }
+
+    #[test]
+    fn raw_dropped_and_wrapped_flags_request_a_full_rescan() {
+        use objc2_core_services::{
+            kFSEventStreamEventFlagEventIdsWrapped, kFSEventStreamEventFlagKernelDropped,
+            kFSEventStreamEventFlagMustScanSubDirs, kFSEventStreamEventFlagUserDropped,
+        };
+        use std::ffi::CString;
+
+        let root = std::path::Path::new("/root");
+        // Not the root itself, so only the lost events make it a full rescan.
+        let path = CString::new("/root/sub").unwrap();
+        for raw in [
+            kFSEventStreamEventFlagMustScanSubDirs | kFSEventStreamEventFlagKernelDropped,
+            kFSEventStreamEventFlagMustScanSubDirs | kFSEventStreamEventFlagUserDropped,
+            kFSEventStreamEventFlagEventIdsWrapped,
+        ] {
+            let event = unsafe { FsEvent::from_raw(path.as_ptr(), raw, 1) };
+            assert!(event.flag.lost_events(), "{raw:#x}");
+            assert!(event.should_rescan(root), "{raw:#x}");
+        }
+
+        let event =
+            unsafe { FsEvent::from_raw(path.as_ptr(), kFSEventStreamEventFlagMustScanSubDirs, 1) };
+        assert!(!event.should_rescan(root));
+    }
+
+    #[test]
+    fn raw_flags_keep_distinct_changes() {
+        use objc2_core_services::{
+            kFSEventStreamEventFlagItemCreated, kFSEventStreamEventFlagItemInodeMetaMod,
+            kFSEventStreamEventFlagItemIsDir, kFSEventStreamEventFlagItemIsFile,
+            kFSEventStreamEventFlagItemModified, kFSEventStreamEventFlagItemRemoved,
+            kFSEventStreamEventFlagItemRenamed,
+        };
+        use std::ffi::CString;
+
+        let path = CString::new("/tmp/file").unwrap();
+        let event = |flag: u32| unsafe { FsEvent::from_raw(path.as_ptr(), flag, 1) };
+
+        let created = event(kFSEventStreamEventFlagItemCreated | kFSEventStreamEventFlagItemIsFile);
+        assert_eq!(created.changes(), EventFlag::ItemCreated);
+        assert_eq!(created.item_type(), EventType::File);
+        assert_eq!(created.path, PathBuf::from("/tmp/file"));
+
+        let renamed = event(kFSEventStreamEventFlagItemRenamed | kFSEventStreamEventFlagItemIsDir);
+        assert_eq!(renamed.changes(), EventFlag::ItemRenamed);
+        assert_eq!(renamed.item_type(), EventType::Dir);
+
+        let coalesced = event(
+            kFSEventStreamEventFlagItemCreated
+                | kFSEventStreamEventFlagItemModified
+                | kFSEventStreamEventFlagItemInodeMetaMod
+                | kFSEventStreamEventFlagItemRemoved
+                | kFSEventStreamEventFlagItemIsFile,
+        );
+        assert_eq!(
+            coalesced.changes(),
+            EventFlag::ItemCreated
+                | EventFlag::ItemModified
+                | EventFlag::ItemInodeMetaMod
+                | EventFlag::ItemRemoved
+        );
+        // The coarse flag still carries everything for existing callers.
+        assert!(coalesced.flag.contains(EventFlag::ItemIsFile));
+    }
+
+    #[test]
+    fn root_in_attributes_events_to_deepest_root() {
+        let roots = [
+            PathBuf::from("/work/a"),
+            PathBuf::from("/work/b"),
+            PathBuf::from("/work/a/nested"),
+        ];
+        let event = |path: &str| FsEvent {
+            path: PathBuf::from(path),
+            flag: EventFlag::ItemCreated | EventFlag::ItemIsFile,
+            id: 1,
+        };
+
+        assert_eq!(event("/work/a/x").root_in(&roots), Some(roots[0].as_path()));
+        assert_eq!(event("/work/b").root_in(&roots), Some(roots[1].as_path()));
+        assert_eq!(
+            event("/work/a/nested/y").root_in(&roots),
+            Some(roots[2].as_path())
+        );
+        assert_eq!(event("/work/bc/z").root_in(&roots), None);
+        assert_eq!(event("/elsewhere").root_in(&roots), None);
+    }
+
+    #[test]
+    fn retain_unignored_drops_ignored_subtree_only() {
+        let event = |path: &str| FsEvent {
+            path: PathBuf::from(path),
+            flag: EventFlag::ItemModified | EventFlag::ItemIsFile,
+            id: 1,
+        };
+        let mut events = vec![
+            event("/System/Volumes/Data"),
+            event("/System/Volumes/Data/Users/a.txt"),
+            event("/System/Volumes/DataBackup/b.txt"),
+            event("/System/Volumes/c.txt"),
+            event("/Users/d.txt"),
+        ];
+        retain_unignored(&mut events, &[PathBuf::from("/System/Volumes/Data")]);
+
+        let paths: Vec<&Path> = events.iter().map(|event| event.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            [
+                Path::new("/System/Volumes/DataBackup/b.txt"),
+                Path::new("/System/Volumes/c.txt"),
+                Path::new("/Users/d.txt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn retain_replayed_keeps_ids_after_since_up_to_until() {
+        let event = |id, flag| FsEvent {
+            path: PathBuf::from("/work/file"),
+            flag,
+            id,
+        };
+        let modified = EventFlag::ItemModified | EventFlag::ItemIsFile;
+        let mut events = vec![
+            event(9, modified),
+            event(10, modified),
+            event(11, modified),
+            event(15, modified),
+            event(15, EventFlag::HistoryDone),
+            event(16, modified),
+        ];
+        retain_replayed(&mut events, 10, 15);
+
+        let ids: Vec<FSEventStreamEventId> = events.iter().map(|event| event.id).collect();
+        assert_eq!(ids, [11, 15]);
+    }
 }
@@ -44,14 +44,33 @@ pub enum ScanType {
     SingleNode,
     /// Scan the whole folder, including sub-folders.
     Folder,
-    /// Something wrong happened, do re-indexing.
-    /// Should only happen with `kFSEventStreamCreateFlagWatchRoot` set in EventStream::new().
+    /// Something wrong happened, do re-indexing: the watched root changed (only reported with
+    /// `kFSEventStreamCreateFlagWatchRoot` set in EventStream::new()) or events were lost, see
+    /// [`EventFlag::lost_events`].
     ReScan,
     /// Do nothing, since event id is always updated.
     Nop,
 }
 
 impl EventFlag {
+    /// Bits describing what happened to the item itself, as opposed to stream bookkeeping
+    /// (`HistoryDone`, `MustScanSubDirs`, ...) and the item type (`ItemIsFile`, ...).
+    pub const ITEM_CHANGES: EventFlag = EventFlag::ItemCreated
+        .union(EventFlag::ItemRemoved)
+        .union(EventFlag::ItemRenamed)
+        .union(EventFlag::ItemModified)
+        .union(EventFlag::ItemInodeMetaMod)
+        .union(EventFlag::ItemFinderInfoMod)
+        .union(EventFlag::ItemChangeOwner)
+        .union(EventFlag::ItemXattrMod)
+        .union(EventFlag::Cloned);
+
+    /// The [`Self::ITEM_CHANGES`] bits of this flag. FSEvents coalesces changes, so several
+    /// may be set at once.
+    pub fn item_changes(&self) -> EventFlag {
+        self.intersection(Self::ITEM_CHANGES)
+    }
+
     pub fn event_type(&self) -> EventType {
         if self.contains(EventFlag::IsHardlink) | self.contains(EventFlag::IsLastHardlink) {
             EventType::Hardlink
@@ -66,13 +85,28 @@ impl EventFlag {
         }
     }
 
+    /// Whether FSEvents dropped events, in its own buffers or the kernel's, or its event ids
+    /// wrapped around. Either way nothing tells which changes were missed, so everything
+    /// watched has to be rescanned.
+    pub fn lost_events(&self) -> bool {
+        self.intersects(
+            EventFlag::UserDropped
+                .union(EventFlag::KernelDropped)
+                .union(EventFlag::EventIdsWrapped),
+        )
+    }
+
     pub fn scan_type(&self) -> ScanType {
         let event_type = self.event_type();
         let is_dir = matches!(event_type, EventType::Dir);
-        if self.contains(EventFlag::HistoryDone) | self.contains(EventFlag::EventIdsWrapped) {
+        if self.contains(EventFlag::HistoryDone) {
             ScanType::Nop
-        } else if self.contains(EventFlag::RootChanged) {
+        } else if self.contains(EventFlag::RootChanged) || self.lost_events() {
             ScanType::ReScan
+        } else if self.contains(EventFlag::MustScanSubDirs) {
+            // Changes under this folder were coalesced into a single event that usually
+            // carries no item type bits, so the whole subtree has to be re-read.
+            ScanType::Folder
         } else {
             // Strange event, doesn't know when it happens, processing it using a generic way
             // e.g. new event: fs_event=FsEvent { path: "/.docid/16777229/changed/782/src=0,dst=41985052", flag: kFSEventStreamEventFlagNone, id: 471533015 }
@@ -142,6 +176,23 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_item_changes_drop_type_and_stream_bits() {
+        let flag = EventFlag::ItemCreated
+            | EventFlag::ItemRenamed
+            | EventFlag::ItemIsFile
+            | EventFlag::MustScanSubDirs;
+        assert_eq!(
+            flag.item_changes(),
+            EventFlag::ItemCreated | EventFlag::ItemRenamed
+        );
+        assert_eq!(EventFlag::HistoryDone.item_changes(), EventFlag::empty());
+        assert_eq!(
+            (EventFlag::ItemInodeMetaMod | EventFlag::ItemIsDir).item_changes(),
+            EventFlag::ItemInodeMetaMod
+        );
+    }
+
     #[test]
     fn test_scan_type_must_scan_subdirs() {
         // MustScanSubDirs => Folder
@@ -149,5 +200,23 @@ mod tests {
             (EventFlag::MustScanSubDirs | EventFlag::ItemIsDir).scan_type(),
             ScanType::Folder
         ));
+        // FSEvents usually sends the flag alone when it only coalesced changes.
+        assert_eq!(EventFlag::MustScanSubDirs.scan_type(), ScanType::Folder);
+    }
+
+    #[test]
+    fn test_scan_type_lost_events() {
+        // Dropped events arrive with MustScanSubDirs, but the whole watch has to be redone.
+        for flag in [
+            EventFlag::MustScanSubDirs | EventFlag::KernelDropped,
+            EventFlag::MustScanSubDirs | EventFlag::UserDropped,
+            EventFlag::EventIdsWrapped,
+        ] {
+            assert!(flag.lost_events(), "{flag:?}");
+            assert_eq!(flag.scan_type(), ScanType::ReScan, "{flag:?}");
+        }
+        assert!(!EventFlag::MustScanSubDirs.lost_events());
+        assert!(!(EventFlag::ItemCreated | EventFlag::ItemIsFile).lost_events());
+        assert_eq!(EventFlag::HistoryDone.scan_type(), ScanType::Nop);
     }
 }
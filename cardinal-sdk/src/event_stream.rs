@@ -1,5 +1,12 @@
-use crate::FsEvent;
-use crossbeam_channel::{Receiver, Sender, bounded, unbounded};
+use crate::{
+    DEFAULT_EVENT_DEBOUNCE, EventFlag, FsEvent,
+    debounce::EventDebouncer,
+    event::{retain_replayed, retain_unignored},
+    utils::current_event_id,
+};
+use crossbeam_channel::{
+    Receiver, RecvTimeoutError, Sender, after, bounded, never, select, unbounded,
+};
 use dispatch2::{DispatchQueue, DispatchQueueAttr, DispatchRetained};
 use libc::dev_t;
 use objc2_core_foundation::{CFArray, CFString, CFTimeInterval};
@@ -13,8 +20,10 @@ use objc2_core_services::{
 use std::{
     ffi::c_void,
     ops::{Deref, DerefMut},
+    path::PathBuf,
     ptr::NonNull,
     slice,
+    time::{Duration, Instant},
 };
 
 type EventsCallback = Box<dyn FnMut(Vec<FsEvent>) + Send>;
@@ -135,6 +144,29 @@ impl Drop for EventStreamWithQueue {
 pub struct EventWatcher {
     receiver: Receiver<Vec<FsEvent>>,
     _cancellation_token: Sender<()>,
+    stop_handle: EventWatcherStopHandle,
+}
+
+/// Stops an [`EventWatcher`] from any thread, independently of dropping it.
+///
+/// Once the watcher thread has stopped the FSEventStream, the watcher's receiver is
+/// disconnected. Dropping the handle doesn't stop anything.
+#[derive(Clone)]
+pub struct EventWatcherStopHandle {
+    stop: Sender<()>,
+    stopped: Receiver<()>,
+}
+
+impl EventWatcherStopHandle {
+    /// Asks the watcher thread to stop and waits up to `timeout` for the FSEventStream to be
+    /// stopped and invalidated. Returns whether it did so in time.
+    pub fn stop(&self, timeout: Duration) -> bool {
+        let _ = self.stop.try_send(());
+        matches!(
+            self.stopped.recv_timeout(timeout),
+            Err(RecvTimeoutError::Disconnected)
+        )
+    }
 }
 
 impl Deref for EventWatcher {
@@ -156,30 +188,140 @@ impl EventWatcher {
         Self {
             receiver: unbounded().1,
             _cancellation_token: bounded::<()>(1).0,
+            stop_handle: EventWatcherStopHandle {
+                stop: bounded::<()>(1).0,
+                stopped: bounded::<()>(0).1,
+            },
+        }
+    }
+
+    /// Handle that can stop this watcher, e.g. before a rescan replaces it.
+    pub fn stop_handle(&self) -> EventWatcherStopHandle {
+        self.stop_handle.clone()
+    }
+
+    /// Shorthand for `self.stop_handle().stop(timeout)`.
+    pub fn stop(&self, timeout: Duration) -> bool {
+        self.stop_handle.stop(timeout)
+    }
+
+    /// Fetches the historical events under `roots` with ids after `since_event_id`, up to the
+    /// [`current_event_id`] at the time of the call, so a stale cache can catch up before live
+    /// watching starts. Waits at most `timeout` for FSEvents to finish the history and returns
+    /// what arrived by then, in delivery order and without debouncing.
+    pub fn replay_since(
+        roots: Vec<String>,
+        ignore_paths: Vec<PathBuf>,
+        since_event_id: FSEventStreamEventId,
+        timeout: Duration,
+    ) -> Vec<FsEvent> {
+        let until = current_event_id();
+        if since_event_id >= until {
+            return Vec::new();
+        }
+        let (sender, receiver) = unbounded();
+        let roots: Vec<&str> = roots.iter().map(String::as_str).collect();
+        let stream = EventStream::new(
+            &roots,
+            since_event_id,
+            0.0,
+            Box::new(move |events| {
+                let _ = sender.send(events);
+            }),
+        );
+        let Some(_stream_and_queue) = stream.spawn() else {
+            return Vec::new();
+        };
+        let deadline = Instant::now() + timeout;
+        let mut replayed = Vec::new();
+        while let Ok(mut events) = receiver.recv_deadline(deadline) {
+            // Live events may follow the history in the same batch.
+            let caught_up = events
+                .iter()
+                .any(|event| event.flag.contains(EventFlag::HistoryDone) || event.id >= until);
+            retain_replayed(&mut events, since_event_id, until);
+            retain_unignored(&mut events, &ignore_paths);
+            replayed.extend(events);
+            if caught_up {
+                break;
+            }
         }
+        replayed
     }
 
+    /// Watches `roots` in a single stream, coalescing events with [`DEFAULT_EVENT_DEBOUNCE`].
+    ///
+    /// Event paths are absolute, use [`FsEvent::root_in`] to attribute them to a root.
+    /// Events at or below any of `ignore_paths` are dropped before delivery.
     pub fn spawn(
-        path: String,
+        roots: Vec<String>,
+        ignore_paths: Vec<PathBuf>,
+        since_event_id: FSEventStreamEventId,
+        latency: f64,
+    ) -> (dev_t, EventWatcher) {
+        Self::spawn_with_debounce(
+            roots,
+            ignore_paths,
+            since_event_id,
+            latency,
+            DEFAULT_EVENT_DEBOUNCE,
+        )
+    }
+
+    /// `latency` is handed to FSEvents, while `debounce` is the window in which repeated
+    /// events for the same path are merged into the latest one before delivery. A zero
+    /// `debounce` forwards batches as FSEvents delivers them.
+    pub fn spawn_with_debounce(
+        roots: Vec<String>,
+        ignore_paths: Vec<PathBuf>,
         since_event_id: FSEventStreamEventId,
         latency: f64,
+        debounce: Duration,
     ) -> (dev_t, EventWatcher) {
         let (_cancellation_token, cancellation_token_rx) = bounded::<()>(1);
+        let (stop, stop_rx) = bounded::<()>(1);
+        let (stopped_guard, stopped) = bounded::<()>(0);
+        let (raw_sender, raw_receiver) = unbounded();
         let (sender, receiver) = unbounded();
+        let roots: Vec<&str> = roots.iter().map(String::as_str).collect();
         let stream = EventStream::new(
-            &[&path],
+            &roots,
             since_event_id,
             latency,
             Box::new(move |events| {
-                let _ = sender.send(events);
+                let _ = raw_sender.send(events);
             }),
         );
         let dev = stream.dev();
         std::thread::Builder::new()
             .name("cardinal-sdk-event-watcher".to_string())
             .spawn(move || {
+                // Dropped after the stream, which signals `EventWatcherStopHandle::stop`.
+                let _stopped_guard = stopped_guard;
                 let _stream_and_queue = stream.spawn().expect("failed to spawn event stream");
-                let _ = cancellation_token_rx.recv();
+                let mut debouncer = EventDebouncer::new(debounce);
+                loop {
+                    let flush_timer = debouncer
+                        .time_until_due(Instant::now())
+                        .map_or_else(never, after);
+                    select! {
+                        recv(raw_receiver) -> events => match events {
+                            Ok(mut events) => {
+                                retain_unignored(&mut events, &ignore_paths);
+                                debouncer.push(events, Instant::now());
+                            }
+                            Err(_) => break,
+                        },
+                        recv(flush_timer) -> _ => {}
+                        recv(cancellation_token_rx) -> _ => break,
+                        recv(stop_rx) -> _ => break,
+                    }
+                    if let Some(events) = debouncer.take_due(Instant::now()) {
+                        if sender.send(events).is_err() {
+                            break;
+                        }
+                    }
+                }
             })
             .unwrap();
         (
@@ -187,6 +329,7 @@ impl EventWatcher {
             EventWatcher {
                 receiver,
                 _cancellation_token,
+                stop_handle: EventWatcherStopHandle { stop, stopped },
             },
         )
     }
@@ -196,8 +339,7 @@ impl EventWatcher {
 mod tests {
     use super::*;
     use crate::utils::current_event_id;
-    use crossbeam_channel::RecvTimeoutError;
-    use std::time::{Duration, Instant};
+    use std::path::PathBuf;
     use tempfile::tempdir;
 
     #[test]
@@ -211,14 +353,19 @@ mod tests {
             .expect("tempdir path should be utf8")
             .to_string();
 
-        let (_, initial_watcher) =
-            EventWatcher::spawn(watch_path.clone(), current_event_id(), 0.05);
+        let (_, initial_watcher) = EventWatcher::spawn(
+            vec![watch_path.clone()],
+            Vec::new(),
+            current_event_id(),
+            0.05,
+        );
         drop(initial_watcher);
 
         // Give the background thread a moment to observe the drop.
         std::thread::sleep(Duration::from_millis(500));
 
-        let (_, respawned_watcher) = EventWatcher::spawn(watch_path, current_event_id(), 0.05);
+        let (_, respawned_watcher) =
+            EventWatcher::spawn(vec![watch_path], Vec::new(), current_event_id(), 0.05);
 
         // Allow the stream to start before triggering filesystem activity.
         std::thread::sleep(Duration::from_millis(500));
@@ -250,4 +397,109 @@ mod tests {
             "respawned watcher failed to deliver file change event"
         );
     }
+
+    #[test]
+    fn replay_since_returns_only_later_events() {
+        let temp_dir = tempdir().expect("failed to create tempdir");
+        let watched_root = temp_dir
+            .path()
+            .canonicalize()
+            .expect("failed to canonicalize");
+        let watch_path = watched_root
+            .to_str()
+            .expect("tempdir path should be utf8")
+            .to_string();
+
+        let before = watched_root.join("before_replay.txt");
+        std::fs::write(&before, "cardinal").expect("failed to write test file");
+        // Let FSEvents assign ids to the first write before recording the starting point.
+        std::thread::sleep(Duration::from_millis(500));
+        let since = current_event_id();
+        let after = watched_root.join("after_replay.txt");
+        std::fs::write(&after, "cardinal").expect("failed to write test file");
+        std::thread::sleep(Duration::from_millis(500));
+
+        let events =
+            EventWatcher::replay_since(vec![watch_path], Vec::new(), since, Duration::from_secs(5));
+        assert!(events.iter().all(|event| event.id > since));
+        assert!(events.iter().any(|event| event.path == after));
+        assert!(events.iter().all(|event| event.path != before));
+    }
+
+    #[test]
+    fn stop_handle_stops_watcher_within_timeout() {
+        let temp_dir = tempdir().expect("failed to create tempdir");
+        let watch_path = temp_dir
+            .path()
+            .canonicalize()
+            .expect("failed to canonicalize")
+            .to_str()
+            .expect("tempdir path should be utf8")
+            .to_string();
+
+        let (_, watcher) =
+            EventWatcher::spawn(vec![watch_path], Vec::new(), current_event_id(), 0.05);
+        let stop_handle = watcher.stop_handle();
+        // Let the stream start so stopping actually tears it down.
+        std::thread::sleep(Duration::from_millis(200));
+
+        assert!(stop_handle.stop(Duration::from_secs(5)));
+        assert!(matches!(
+            watcher.recv_timeout(Duration::from_secs(1)),
+            Err(RecvTimeoutError::Disconnected)
+        ));
+        // Stopping again is a no-op.
+        assert!(watcher.stop(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn watcher_reports_changes_under_every_root() {
+        let temp_dirs = [
+            tempdir().expect("failed to create tempdir"),
+            tempdir().expect("failed to create tempdir"),
+        ];
+        let roots: Vec<PathBuf> = temp_dirs
+            .iter()
+            .map(|dir| dir.path().canonicalize().expect("failed to canonicalize"))
+            .collect();
+        let root_strings = roots
+            .iter()
+            .map(|root| {
+                root.to_str()
+                    .expect("tempdir path should be utf8")
+                    .to_string()
+            })
+            .collect();
+
+        let (_, watcher) = EventWatcher::spawn(root_strings, Vec::new(), current_event_id(), 0.05);
+        std::thread::sleep(Duration::from_millis(500));
+
+        let created_files: Vec<PathBuf> = roots
+            .iter()
+            .map(|root| root.join("multi_root_event.txt"))
+            .collect();
+        for file in &created_files {
+            std::fs::write(file, "cardinal").expect("failed to write test file");
+        }
+
+        let mut observed = [false, false];
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline && observed.contains(&false) {
+            match watcher.recv_timeout(Duration::from_millis(200)) {
+                Ok(batch) => {
+                    for event in batch {
+                        let Some(index) = created_files.iter().position(|f| *f == event.path)
+                        else {
+                            continue;
+                        };
+                        assert_eq!(event.root_in(&roots), Some(roots[index].as_path()));
+                        observed[index] = true;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        assert_eq!(observed, [true, true], "missing events for some roots");
+    }
 }
@@ -1,10 +1,14 @@
+mod debounce;
 mod event;
 mod event_flag;
 mod event_stream;
+mod rename;
 mod utils;
 
+pub use debounce::DEFAULT_EVENT_DEBOUNCE;
 pub use event::FsEvent;
 pub use event_flag::{EventFlag, EventType, ScanType};
-pub use event_stream::{EventStream, EventWatcher};
+pub use event_stream::{EventStream, EventWatcher, EventWatcherStopHandle};
 pub use objc2_core_services::FSEventStreamEventId;
+pub use rename::{PairedEvent, pair_renames};
 pub use utils::{current_event_id, event_id_to_timestamp};
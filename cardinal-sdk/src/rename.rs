@@ -0,0 +1,135 @@
+use crate::{EventFlag, FsEvent};
+
+/// A batch entry after rename pairing, see [`pair_renames`].
+#[derive(Debug)]
+pub enum PairedEvent {
+    /// Any other event, including a rename whose counterpart is outside the batch.
+    Single(FsEvent),
+    /// Both sides of a rename: the item previously at `from.path` now lives at `to.path`.
+    Rename { from: FsEvent, to: FsEvent },
+}
+
+/// FSEvents reports a rename as two `ItemRenamed` events, the old path followed by the new one,
+/// with consecutive event ids. This folds such pairs into a single [`PairedEvent::Rename`].
+///
+/// Renames with only one side in the batch (e.g. moved in from or out to an unwatched
+/// location) are kept as [`PairedEvent::Single`], so they keep being handled as a plain
+/// create or delete.
+pub fn pair_renames(events: Vec<FsEvent>) -> Vec<PairedEvent> {
+    let mut paired = Vec::with_capacity(events.len());
+    let mut events = events.into_iter().peekable();
+    while let Some(event) = events.next() {
+        if let Some(next) = events.next_if(|next| is_rename_pair(&event, next)) {
+            paired.push(PairedEvent::Rename {
+                from: event,
+                to: next,
+            });
+        } else {
+            paired.push(PairedEvent::Single(event));
+        }
+    }
+    paired
+}
+
+fn is_rename_pair(from: &FsEvent, to: &FsEvent) -> bool {
+    from.flag.contains(EventFlag::ItemRenamed)
+        && to.flag.contains(EventFlag::ItemRenamed)
+        && from.id.checked_add(1) == Some(to.id)
+        && from.flag.event_type() == to.flag.event_type()
+        && from.path != to.path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    fn event(path: &str, flag: EventFlag, id: u64) -> FsEvent {
+        FsEvent {
+            path: PathBuf::from(path),
+            flag,
+            id,
+        }
+    }
+
+    fn renamed_file(path: &str, id: u64) -> FsEvent {
+        event(path, EventFlag::ItemRenamed | EventFlag::ItemIsFile, id)
+    }
+
+    fn assert_rename(paired: &PairedEvent, from: &str, to: &str) {
+        match paired {
+            PairedEvent::Rename {
+                from: from_event,
+                to: to_event,
+            } => {
+                assert_eq!(from_event.path, Path::new(from));
+                assert_eq!(to_event.path, Path::new(to));
+            }
+            other => panic!("expected rename, got {other:?}"),
+        }
+    }
+
+    fn assert_single(paired: &PairedEvent, path: &str) {
+        match paired {
+            PairedEvent::Single(event) => assert_eq!(event.path, Path::new(path)),
+            other => panic!("expected single event, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn pairs_consecutive_rename_events() {
+        let paired = pair_renames(vec![
+            renamed_file("/root/old.txt", 10),
+            renamed_file("/root/new.txt", 11),
+        ]);
+        assert_eq!(paired.len(), 1);
+        assert_rename(&paired[0], "/root/old.txt", "/root/new.txt");
+    }
+
+    #[test]
+    fn unpaired_rename_degrades_to_single() {
+        let paired = pair_renames(vec![
+            renamed_file("/root/moved_out.txt", 10),
+            event(
+                "/root/created.txt",
+                EventFlag::ItemCreated | EventFlag::ItemIsFile,
+                11,
+            ),
+        ]);
+        assert_eq!(paired.len(), 2);
+        assert_single(&paired[0], "/root/moved_out.txt");
+        assert_single(&paired[1], "/root/created.txt");
+    }
+
+    #[test]
+    fn back_to_back_renames_pair_independently() {
+        let paired = pair_renames(vec![
+            renamed_file("/root/a", 1),
+            renamed_file("/root/b", 2),
+            renamed_file("/root/c", 3),
+            renamed_file("/root/d", 4),
+            renamed_file("/root/e", 5),
+        ]);
+        assert_eq!(paired.len(), 3);
+        assert_rename(&paired[0], "/root/a", "/root/b");
+        assert_rename(&paired[1], "/root/c", "/root/d");
+        assert_single(&paired[2], "/root/e");
+    }
+
+    #[test]
+    fn non_consecutive_ids_or_mismatched_types_are_not_paired() {
+        let paired = pair_renames(vec![
+            renamed_file("/root/a", 1),
+            renamed_file("/root/b", 3),
+            event(
+                "/root/dir",
+                EventFlag::ItemRenamed | EventFlag::ItemIsDir,
+                4,
+            ),
+        ]);
+        assert_eq!(paired.len(), 3);
+        assert_single(&paired[0], "/root/a");
+        assert_single(&paired[1], "/root/b");
+        assert_single(&paired[2], "/root/dir");
+    }
+}
@@ -29,15 +29,222 @@
 //! }
 //! ```
 
-use std::fmt;
+use std::{borrow::Cow, collections::HashSet, fmt};
+
+mod tokenize;
+
+pub use tokenize::{Token, TokenKind, tokenize};
 
 /// Parses an Everything-like query string into a structured expression tree.
 pub fn parse_query(input: &str) -> Result<Query, ParseError> {
-    Parser::new(input).parse()
+    Parser::new(input, &ParseOptions::default()).parse()
 }
 
-/// User input normalized into a single expression tree.
+/// Like [`parse_query`], but keeps going after an error so every problem in
+/// `input` can be reported at once. Each failed term is replaced by
+/// [`Expr::Empty`]. When the returned errors are empty the query is the one
+/// [`parse_query`] returns.
+///
+/// ```
+/// use cardinal_syntax::parse_query_recovering;
+/// let (_, errors) = parse_query_recovering("(foo \"bar");
+/// let messages: Vec<_> = errors.iter().map(|err| err.message.as_str()).collect();
+/// assert_eq!(messages, ["missing closing quote", "expected ')'"]);
+/// ```
+pub fn parse_query_recovering(input: &str) -> (Query, Vec<ParseError>) {
+    Parser::new(input, &ParseOptions::default()).parse_recovering()
+}
+
+/// Removes `_` and `,` digit group separators from a numeric filter value
+/// (`1_000_000`, `1,000kb`). Only separators between two digits are dropped,
+/// so units and other text are kept as is.
+///
+/// ```
+/// use cardinal_syntax::strip_digit_separators;
+/// assert_eq!(strip_digit_separators("1,000,000kb"), "1000000kb");
+/// assert_eq!(strip_digit_separators("1_0_"), "10_");
+/// ```
+pub fn strip_digit_separators(value: &str) -> Cow<'_, str> {
+    let bytes = value.as_bytes();
+    let is_separator = |idx: usize| {
+        matches!(bytes[idx], b'_' | b',')
+            && idx > 0
+            && bytes[idx - 1].is_ascii_digit()
+            && bytes.get(idx + 1).is_some_and(u8::is_ascii_digit)
+    };
+    if !(0..bytes.len()).any(is_separator) {
+        return Cow::Borrowed(value);
+    }
+    let stripped = value
+        .char_indices()
+        .filter(|&(idx, _)| !is_separator(idx))
+        .map(|(_, ch)| ch)
+        .collect();
+    Cow::Owned(stripped)
+}
+
+/// Like [`parse_query`], with the opt-in behaviors in `options` applied.
+///
+/// ```
+/// use cardinal_syntax::{parse_query_with, Expr, ParseOptions, Term};
+/// let options = ParseOptions { allow_comments: true, ..Default::default() };
+/// let query = parse_query_with("report # weekly reports", &options).unwrap();
+/// assert!(matches!(query.expr, Expr::Term(Term::Word(word)) if word == "report"));
+/// ```
+pub fn parse_query_with(input: &str, options: &ParseOptions) -> Result<Query, ParseError> {
+    let input = if options.allow_comments {
+        strip_comments(input)
+    } else {
+        Cow::Borrowed(input)
+    };
+    Parser::new(&input, options).parse()
+}
+
+/// Opt-in parser behaviors that Everything itself doesn't have. The default
+/// matches [`parse_query`].
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Treat `#` outside quotes as the start of a comment running to the end
+    /// of the line, so saved query files can be annotated.
+    pub allow_comments: bool,
+    /// Custom filter names (e.g. Cardinal macros such as `musiclastyear`) that
+    /// are known to the caller. They still parse as [`FilterKind::Custom`];
+    /// matching is ASCII case-insensitive.
+    pub known_custom_filters: HashSet<String>,
+    /// Reject custom filter names missing from `known_custom_filters` with a
+    /// parse error instead of accepting any `name:`.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query_with, ParseOptions};
+    /// let options = ParseOptions {
+    ///     known_custom_filters: ["proj".to_string()].into(),
+    ///     reject_unknown_filters: true,
+    ///     ..Default::default()
+    /// };
+    /// assert!(parse_query_with("proj:cardinal", &options).is_ok());
+    /// assert!(parse_query_with("bogus:", &options).is_err());
+    /// ```
+    pub reject_unknown_filters: bool,
+    /// Characters that split a filter argument into an [`ArgumentKind::List`].
+    /// `;` splits any filter argument; the others (`,` by default) only split
+    /// filters taking plain names such as `ext:` and `type:`, so commas in
+    /// paths and regexes stay intact.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query_with, ArgumentKind, Expr, ParseOptions, Term};
+    /// let options = ParseOptions { list_separators: vec![';'], ..Default::default() };
+    /// let Expr::Term(Term::Filter(filter)) = parse_query_with("ext:jpg,png", &options).unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.argument.unwrap().kind, ArgumentKind::Bare));
+    /// ```
+    pub list_separators: Vec<char>,
+    /// Accept `name=value` as a spelling of `name:value` for built-in filters
+    /// and `known_custom_filters`. Other `a=b` tokens stay words.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, parse_query_with, ParseOptions};
+    /// let options = ParseOptions { equals_filters: true, ..Default::default() };
+    /// assert_eq!(
+    ///     parse_query_with("type=picture", &options).unwrap(),
+    ///     parse_query("type:picture").unwrap(),
+    /// );
+    /// ```
+    pub equals_filters: bool,
+    /// How whitespace-separated terms combine. Explicit `AND`, `OR` and `|`
+    /// keep their meaning either way, `AND` still binding its neighbours.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query_with, Expr, ImplicitOp, ParseOptions};
+    /// let options = ParseOptions { implicit_operator: ImplicitOp::Or, ..Default::default() };
+    /// assert!(matches!(parse_query_with("foo bar", &options).unwrap().expr, Expr::Or(_)));
+    /// ```
+    pub implicit_operator: ImplicitOp,
+    /// How deeply `(...)` and `<...>` groups may nest, [`DEFAULT_MAX_DEPTH`] by
+    /// default. Deeper input is a [`ParseError`] instead of a stack overflow.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query_with, ParseOptions};
+    /// let options = ParseOptions { max_depth: 2, ..Default::default() };
+    /// assert!(parse_query_with("((a))", &options).is_ok());
+    /// assert!(parse_query_with("(((a)))", &options).is_err());
+    /// ```
+    pub max_depth: usize,
+}
+
+/// Group nesting accepted by default, see [`ParseOptions::max_depth`].
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// The operator implied between whitespace-separated terms, see
+/// [`ParseOptions::implicit_operator`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImplicitOp {
+    /// Every term must match, like Everything.
+    #[default]
+    And,
+    /// Any term may match.
+    Or,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            allow_comments: false,
+            known_custom_filters: HashSet::new(),
+            reject_unknown_filters: false,
+            list_separators: vec![';', ','],
+            equals_filters: false,
+            implicit_operator: ImplicitOp::And,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+impl ParseOptions {
+    fn is_known_custom_filter(&self, name: &str) -> bool {
+        self.known_custom_filters
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether `name=` opens a filter under [`Self::equals_filters`].
+    fn is_equals_filter_name(&self, name: &str) -> bool {
+        self.equals_filters
+            && is_valid_filter_name(name)
+            && (name.eq_ignore_ascii_case("regex")
+                || !matches!(FilterKind::from_name(name), FilterKind::Custom(_))
+                || self.is_known_custom_filter(name))
+    }
+}
+
+/// Blanks out `#` comments. Every removed byte becomes a space so error
+/// positions still point into the original input.
+fn strip_comments(input: &str) -> Cow<'_, str> {
+    if !input.contains('#') {
+        return Cow::Borrowed(input);
+    }
+    let mut output = String::with_capacity(input.len());
+    let mut in_phrase = false;
+    let mut in_comment = false;
+    for ch in input.chars() {
+        match ch {
+            '\n' => in_comment = false,
+            '"' if !in_comment => in_phrase = !in_phrase,
+            '#' if !in_phrase => in_comment = true,
+            _ => {}
+        }
+        if in_comment {
+            output.extend(std::iter::repeat_n(' ', ch.len_utf8()));
+        } else {
+            output.push(ch);
+        }
+    }
+    Cow::Owned(output)
+}
+
+/// User input normalized into a single expression tree.
+///
+/// Queries hash and order structurally, so they can key a result cache or be
+/// sorted; the order follows the AST, not the query text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Query {
     pub expr: Expr,
 }
@@ -46,6 +253,104 @@ impl Query {
     pub fn is_empty(&self) -> bool {
         matches!(self.expr, Expr::Empty)
     }
+
+    /// See [`Expr::filters`].
+    pub fn filters(&self) -> Vec<&Filter> {
+        self.expr.filters()
+    }
+
+    /// See [`Expr::words`].
+    pub fn words(&self) -> Vec<&str> {
+        self.expr.words()
+    }
+
+    /// See [`Expr::single_term`].
+    pub fn single_term(&self) -> Option<&Term> {
+        self.expr.single_term()
+    }
+}
+
+/// Renders `expr` with every boolean node spelled out and parenthesized, so it
+/// reads unambiguously regardless of precedence, e.g. to show users how a query
+/// was understood. The output is meant for reading, not for parsing again.
+///
+/// ```
+/// use cardinal_syntax::{explain, parse_query};
+/// let expr = parse_query("foo bar|baz").unwrap().expr;
+/// assert_eq!(explain(&expr), "(foo AND (bar OR baz))");
+/// ```
+pub fn explain(expr: &Expr) -> String {
+    let mut out = String::new();
+    explain_into(expr, &mut out);
+    out
+}
+
+fn explain_into(expr: &Expr, out: &mut String) {
+    match expr {
+        Expr::Empty => out.push_str("EMPTY"),
+        Expr::Term(term) => explain_term(term, out),
+        Expr::Not(inner) => {
+            out.push_str("NOT ");
+            explain_into(inner, out);
+        }
+        Expr::And(parts) => explain_chain(parts, " AND ", out),
+        Expr::Or(parts) => explain_chain(parts, " OR ", out),
+        Expr::Near {
+            left,
+            right,
+            distance,
+        } => {
+            out.push('(');
+            explain_into(left, out);
+            out.push_str(&format!(" NEAR/{distance} "));
+            explain_into(right, out);
+            out.push(')');
+        }
+    }
+}
+
+fn explain_chain(parts: &[Expr], operator: &str, out: &mut String) {
+    out.push('(');
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            out.push_str(operator);
+        }
+        explain_into(part, out);
+    }
+    out.push(')');
+}
+
+fn explain_term(term: &Term, out: &mut String) {
+    match term {
+        Term::Word(word) => out.push_str(word),
+        Term::Phrase(phrase) => {
+            out.push('"');
+            out.push_str(phrase);
+            out.push('"');
+        }
+        Term::Regex(pattern) => {
+            out.push_str("regex:");
+            out.push_str(pattern);
+        }
+        Term::Filter(filter) => {
+            if filter.negated {
+                out.push_str("NOT ");
+            }
+            out.push_str(&filter.kind.name());
+            out.push(':');
+            if let Some(argument) = &filter.argument {
+                if matches!(argument.kind, ArgumentKind::Phrase)
+                    || argument.raw.contains(char::is_whitespace)
+                {
+                    out.push('"');
+                    out.push_str(&argument.raw);
+                    out.push('"');
+                } else {
+                    out.push_str(&argument.raw);
+                }
+            }
+        }
+    }
 }
 
 /// Applies deterministic rewrites that make downstream evaluation cheaper.
@@ -55,9 +360,13 @@ impl Query {
 /// - Removes `Expr::Empty` operands from conjunctions (returning `Expr::Empty`
 ///   or the lone operand when appropriate).
 /// - Moves all filters to the tail of AND chains so cheaper textual terms run
-///   first.
+///   first. Bare modifier toggles (`case:`) stay where they are and nothing
+///   moves across them, as they only cover the terms after them; groups
+///   holding one aren't flattened into the enclosing chain.
 /// - Collapses any OR chain containing `Expr::Empty` into a single
 ///   `Expr::Empty`, matching Cardinal's "empty means whole universe" semantics.
+/// - Rewrites ranges with a single endpoint into comparisons (`size:..10mb`
+///   becomes `size:<=10mb`, `size:1mb..` becomes `size:>=1mb`).
 ///
 /// The function never mutates the input query in place; a new tree is returned
 /// so upstream caches can keep the parsed form if needed.
@@ -71,10 +380,95 @@ fn optimize_expr(expr: Expr) -> Expr {
         Expr::And(parts) => optimize_and(parts),
         Expr::Or(parts) => optimize_or(parts),
         Expr::Not(inner) => Expr::Not(Box::new(optimize_expr(*inner))),
+        Expr::Near {
+            left,
+            right,
+            distance,
+        } => Expr::Near {
+            left: Box::new(optimize_expr(*left)),
+            right: Box::new(optimize_expr(*right)),
+            distance,
+        },
+        Expr::Term(Term::Filter(filter)) => Expr::Term(Term::Filter(normalize_open_range(filter))),
+        Expr::Term(_) | Expr::Empty => expr,
+    }
+}
+
+/// Folds `Expr::Not` directly wrapping a filter into [`Filter::negated`], for
+/// consumers that plan per filter. Negated words and boolean subtrees such as
+/// `!(a b)` keep their `Expr::Not`. Opt-in, like [`optimize_query`].
+///
+/// ```
+/// use cardinal_syntax::{fold_negated_filters, parse_query, Expr, Term};
+/// let folded = fold_negated_filters(parse_query("!ext:tmp").unwrap());
+/// let Expr::Term(Term::Filter(filter)) = folded.expr else { panic!() };
+/// assert!(filter.negated);
+/// ```
+pub fn fold_negated_filters(mut query: Query) -> Query {
+    query.expr = fold_negated_expr(query.expr);
+    query
+}
+
+fn fold_negated_expr(expr: Expr) -> Expr {
+    match expr {
+        Expr::Not(inner) => match fold_negated_expr(*inner) {
+            Expr::Term(Term::Filter(mut filter)) => {
+                filter.negated = !filter.negated;
+                Expr::Term(Term::Filter(filter))
+            }
+            inner => Expr::Not(Box::new(inner)),
+        },
+        Expr::And(parts) => Expr::And(parts.into_iter().map(fold_negated_expr).collect()),
+        Expr::Or(parts) => Expr::Or(parts.into_iter().map(fold_negated_expr).collect()),
+        Expr::Near {
+            left,
+            right,
+            distance,
+        } => Expr::Near {
+            left: Box::new(fold_negated_expr(*left)),
+            right: Box::new(fold_negated_expr(*right)),
+            distance,
+        },
         Expr::Term(_) | Expr::Empty => expr,
     }
 }
 
+/// Turns a range missing one endpoint into the equivalent comparison so evaluators
+/// don't have to special-case open intervals. `raw` keeps the text as typed.
+fn normalize_open_range(mut filter: Filter) -> Filter {
+    let Some(argument) = filter.argument.as_mut() else {
+        return filter;
+    };
+    let ArgumentKind::Range(range) = &mut argument.kind else {
+        return filter;
+    };
+    let comparison = match (range.start.take(), range.end.take()) {
+        (None, Some(end)) => ComparisonValue {
+            op: if range.inclusive_end {
+                ComparisonOp::Lte
+            } else {
+                ComparisonOp::Lt
+            },
+            value: end,
+        },
+        (Some(start), None) => ComparisonValue {
+            op: if range.inclusive_start {
+                ComparisonOp::Gte
+            } else {
+                ComparisonOp::Gt
+            },
+            value: start,
+        },
+        (start, end) => {
+            range.start = start;
+            range.end = end;
+            return filter;
+        }
+    };
+    argument.kind = ArgumentKind::Comparison(comparison);
+    filter
+}
+
 /// Normalizes AND expressions by eliding `Expr::Empty`, flattening single-item
 /// conjunctions, and reordering filters to the end of the chain.
 fn optimize_and(parts: Vec<Expr>) -> Expr {
@@ -82,7 +476,7 @@ fn optimize_and(parts: Vec<Expr>) -> Expr {
     for expr in parts.into_iter().map(optimize_expr) {
         match expr {
             Expr::Empty => {}
-            Expr::And(nested) => flattened.extend(nested),
+            Expr::And(nested) if !nested.iter().any(is_modifier_toggle) => flattened.extend(nested),
             other => flattened.push(other),
         }
     }
@@ -121,41 +515,48 @@ fn optimize_or(parts: Vec<Expr>) -> Expr {
     }
 }
 
-/// Reorders `filter:` terms to the end of `parts`.
+/// Reorders `filter:` terms to the end of `parts`, with `content:` filters after
+/// the others so file contents are only read for nodes every cheaper term kept.
+/// Duplicate filters (`dupe:`, `sizedupe:`, ...) go last as they compare the
+/// nodes every other term matched. Each run between bare modifier toggles is
+/// reordered on its own.
 ///
 /// Returns `true` when any movement was performed so future optimizations could
 /// skip redundant work.
-fn move_filters_to_tail(parts: &mut Vec<Expr>) -> bool {
-    if parts.len() <= 1 {
-        return false;
-    }
-
-    let Some(first) = parts.iter().position(is_filter_term) else {
-        return false;
-    };
-
-    if parts[first..].iter().all(is_filter_term) {
-        return false;
-    }
-
-    let mut reordered = Vec::with_capacity(parts.len());
-    let mut metadata = Vec::new();
-
-    for expr in parts.drain(..) {
-        if is_filter_term(&expr) {
-            metadata.push(expr);
-        } else {
-            reordered.push(expr);
+fn move_filters_to_tail(parts: &mut [Expr]) -> bool {
+    let mut moved = false;
+    for run in parts.split_mut(is_modifier_toggle) {
+        if !run.is_sorted_by_key(tail_rank) {
+            // Stable, so terms of the same rank keep their relative order.
+            run.sort_by_key(tail_rank);
+            moved = true;
         }
     }
+    moved
+}
 
-    parts.extend(reordered);
-    parts.extend(metadata);
-    true
+/// A bare, non-negated modifier such as `case:` or `wfn:`, see [`ModifierScope::Toggle`].
+fn is_modifier_toggle(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Term(Term::Filter(filter))
+            if !filter.negated && filter.modifier_scope() == Some(ModifierScope::Toggle)
+    )
 }
 
-fn is_filter_term(expr: &Expr) -> bool {
-    matches!(expr, Expr::Term(Term::Filter(_)))
+fn tail_rank(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Term(Term::Filter(filter)) => match filter.kind {
+            FilterKind::Duplicate
+            | FilterKind::SizeDuplicate
+            | FilterKind::NamePartDuplicate
+            | FilterKind::AttributeDuplicate
+            | FilterKind::DateModifiedDuplicate => 3,
+            FilterKind::Content => 2,
+            _ => 1,
+        },
+        _ => 0,
+    }
 }
 
 /// Logical structure for Everything queries.
@@ -164,7 +565,7 @@ fn is_filter_term(expr: &Expr) -> bool {
 /// consumers can iterate terms without rebalancing. `Empty` exists so parsing
 /// helpers can return a sentinel when a group contains whitespace or is
 /// mid-construction.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Expr {
     /// Returned when a query (or sub query) only contains whitespace.
     ///
@@ -201,18 +602,112 @@ pub enum Expr {
     And(Vec<Expr>),
     /// Disjunction triggered by the `|` operator.
     ///
+    /// A missing operand (`foo||bar`, a leading or trailing `|`, or `|` next
+    /// to a group's closing delimiter as in `<foo|>`) becomes an [`Expr::Empty`]
+    /// in its position, the same inside and outside groups.
+    /// [`optimize_query`] then reduces the whole `OR` to `Empty`.
+    ///
     /// ```
     /// use cardinal_syntax::{parse_query, Expr};
     /// let Expr::Or(parts) = parse_query("foo|bar").unwrap().expr else { panic!() };
     /// assert_eq!(parts.len(), 2);
     /// ```
     Or(Vec<Expr>),
+    /// Both sides match and appear within `distance` words of each other, written
+    /// `left NEAR/<distance> right`. A bare `NEAR` uses [`DEFAULT_NEAR_DISTANCE`]. Binds
+    /// like `AND`, so `a|b NEAR c` pairs `a|b` with `c`.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr};
+    /// let Expr::Near { distance, .. } = parse_query("error NEAR/3 timeout").unwrap().expr else {
+    ///     panic!()
+    /// };
+    /// assert_eq!(distance, 3);
+    /// ```
+    Near {
+        left: Box<Expr>,
+        right: Box<Expr>,
+        distance: u32,
+    },
 }
 
+impl Expr {
+    /// Every filter in the tree in query order, whether under `AND`, `OR`,
+    /// `NOT` or `NEAR`.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, FilterKind};
+    /// let query = parse_query("report ext:pdf|!size:>1mb").unwrap();
+    /// let kinds: Vec<_> = query.filters().iter().map(|filter| &filter.kind).collect();
+    /// assert_eq!(kinds, [&FilterKind::Ext, &FilterKind::Size]);
+    /// ```
+    pub fn filters(&self) -> Vec<&Filter> {
+        let mut filters = Vec::new();
+        self.visit_terms(&mut |term| {
+            if let Term::Filter(filter) = term {
+                filters.push(filter);
+            }
+        });
+        filters
+    }
+
+    /// Every [`Term::Word`] in the tree in query order, like [`Self::filters`].
+    /// Phrases, regexes and filter arguments are not included.
+    ///
+    /// ```
+    /// use cardinal_syntax::parse_query;
+    /// let query = parse_query("report <draft|!final> \"q1 notes\" ext:pdf").unwrap();
+    /// assert_eq!(query.words(), ["report", "draft", "final"]);
+    /// ```
+    pub fn words(&self) -> Vec<&str> {
+        let mut words = Vec::new();
+        self.visit_terms(&mut |term| {
+            if let Term::Word(word) = term {
+                words.push(word.as_str());
+            }
+        });
+        words
+    }
+
+    /// The term when the whole expression is a single one, without any operator.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, Term};
+    /// assert!(matches!(parse_query("report").unwrap().single_term(), Some(Term::Word(_))));
+    /// assert!(parse_query("!report").unwrap().single_term().is_none());
+    /// ```
+    pub fn single_term(&self) -> Option<&Term> {
+        match self {
+            Expr::Term(term) => Some(term),
+            _ => None,
+        }
+    }
+
+    fn visit_terms<'a>(&'a self, visit: &mut impl FnMut(&'a Term)) {
+        match self {
+            Expr::Empty => {}
+            Expr::Term(term) => visit(term),
+            Expr::Not(inner) => inner.visit_terms(visit),
+            Expr::And(parts) | Expr::Or(parts) => {
+                for part in parts {
+                    part.visit_terms(visit);
+                }
+            }
+            Expr::Near { left, right, .. } => {
+                left.visit_terms(visit);
+                right.visit_terms(visit);
+            }
+        }
+    }
+}
+
+/// Word distance of a bare `NEAR` operator.
+pub const DEFAULT_NEAR_DISTANCE: u32 = 3;
+
 /// A leaf expression that Everything understands without further boolean
 /// structure. Filters are kept separate from raw words so higher layers can
 /// translate them into structured lookups or validation.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Term {
     /// A bare word or wildcard token (e.g., `report`, `*.mp3`).
     ///
@@ -249,16 +744,105 @@ pub enum Term {
     Regex(String),
 }
 
+impl Term {
+    /// Whether this is a word containing `*` or `?`, i.e. a glob rather than a
+    /// plain substring. Phrases keep those characters literal.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr};
+    /// let Expr::Term(term) = parse_query("*.mp3").unwrap().expr else { panic!() };
+    /// assert!(term.is_wildcard());
+    /// ```
+    pub fn is_wildcard(&self) -> bool {
+        match self {
+            Term::Word(word) => word.contains(['*', '?']),
+            Term::Phrase(_) | Term::Filter(_) | Term::Regex(_) => false,
+        }
+    }
+
+    /// The `*`s of a wildcard word and whether each may match across `/`.
+    ///
+    /// By convention a lone `*` (or `?`) stays within one path component, so
+    /// the middle `*` of `src/*/test.rs` matches a single directory name. A run
+    /// of two or more stars, as in `src/**/test.rs`, may span any number of
+    /// components and is reported once.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, WildcardStar};
+    /// let Expr::Term(term) = parse_query("src/*/test.rs").unwrap().expr else { panic!() };
+    /// assert_eq!(term.wildcard_stars(), [WildcardStar { offset: 4, crosses_separators: false }]);
+    /// ```
+    pub fn wildcard_stars(&self) -> Vec<WildcardStar> {
+        let Term::Word(word) = self else {
+            return Vec::new();
+        };
+        let mut stars = Vec::new();
+        let mut rest = word.as_str();
+        while let Some(offset) = rest.find('*') {
+            let run = rest[offset..].bytes().take_while(|&b| b == b'*').count();
+            stars.push(WildcardStar {
+                offset: word.len() - rest.len() + offset,
+                crosses_separators: run > 1,
+            });
+            rest = &rest[offset + run..];
+        }
+        stars
+    }
+}
+
+/// A `*` wildcard inside a word, see [`Term::wildcard_stars`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WildcardStar {
+    /// Byte offset of the star, or of the first star of a `**` run, in the word.
+    pub offset: usize,
+    /// Whether the star may match `/`, i.e. it was written as `**`.
+    pub crosses_separators: bool,
+}
+
 /// `name:argument` style filters Everything exposes (e.g. `size:>1gb`).
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Filter {
     pub kind: FilterKind,
     pub argument: Option<FilterArgument>,
+    /// Whether the filter is negated (`!ext:tmp`). The parser wraps negation in
+    /// [`Expr::Not`] and leaves this `false`; only [`fold_negated_filters`] sets it.
+    pub negated: bool,
+}
+
+impl Filter {
+    /// How far a search modifier reaches: a bare `ww:` toggles the behavior,
+    /// while `ww:report` only applies to its argument. `None` for filters that
+    /// aren't modifiers (see [`FilterKind::is_modifier`]).
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, ModifierScope, Term};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("ww:report").unwrap().expr else { panic!() };
+    /// assert_eq!(filter.modifier_scope(), Some(ModifierScope::Term));
+    /// ```
+    pub fn modifier_scope(&self) -> Option<ModifierScope> {
+        if !self.kind.is_modifier() {
+            return None;
+        }
+        Some(match self.argument {
+            Some(_) => ModifierScope::Term,
+            None => ModifierScope::Toggle,
+        })
+    }
+}
+
+/// See [`Filter::modifier_scope`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifierScope {
+    /// Written without an argument (`case:`), switches the behavior on for the
+    /// terms after it in its AND chain.
+    Toggle,
+    /// Applies to the argument only (`case:ABC`).
+    Term,
 }
 
 /// Strongly-typed view over Everything's built-in filters. Custom macros fall
 /// back to [`FilterKind::Custom`].
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum FilterKind {
     /// Only match files (`file:`).
     /// ```
@@ -372,6 +956,13 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::NoSubfolders));
     /// ```
     NoSubfolders,
+    /// Match the argument against full paths instead of names (`path:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("path:Projects/cardinal").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::Path));
+    /// ```
+    Path,
     /// Require a folder containing matching children (`child:`).
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
@@ -526,6 +1117,59 @@ pub enum FilterKind {
     /// assert!(matches!(filter.kind, FilterKind::NoWholeFilename));
     /// ```
     NoWholeFilename,
+    /// Case-insensitive search toggle (`nocase:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("nocase:abc").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::NoCase));
+    /// ```
+    NoCase,
+    /// Match whole words only (`wholeword:`, `ww:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("ww:report").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::WholeWord));
+    /// ```
+    WholeWord,
+    /// Undo `wholeword:` (`nowholeword:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("nowholeword:report").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::NoWholeWord));
+    /// ```
+    NoWholeWord,
+    /// Match the whole file name (`wholefilename:`, `wfn:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("wfn:report.txt").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::WholeFilename));
+    /// ```
+    WholeFilename,
+    /// Diacritics-sensitive search (`diacritics:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("diacritics:café").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::Diacritics));
+    /// ```
+    Diacritics,
+    /// Diacritics-insensitive search (`nodiacritics:`).
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("nodiacritics:cafe").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::NoDiacritics));
+    /// ```
+    NoDiacritics,
+    /// Windows drive root such as `D:` or `D:\Downloads\`, stored upper-cased.
+    /// Only single letters directly followed by the end of input, whitespace,
+    /// `|`, a group delimiter, or a path separator are drives; anything after
+    /// the colon becomes the argument.
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query(r"d:\Downloads\").unwrap().expr else { panic!() };
+    /// assert!(matches!(filter.kind, FilterKind::Drive('D')));
+    /// assert_eq!(filter.argument.unwrap().raw, r"\Downloads\");
+    /// ```
+    Drive(char),
     /// User-defined macro or unrecognized filter name.
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, FilterKind};
@@ -536,6 +1180,133 @@ pub enum FilterKind {
 }
 
 impl FilterKind {
+    /// Search modifiers change how text is matched (case, whole words, whole
+    /// file names, diacritics) instead of selecting items themselves.
+    pub fn is_modifier(&self) -> bool {
+        matches!(
+            self,
+            FilterKind::CaseSensitive
+                | FilterKind::NoCase
+                | FilterKind::WholeWord
+                | FilterKind::NoWholeWord
+                | FilterKind::WholeFilename
+                | FilterKind::NoWholeFilename
+                | FilterKind::Diacritics
+                | FilterKind::NoDiacritics
+        )
+    }
+
+    /// Canonical spelling of the filter name, without the colon. Aliases map
+    /// to one name (`datemodified:` is `dm`) and drives to their letter.
+    ///
+    /// ```
+    /// use cardinal_syntax::FilterKind;
+    /// assert_eq!(FilterKind::WholeWord.name(), "ww");
+    /// assert_eq!(FilterKind::Drive('D').name(), "D");
+    /// ```
+    pub fn name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(match self {
+            FilterKind::File => "file",
+            FilterKind::Folder => "folder",
+            FilterKind::Ext => "ext",
+            FilterKind::Type => "type",
+            FilterKind::Audio => "audio",
+            FilterKind::Video => "video",
+            FilterKind::Doc => "doc",
+            FilterKind::Exe => "exe",
+            FilterKind::Size => "size",
+            FilterKind::DateModified => "dm",
+            FilterKind::DateCreated => "dc",
+            FilterKind::DateAccessed => "da",
+            FilterKind::DateRun => "dr",
+            FilterKind::Parent => "parent",
+            FilterKind::InFolder => "infolder",
+            FilterKind::NoSubfolders => "nosubfolders",
+            FilterKind::Path => "path",
+            FilterKind::Child => "child",
+            FilterKind::Attribute => "attrib",
+            FilterKind::AttributeDuplicate => "attribdupe",
+            FilterKind::DateModifiedDuplicate => "dmdupe",
+            FilterKind::Duplicate => "dupe",
+            FilterKind::NamePartDuplicate => "namepartdupe",
+            FilterKind::SizeDuplicate => "sizedupe",
+            FilterKind::Artist => "artist",
+            FilterKind::Album => "album",
+            FilterKind::Title => "title",
+            FilterKind::Genre => "genre",
+            FilterKind::Year => "year",
+            FilterKind::Track => "track",
+            FilterKind::Comment => "comment",
+            FilterKind::Width => "width",
+            FilterKind::Height => "height",
+            FilterKind::Dimensions => "dimensions",
+            FilterKind::Orientation => "orientation",
+            FilterKind::BitDepth => "bitdepth",
+            FilterKind::CaseSensitive => "case",
+            FilterKind::Content => "content",
+            FilterKind::NoWholeFilename => "nowholefilename",
+            FilterKind::NoCase => "nocase",
+            FilterKind::WholeWord => "ww",
+            FilterKind::NoWholeWord => "nowholeword",
+            FilterKind::WholeFilename => "wfn",
+            FilterKind::Diacritics => "diacritics",
+            FilterKind::NoDiacritics => "nodiacritics",
+            FilterKind::Drive(letter) => return Cow::Owned(letter.to_string()),
+            FilterKind::Custom(name) => name,
+        })
+    }
+
+    /// Filters taking a path, whose unquoted argument keeps embedded colons
+    /// (`infolder:/a:b`, `parent:C:\Windows`) instead of stopping at what
+    /// looks like the next `name:` filter.
+    fn takes_path(&self) -> bool {
+        matches!(
+            self,
+            FilterKind::Parent | FilterKind::InFolder | FilterKind::NoSubfolders | FilterKind::Path
+        )
+    }
+
+    /// Filters whose argument is a set of plain names (extensions, type
+    /// categories), where `,` is safe to treat as a list separator.
+    fn takes_name_list(&self) -> bool {
+        matches!(self, FilterKind::Ext | FilterKind::Type)
+    }
+
+    /// Filters comparing numbers, whose values may use digit group separators
+    /// (see [`strip_digit_separators`]).
+    fn takes_number(&self) -> bool {
+        matches!(
+            self,
+            FilterKind::Size
+                | FilterKind::Width
+                | FilterKind::Height
+                | FilterKind::Dimensions
+                | FilterKind::BitDepth
+                | FilterKind::Year
+                | FilterKind::Track
+        )
+    }
+
+    /// Filters comparing dates.
+    fn takes_date(&self) -> bool {
+        matches!(
+            self,
+            FilterKind::DateCreated
+                | FilterKind::DateModified
+                | FilterKind::DateAccessed
+                | FilterKind::DateRun
+        )
+    }
+
+    /// Numeric filters that only make sense with whole numbers; any other
+    /// unquoted value is a [`ParseError`].
+    fn takes_integer(&self) -> bool {
+        matches!(
+            self,
+            FilterKind::Width | FilterKind::Height | FilterKind::BitDepth
+        )
+    }
+
     fn from_name(name: &str) -> Self {
         let lower = name.to_ascii_lowercase();
         match lower.as_str() {
@@ -555,6 +1326,7 @@ impl FilterKind {
             "parent" => FilterKind::Parent,
             "infolder" => FilterKind::InFolder,
             "nosubfolders" => FilterKind::NoSubfolders,
+            "path" => FilterKind::Path,
             "child" => FilterKind::Child,
             "attrib" => FilterKind::Attribute,
             "attribdupe" => FilterKind::AttributeDuplicate,
@@ -577,6 +1349,12 @@ impl FilterKind {
             "case" => FilterKind::CaseSensitive,
             "content" => FilterKind::Content,
             "nowholefilename" => FilterKind::NoWholeFilename,
+            "nocase" => FilterKind::NoCase,
+            "wholeword" | "ww" => FilterKind::WholeWord,
+            "nowholeword" => FilterKind::NoWholeWord,
+            "wholefilename" | "wfn" => FilterKind::WholeFilename,
+            "diacritics" => FilterKind::Diacritics,
+            "nodiacritics" => FilterKind::NoDiacritics,
             _ => FilterKind::Custom(name.to_string()),
         }
     }
@@ -585,14 +1363,14 @@ impl FilterKind {
 /// Captures both the raw string and the heuristically detected shape so a
 /// consumer can distinguish between e.g. a list (`ext:jpg;png`) and a comparison
 /// (`size:>1GB`).
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct FilterArgument {
     pub raw: String,
     pub kind: ArgumentKind,
 }
 
 /// Common syntactic patterns supported by Everything filters.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum ArgumentKind {
     /// Plain argument with no additional structure.
     ///
@@ -610,7 +1388,8 @@ pub enum ArgumentKind {
     /// assert!(matches!(filter.argument.unwrap().kind, ArgumentKind::Phrase));
     /// ```
     Phrase,
-    /// Semicolon-delimited list such as `ext:jpg;png`.
+    /// Delimited list such as `ext:jpg;png` or `ext:jpg,png`, see
+    /// [`ParseOptions::list_separators`].
     ///
     /// ```
     /// use cardinal_syntax::{parse_query, Expr, Term, ArgumentKind};
@@ -634,18 +1413,259 @@ pub enum ArgumentKind {
     /// assert!(matches!(filter.argument.unwrap().kind, ArgumentKind::Comparison(_)));
     /// ```
     Comparison(ComparisonValue),
+    /// `WIDTHxHEIGHT` argument of `dimensions:`, optionally prefixed by a
+    /// comparison operator. Malformed values stay [`ArgumentKind::Bare`].
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, ArgumentKind, ComparisonOp};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("dimensions:>=1920x1080").unwrap().expr else { panic!() };
+    /// assert_eq!(
+    ///     filter.argument.unwrap().kind,
+    ///     ArgumentKind::Dimensions { op: Some(ComparisonOp::Gte), width: 1920, height: 1080 }
+    /// );
+    /// ```
+    Dimensions {
+        op: Option<ComparisonOp>,
+        width: u32,
+        height: u32,
+    },
+    /// Letters of `attrib:`, one flag per letter in input order.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, ArgumentKind, AttributeFlag};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("attrib:rh").unwrap().expr else { panic!() };
+    /// assert_eq!(
+    ///     filter.argument.unwrap().kind,
+    ///     ArgumentKind::Attributes(vec![AttributeFlag::ReadOnly, AttributeFlag::Hidden])
+    /// );
+    /// ```
+    Attributes(Vec<AttributeFlag>),
+    /// Date keyword or relative span of a date filter, see [`DateSpec`]. Exact
+    /// dates stay [`ArgumentKind::Bare`].
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, ArgumentKind, DateSpec, DateUnit};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("dm:past7days").unwrap().expr else { panic!() };
+    /// assert_eq!(
+    ///     filter.argument.unwrap().kind,
+    ///     ArgumentKind::Date(DateSpec::Relative { n: 7, unit: DateUnit::Days })
+    /// );
+    /// ```
+    Date(DateSpec),
+    /// Known value of `orientation:`, see [`Orientation`]. Other values stay
+    /// [`ArgumentKind::Bare`].
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, ArgumentKind, Orientation};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("orientation:Vertical").unwrap().expr else { panic!() };
+    /// assert_eq!(filter.argument.unwrap().kind, ArgumentKind::Orientation(Orientation::Vertical));
+    /// ```
+    Orientation(Orientation),
+}
+
+/// Single-letter file attribute accepted by `attrib:` (case-insensitive).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AttributeFlag {
+    /// `R`
+    ReadOnly,
+    /// `H`
+    Hidden,
+    /// `S`
+    System,
+    /// `D`
+    Directory,
+    /// `A`
+    Archive,
+    /// `N`
+    Normal,
+    /// `T`
+    Temporary,
+    /// `C`
+    Compressed,
+    /// `O`
+    Offline,
+    /// `I`
+    NotIndexed,
+    /// `E`
+    Encrypted,
+    /// Any other letter, upper-cased.
+    Other(char),
+}
+
+impl AttributeFlag {
+    pub fn from_letter(letter: char) -> Self {
+        match letter.to_ascii_uppercase() {
+            'R' => AttributeFlag::ReadOnly,
+            'H' => AttributeFlag::Hidden,
+            'S' => AttributeFlag::System,
+            'D' => AttributeFlag::Directory,
+            'A' => AttributeFlag::Archive,
+            'N' => AttributeFlag::Normal,
+            'T' => AttributeFlag::Temporary,
+            'C' => AttributeFlag::Compressed,
+            'O' => AttributeFlag::Offline,
+            'I' => AttributeFlag::NotIndexed,
+            'E' => AttributeFlag::Encrypted,
+            other => AttributeFlag::Other(other),
+        }
+    }
+}
+
+/// Image orientation accepted by `orientation:` (case-insensitive).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Orientation {
+    /// `horizontal`, wider than tall.
+    Horizontal,
+    /// `vertical`, taller than wide.
+    Vertical,
+    /// `square`, as wide as tall.
+    Square,
+}
+
+impl Orientation {
+    /// Parses one of the orientation names, `None` for anything else.
+    pub fn parse(value: &str) -> Option<Self> {
+        Some(match value.to_ascii_lowercase().as_str() {
+            "horizontal" => Orientation::Horizontal,
+            "vertical" => Orientation::Vertical,
+            "square" => Orientation::Square,
+            _ => return None,
+        })
+    }
+}
+
+/// Date filter value relative to today (case-insensitive).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DateSpec {
+    /// `today`, `lastweek`, `pastmonth`, ...
+    Keyword(DateKeyword),
+    /// `past<N><unit>` or `last<N><unit>`, e.g. `past7days`: the last `n`
+    /// units up to and including today.
+    Relative { n: u32, unit: DateUnit },
+}
+
+impl DateSpec {
+    /// Parses a keyword or a relative span, `None` for anything else.
+    ///
+    /// ```
+    /// use cardinal_syntax::{DateKeyword, DateSpec, DateUnit};
+    /// assert_eq!(DateSpec::parse("Today"), Some(DateSpec::Keyword(DateKeyword::Today)));
+    /// assert_eq!(
+    ///     DateSpec::parse("last3months"),
+    ///     Some(DateSpec::Relative { n: 3, unit: DateUnit::Months })
+    /// );
+    /// assert_eq!(DateSpec::parse("2024/1/1"), None);
+    /// ```
+    pub fn parse(value: &str) -> Option<Self> {
+        let lower = value.trim().to_ascii_lowercase();
+        if let Some(keyword) = DateKeyword::from_name(&lower) {
+            return Some(DateSpec::Keyword(keyword));
+        }
+        let rest = lower
+            .strip_prefix("past")
+            .or_else(|| lower.strip_prefix("last"))?;
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        let n = rest[..digits].parse().ok()?;
+        let unit = DateUnit::from_name(&rest[digits..])?;
+        Some(DateSpec::Relative { n, unit })
+    }
+}
+
+/// Named periods accepted by date filters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DateKeyword {
+    Today,
+    Yesterday,
+    ThisWeek,
+    LastWeek,
+    ThisMonth,
+    LastMonth,
+    ThisYear,
+    LastYear,
+    /// The last 7 days.
+    PastWeek,
+    /// The last 30 days.
+    PastMonth,
+    /// The last 365 days.
+    PastYear,
+}
+
+impl DateKeyword {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "today" => DateKeyword::Today,
+            "yesterday" => DateKeyword::Yesterday,
+            "thisweek" => DateKeyword::ThisWeek,
+            "lastweek" => DateKeyword::LastWeek,
+            "thismonth" => DateKeyword::ThisMonth,
+            "lastmonth" => DateKeyword::LastMonth,
+            "thisyear" => DateKeyword::ThisYear,
+            "lastyear" => DateKeyword::LastYear,
+            "pastweek" => DateKeyword::PastWeek,
+            "pastmonth" => DateKeyword::PastMonth,
+            "pastyear" => DateKeyword::PastYear,
+            _ => return None,
+        })
+    }
+}
+
+/// Unit of a [`DateSpec::Relative`] span, singular or plural (`1day`, `7days`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DateUnit {
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
+
+impl DateUnit {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "day" | "days" => DateUnit::Days,
+            "week" | "weeks" => DateUnit::Weeks,
+            "month" | "months" => DateUnit::Months,
+            "year" | "years" => DateUnit::Years,
+            _ => return None,
+        })
+    }
 }
 
 /// Represents `start..end` or `start-end` ranges. Empty endpoints are allowed
 /// for open ranges (Everything treats `..10mb` as `<=10mb`).
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct RangeValue {
     pub start: Option<String>,
     pub end: Option<String>,
     pub separator: RangeSeparator,
+    /// Whether `start` itself is part of the range. The parser always sets this,
+    /// Everything ranges include both endpoints.
+    pub inclusive_start: bool,
+    /// Whether `end` itself is part of the range, `true` when parsed.
+    pub inclusive_end: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl RangeValue {
+    /// Both endpoints as integers, `None` when a present endpoint is not one.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, ArgumentKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("width:1000..2000").unwrap().expr else { panic!() };
+    /// let ArgumentKind::Range(range) = filter.argument.unwrap().kind else { panic!() };
+    /// assert_eq!(range.as_u64(), Some((Some(1000), Some(2000))));
+    /// ```
+    pub fn as_u64(&self) -> Option<(Option<u64>, Option<u64>)> {
+        let endpoint = |value: &Option<String>| match value {
+            Some(value) => parse_integer(value).map(Some),
+            None => Some(None),
+        };
+        Some((endpoint(&self.start)?, endpoint(&self.end)?))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum RangeSeparator {
     /// Range expressed with `..` such as `size:1..10`.
     ///
@@ -668,13 +1688,27 @@ pub enum RangeSeparator {
 }
 
 /// `size:>1GB` style comparisons.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ComparisonValue {
     pub op: ComparisonOp,
     pub value: String,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl ComparisonValue {
+    /// The value as an integer, `None` for units (`1GB`), dates and the like.
+    ///
+    /// ```
+    /// use cardinal_syntax::{parse_query, Expr, Term, ArgumentKind};
+    /// let Expr::Term(Term::Filter(filter)) = parse_query("width:>4000").unwrap().expr else { panic!() };
+    /// let ArgumentKind::Comparison(value) = filter.argument.unwrap().kind else { panic!() };
+    /// assert_eq!(value.as_u64(), Some(4000));
+    /// ```
+    pub fn as_u64(&self) -> Option<u64> {
+        parse_integer(&self.value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum ComparisonOp {
     /// `< value` comparison.
     ///
@@ -754,14 +1788,19 @@ struct Parser<'a> {
     input: &'a str,
     pos: usize,
     group_stack: Vec<char>,
+    options: &'a ParseOptions,
+    /// Errors recovered from so far; `None` fails on the first error.
+    errors: Option<Vec<ParseError>>,
 }
 
 impl<'a> Parser<'a> {
-    fn new(input: &'a str) -> Self {
+    fn new(input: &'a str, options: &'a ParseOptions) -> Self {
         Self {
             input,
             pos: 0,
             group_stack: Vec::new(),
+            options,
+            errors: None,
         }
     }
 
@@ -774,10 +1813,45 @@ impl<'a> Parser<'a> {
         Ok(Query { expr })
     }
 
+    fn parse_recovering(mut self) -> (Query, Vec<ParseError>) {
+        self.errors = Some(Vec::new());
+        let expr = self.parse_and();
+        let mut errors = self.errors.take().unwrap_or_default();
+        let expr = expr.unwrap_or_else(|err| {
+            errors.push(err);
+            Expr::Empty
+        });
+        self.skip_ws();
+        if !self.eof() {
+            errors.push(self.error("unexpected trailing characters"));
+        }
+        (Query { expr }, errors)
+    }
+
+    fn error_count(&self) -> usize {
+        self.errors.as_ref().map_or(0, Vec::len)
+    }
+
+    // In recovering mode, records `err` and stands in `Expr::Empty` for the
+    // failed term. Always consumes input so the caller's loop makes progress.
+    fn recover(&mut self, err: ParseError, start: usize) -> Result<Expr, ParseError> {
+        let Some(errors) = self.errors.as_mut() else {
+            return Err(err);
+        };
+        errors.push(err);
+        if self.pos == start {
+            self.advance_char();
+        }
+        Ok(Expr::Empty)
+    }
+
     // AND has the lowest precedence and is implicit between whitespace-delimited
     // terms. We accumulate a Vec instead of nesting binary nodes so callers get
-    // a normalized structure regardless of how many terms are chained.
+    // a normalized structure regardless of how many terms are chained. With
+    // `ImplicitOp::Or`, whitespace instead closes the current conjunction and
+    // starts another alternative.
     fn parse_and(&mut self) -> Result<Expr, ParseError> {
+        let mut alternatives = Vec::new();
         let mut parts = Vec::new();
         let mut pending_keyword_and = false;
         loop {
@@ -789,31 +1863,59 @@ impl<'a> Parser<'a> {
                 pending_keyword_and = true;
                 continue;
             }
+            if let Some(distance) = self.consume_near() {
+                let left = parts.pop().unwrap_or(Expr::Empty);
+                let right = self.parse_near_operand()?;
+                parts.push(Expr::Near {
+                    left: Box::new(left),
+                    right: Box::new(right),
+                    distance,
+                });
+                pending_keyword_and = false;
+                continue;
+            }
             if self.eof() || self.is_at_group_close() {
                 if pending_keyword_and {
                     parts.push(Expr::Empty);
                 }
                 break;
             }
+            let errors_before = self.error_count();
             let expr = self.parse_or()?;
-            if matches!(expr, Expr::Empty) {
+            if matches!(expr, Expr::Empty) && self.error_count() == errors_before {
                 break;
             }
+            if self.options.implicit_operator == ImplicitOp::Or
+                && !pending_keyword_and
+                && !parts.is_empty()
+            {
+                alternatives.push(conjunction(std::mem::take(&mut parts)));
+            }
             parts.push(expr);
             if pending_keyword_and {
                 pending_keyword_and = false;
             }
         }
 
-        match parts.len() {
-            0 => Ok(Expr::Empty),
-            1 => Ok(parts.remove(0)),
-            _ => Ok(Expr::And(parts)),
+        let last = conjunction(parts);
+        if alternatives.is_empty() {
+            return Ok(last);
         }
+        alternatives.push(last);
+        Ok(Expr::Or(alternatives))
+    }
+
+    fn parse_near_operand(&mut self) -> Result<Expr, ParseError> {
+        self.skip_ws();
+        if self.eof() || self.is_at_group_close() {
+            return Ok(Expr::Empty);
+        }
+        self.parse_or()
     }
 
     // OR has higher precedence than AND but still associates left-to-right via
-    // the vector accumulator, mirroring how Everything evaluates `|`.
+    // the vector accumulator, mirroring how Everything evaluates `|`. Missing
+    // operands, including one cut short by a group closer, become `Empty`.
     fn parse_or(&mut self) -> Result<Expr, ParseError> {
         let mut parts = Vec::new();
         loop {
@@ -864,7 +1966,11 @@ impl<'a> Parser<'a> {
             break;
         }
 
-        let mut expr = self.parse_primary()?;
+        let start = self.pos;
+        let mut expr = match self.parse_primary() {
+            Ok(expr) => expr,
+            Err(err) => self.recover(err, start)?,
+        };
         if negations % 2 == 1 {
             expr = Expr::Not(Box::new(expr));
         }
@@ -899,7 +2005,14 @@ impl<'a> Parser<'a> {
         }
     }
 
+    // The only recursion in the parser, so bounding it bounds the stack.
     fn parse_group(&mut self, closing: char) -> Result<Expr, ParseError> {
+        if self.group_stack.len() >= self.options.max_depth {
+            return Err(self.error(format!(
+                "groups nested deeper than {}",
+                self.options.max_depth
+            )));
+        }
         self.advance_char(); // consume opening token
         self.group_stack.push(closing);
         let expr = self.parse_and()?;
@@ -927,6 +2040,13 @@ impl<'a> Parser<'a> {
                     return self.parse_filter_term(name.to_string());
                 }
             }
+            if ch == '=' && seen {
+                let name = &self.input[start..self.pos];
+                if self.options.is_equals_filter_name(name) {
+                    self.advance_char();
+                    return self.parse_filter_term(name.to_string());
+                }
+            }
 
             if is_term_breaker(ch) {
                 break;
@@ -951,9 +2071,27 @@ impl<'a> Parser<'a> {
             return Ok(Term::Regex(pattern));
         }
 
-        let kind = FilterKind::from_name(&name);
+        let kind = match drive_letter(&name) {
+            Some(letter) if self.peek_char().is_none_or(is_drive_terminator) => {
+                FilterKind::Drive(letter)
+            }
+            _ => FilterKind::from_name(&name),
+        };
+        if let FilterKind::Custom(name) = &kind {
+            if self.options.reject_unknown_filters && !self.options.is_known_custom_filter(name) {
+                return Err(ParseError {
+                    message: format!("unknown filter '{name}:'"),
+                    // `name:` has just been consumed.
+                    position: self.pos - name.len() - 1,
+                });
+            }
+        }
         let argument = self.parse_filter_argument(&kind)?;
-        Ok(Term::Filter(Filter { kind, argument }))
+        Ok(Term::Filter(Filter {
+            kind,
+            argument,
+            negated: false,
+        }))
     }
 
     fn parse_regex_pattern(&mut self) -> Result<String, ParseError> {
@@ -1027,7 +2165,7 @@ impl<'a> Parser<'a> {
             return Ok(None);
         }
 
-        if self.starts_with_filter_token() {
+        if !kind.takes_path() && self.starts_with_filter_token() {
             return Ok(None);
         }
 
@@ -1071,7 +2209,16 @@ impl<'a> Parser<'a> {
             return Ok(None);
         }
 
-        let argument_kind = classify_argument(kind, &buffer, false);
+        let argument_kind = classify_argument(kind, &buffer, false, self.options);
+        if kind.takes_integer()
+            && argument_kind == ArgumentKind::Bare
+            && parse_integer(&strip_digit_separators(&buffer)).is_none()
+        {
+            return Err(ParseError {
+                message: format!("{}: expects a whole number, found '{buffer}'", kind.name()),
+                position: start,
+            });
+        }
         Ok(Some(FilterArgument {
             raw: buffer,
             kind: argument_kind,
@@ -1127,6 +2274,29 @@ impl<'a> Parser<'a> {
         true
     }
 
+    // `NEAR` or `NEAR/<n>`. Unlike the other keywords it must be upper case, as
+    // "near" is a common word in file names.
+    fn consume_near(&mut self) -> Option<u32> {
+        let rest = self.remaining();
+        let after = rest.strip_prefix("NEAR")?;
+        let (distance, len) = match after.strip_prefix('/') {
+            Some(digits) => {
+                let count = digits.bytes().take_while(u8::is_ascii_digit).count();
+                let distance = digits[..count].parse().ok()?;
+                (distance, "NEAR/".len() + count)
+            }
+            None => (DEFAULT_NEAR_DISTANCE, "NEAR".len()),
+        };
+        if let Some(next) = rest[len..].chars().next() {
+            // Narrower than `is_keyword_boundary_char` so names like `NEAR.txt` stay words.
+            if !next.is_whitespace() && !matches!(next, '(' | ')' | '<' | '>') {
+                return None;
+            }
+        }
+        self.pos += len;
+        Some(distance)
+    }
+
     fn current_closer_is(&self, ch: char) -> bool {
         matches!(self.group_stack.last(), Some(&closer) if closer == ch)
     }
@@ -1182,11 +2352,22 @@ impl<'a> Parser<'a> {
                 }
                 return true;
             }
+            if ch == '=' {
+                return self.options.is_equals_filter_name(&rest[..idx]);
+            }
         }
         false
     }
 }
 
+fn conjunction(mut parts: Vec<Expr>) -> Expr {
+    match parts.len() {
+        0 => Expr::Empty,
+        1 => parts.remove(0),
+        _ => Expr::And(parts),
+    }
+}
+
 fn is_term_breaker(ch: char) -> bool {
     ch.is_whitespace() || matches!(ch, '|' | '<' | '>' | '(' | ')' | '!')
 }
@@ -1201,6 +2382,18 @@ fn is_keyword_boundary_char(ch: char) -> bool {
         || ch == '\0'
 }
 
+fn drive_letter(name: &str) -> Option<char> {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None) if letter.is_ascii_alphabetic() => Some(letter.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+fn is_drive_terminator(ch: char) -> bool {
+    ch.is_whitespace() || matches!(ch, '|' | '<' | '>' | '(' | ')' | '\\' | '/')
+}
+
 fn is_valid_filter_name(name: &str) -> bool {
     !name.is_empty()
         && name
@@ -1210,34 +2403,91 @@ fn is_valid_filter_name(name: &str) -> bool {
 
 /// Lightweight heuristic classification so downstream code can handle the most
 /// common filter syntaxes without writing custom parsers.
-fn classify_argument(kind: &FilterKind, raw: &str, quoted: bool) -> ArgumentKind {
+fn classify_argument(
+    kind: &FilterKind,
+    raw: &str,
+    quoted: bool,
+    options: &ParseOptions,
+) -> ArgumentKind {
     if quoted {
         return ArgumentKind::Phrase;
     }
 
-    if let Some(list) = try_parse_list(raw) {
+    let separators: Vec<char> = options
+        .list_separators
+        .iter()
+        .copied()
+        .filter(|&separator| separator == ';' || kind.takes_name_list())
+        .collect();
+    if let Some(list) = try_parse_list(raw, &separators) {
         return ArgumentKind::List(list);
     }
 
+    let normalized = if kind.takes_number() {
+        strip_digit_separators(raw)
+    } else {
+        Cow::Borrowed(raw)
+    };
+    let raw = normalized.as_ref();
+
+    if *kind == FilterKind::Dimensions {
+        if let Some(dimensions) = try_parse_dimensions(raw) {
+            return dimensions;
+        }
+    }
+
+    if *kind == FilterKind::Attribute {
+        if let Some(attributes) = try_parse_attributes(raw) {
+            return attributes;
+        }
+    }
+
+    if *kind == FilterKind::Orientation {
+        if let Some(orientation) = Orientation::parse(raw) {
+            return ArgumentKind::Orientation(orientation);
+        }
+    }
+
+    if kind.takes_date() {
+        if let Some(spec) = DateSpec::parse(raw) {
+            return ArgumentKind::Date(spec);
+        }
+    }
+
     if let Some(comparison) = try_parse_comparison(raw) {
+        if kind.takes_integer() && comparison.as_u64().is_none() {
+            return ArgumentKind::Bare;
+        }
         return ArgumentKind::Comparison(comparison);
     }
 
     if let Some(range) = try_parse_range(kind, raw) {
+        if kind.takes_integer() && range.as_u64().is_none() {
+            return ArgumentKind::Bare;
+        }
         return ArgumentKind::Range(range);
     }
 
     ArgumentKind::Bare
 }
 
-/// Splits `foo;bar;baz` style extension lists.
-fn try_parse_list(raw: &str) -> Option<Vec<String>> {
-    if !raw.contains(';') {
+/// Plain decimal digits only, so signs and units don't sneak through `str::parse`.
+fn parse_integer(value: &str) -> Option<u64> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+/// Splits `foo;bar;baz` (or `foo,bar`) style extension lists. Empty items,
+/// e.g. from a trailing separator, are dropped.
+fn try_parse_list(raw: &str, separators: &[char]) -> Option<Vec<String>> {
+    if !raw.contains(separators) {
         return None;
     }
 
     let parts: Vec<String> = raw
-        .split(';')
+        .split(separators)
         .map(|p| p.trim())
         .filter(|p| !p.is_empty())
         .map(|p| p.to_string())
@@ -1246,6 +2496,37 @@ fn try_parse_list(raw: &str) -> Option<Vec<String>> {
     if parts.len() > 1 { Some(parts) } else { None }
 }
 
+/// Parses `1920x1080`, optionally behind a comparison operator (`>1920x1080`).
+fn try_parse_dimensions(raw: &str) -> Option<ArgumentKind> {
+    let (op, value) = match try_parse_comparison(raw) {
+        Some(comparison) => (Some(comparison.op), comparison.value),
+        None => (None, raw.to_string()),
+    };
+    let (width, height) = value.split_once(['x', 'X'])?;
+    let parse = |value: &str| {
+        let value = value.trim();
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        value.parse::<u32>().ok()
+    };
+    Some(ArgumentKind::Dimensions {
+        op,
+        width: parse(width)?,
+        height: parse(height)?,
+    })
+}
+
+/// Parses `RHS` style attribute letters; anything but ASCII letters stays bare.
+fn try_parse_attributes(raw: &str) -> Option<ArgumentKind> {
+    if raw.is_empty() || !raw.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some(ArgumentKind::Attributes(
+        raw.chars().map(AttributeFlag::from_letter).collect(),
+    ))
+}
+
 /// Detects `<, <=, >, >=, =, !=` prefixes.
 fn try_parse_comparison(raw: &str) -> Option<ComparisonValue> {
     let operators = ["<=", ">=", "!=", "<", ">", "="];
@@ -1311,6 +2592,8 @@ fn try_parse_dotted_range(raw: &str) -> Option<RangeValue> {
             Some(end_raw.to_string())
         },
         separator: RangeSeparator::Dots,
+        inclusive_start: true,
+        inclusive_end: true,
     })
 }
 
@@ -1331,6 +2614,8 @@ fn try_parse_hyphen_range(raw: &str) -> Option<RangeValue> {
                 start: Some(left.to_string()),
                 end: Some(right.to_string()),
                 separator: RangeSeparator::Hyphen,
+                inclusive_start: true,
+                inclusive_end: true,
             });
         }
     }
@@ -1339,13 +2624,7 @@ fn try_parse_hyphen_range(raw: &str) -> Option<RangeValue> {
 
 /// Only date-related filters accept hyphenated ranges.
 fn allows_hyphen_range(kind: &FilterKind) -> bool {
-    matches!(
-        kind,
-        FilterKind::DateCreated
-            | FilterKind::DateModified
-            | FilterKind::DateAccessed
-            | FilterKind::DateRun
-    )
+    kind.takes_date()
 }
 
 fn has_digit(value: &str) -> bool {
@@ -1454,18 +2733,18 @@ mod tests {
             panic!("expected OR group");
         };
         assert_eq!(choices.len(), 2);
-        let mut names = Vec::new();
+        let mut drives = Vec::new();
         for choice in choices {
             let Expr::Term(Term::Filter(filter)) = choice else {
                 panic!("expected filter term");
             };
             assert!(filter.argument.is_none());
-            let FilterKind::Custom(name) = &filter.kind else {
-                panic!("expected drive-like custom filter");
+            let FilterKind::Drive(letter) = filter.kind else {
+                panic!("expected drive filter");
             };
-            names.push(name.as_str());
+            drives.push(letter);
         }
-        assert_eq!(names, ["D", "E"]);
+        assert_eq!(drives, ['D', 'E']);
 
         assert_eq!(parts[1], word("*.mp3"));
     }
@@ -1654,7 +2933,10 @@ mod tests {
             panic!("expected dm: argument");
         };
         assert_eq!(argument.raw, "pastyear");
-        assert!(matches!(argument.kind, ArgumentKind::Bare));
+        assert_eq!(
+            argument.kind,
+            ArgumentKind::Date(DateSpec::Keyword(DateKeyword::PastYear))
+        );
     }
 
     #[test]
@@ -0,0 +1,178 @@
+//! Lexer-level view of a query for editor tooling such as syntax highlighting.
+
+use crate::{ParseOptions, Parser, Term};
+use std::ops::Range;
+
+/// A classified slice of the input, `span` being its byte range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Bare word or wildcard pattern.
+    Word,
+    /// Double quoted text, quotes included. An unterminated phrase runs to the end
+    /// of the input.
+    Phrase,
+    /// The `name` of a `name:` filter, `regex` included.
+    FilterName,
+    /// The `:` following a filter name.
+    Colon,
+    /// Unquoted filter argument or regex pattern. Quoted arguments are [`TokenKind::Phrase`].
+    Argument,
+    /// `|`, `!` and the `AND`/`OR`/`NOT`/`NEAR` keywords.
+    Operator,
+    /// `(` or `<`.
+    GroupOpen,
+    /// `)` or `>`.
+    GroupClose,
+    Whitespace,
+}
+
+/// Splits `input` into tokens without building an expression tree. Never fails:
+/// malformed input still yields tokens covering every byte, in order.
+///
+/// ```
+/// use cardinal_syntax::{tokenize, TokenKind};
+/// let kinds: Vec<_> = tokenize("ext:rs !test").into_iter().map(|token| token.kind).collect();
+/// assert_eq!(
+///     kinds,
+///     [
+///         TokenKind::FilterName,
+///         TokenKind::Colon,
+///         TokenKind::Argument,
+///         TokenKind::Whitespace,
+///         TokenKind::Operator,
+///         TokenKind::Word,
+///     ]
+/// );
+/// ```
+pub fn tokenize(input: &str) -> Vec<Token> {
+    let options = ParseOptions::default();
+    let mut lexer = Lexer {
+        parser: Parser::new(input, &options),
+        tokens: Vec::new(),
+    };
+    lexer.run();
+    lexer.tokens
+}
+
+struct Lexer<'a> {
+    parser: Parser<'a>,
+    tokens: Vec<Token>,
+}
+
+impl Lexer<'_> {
+    fn run(&mut self) {
+        while let Some(ch) = self.parser.peek_char() {
+            let start = self.parser.pos;
+            let kind = match ch {
+                ch if ch.is_whitespace() => {
+                    self.parser.skip_ws();
+                    TokenKind::Whitespace
+                }
+                '|' | '!' => {
+                    self.parser.advance_char();
+                    TokenKind::Operator
+                }
+                '(' | '<' => {
+                    self.parser.advance_char();
+                    self.parser
+                        .group_stack
+                        .push(if ch == '(' { ')' } else { '>' });
+                    TokenKind::GroupOpen
+                }
+                ')' | '>' => {
+                    if self.parser.current_closer_is(ch) {
+                        self.parser.group_stack.pop();
+                    }
+                    self.parser.advance_char();
+                    TokenKind::GroupClose
+                }
+                '"' => {
+                    // Runs to the end of the input when the closing quote is missing.
+                    let _ = self.parser.parse_phrase_string();
+                    TokenKind::Phrase
+                }
+                _ if self.consume_operator_keyword() => TokenKind::Operator,
+                _ => {
+                    self.word_like(start);
+                    continue;
+                }
+            };
+            self.push(kind, start);
+        }
+    }
+
+    fn consume_operator_keyword(&mut self) -> bool {
+        ["AND", "OR", "NOT"]
+            .into_iter()
+            .any(|keyword| self.parser.consume_keyword(keyword))
+            || self.parser.consume_near().is_some()
+    }
+
+    fn word_like(&mut self, start: usize) {
+        let term = self.parser.parse_word_like();
+        if matches!(term, Ok(Term::Word(_))) {
+            self.push(TokenKind::Word, start);
+            return;
+        }
+        // Anything else went through `name:`, and a valid name holds no separator.
+        let colon = start
+            + self.parser.input[start..]
+                .find([':', '='])
+                .expect("filter terms contain a separator");
+        self.tokens.push(Token {
+            kind: TokenKind::FilterName,
+            span: start..colon,
+        });
+        self.tokens.push(Token {
+            kind: TokenKind::Colon,
+            span: colon..colon + 1,
+        });
+        if term.is_ok() {
+            self.argument(colon + 1);
+        } else {
+            // Let the main loop lex whatever follows, e.g. an unterminated phrase.
+            self.parser.pos = colon + 1;
+        }
+    }
+
+    /// Classifies `start..pos`, the argument the parser just consumed. `regex:` may
+    /// skip whitespace before its pattern.
+    fn argument(&mut self, start: usize) {
+        let end = self.parser.pos;
+        let rest = &self.parser.input[start..end];
+        let pattern = start + (rest.len() - rest.trim_start().len());
+        if pattern > start {
+            self.tokens.push(Token {
+                kind: TokenKind::Whitespace,
+                span: start..pattern,
+            });
+        }
+        if pattern < end {
+            let kind = if self.parser.input[pattern..].starts_with('"') {
+                TokenKind::Phrase
+            } else {
+                TokenKind::Argument
+            };
+            self.tokens.push(Token {
+                kind,
+                span: pattern..end,
+            });
+        }
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize) {
+        if self.parser.pos == start {
+            self.parser.advance_char();
+        }
+        self.tokens.push(Token {
+            kind,
+            span: start..self.parser.pos,
+        });
+    }
+}
@@ -0,0 +1,62 @@
+use cardinal_syntax::*;
+
+fn query(input: &str) -> Query {
+    parse_query(input).expect("parse ok")
+}
+
+#[test]
+fn filters_are_collected_through_every_operator() {
+    let q = query("folder:src report <ext:rs|ext:toml> !size:>1mb (dm:today NEAR regex:^a) draft");
+    let kinds: Vec<_> = q
+        .filters()
+        .into_iter()
+        .map(|filter| filter.kind.clone())
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            FilterKind::Folder,
+            FilterKind::Ext,
+            FilterKind::Ext,
+            FilterKind::Size,
+            FilterKind::DateModified,
+        ]
+    );
+    let raws: Vec<_> = q
+        .filters()
+        .into_iter()
+        .filter_map(|filter| filter.argument.as_ref())
+        .map(|argument| argument.raw.as_str())
+        .collect();
+    assert_eq!(raws, ["src", "rs", "toml", ">1mb", "today"]);
+}
+
+#[test]
+fn words_skip_phrases_regexes_and_filter_arguments() {
+    let q = query("report <draft|!final> \"q1 notes\" ext:pdf regex:^x *.md NEAR budget");
+    assert_eq!(q.words(), ["report", "draft", "final", "*.md", "budget"]);
+    assert!(query("ext:pdf \"only phrase\"").words().is_empty());
+}
+
+#[test]
+fn single_term_only_for_operator_free_queries() {
+    assert!(matches!(query("report").single_term(), Some(Term::Word(word)) if word == "report"));
+    assert!(matches!(
+        query("ext:pdf").single_term(),
+        Some(Term::Filter(filter)) if filter.kind == FilterKind::Ext
+    ));
+    assert!(matches!(
+        query("<report>").single_term(),
+        Some(Term::Word(_))
+    ));
+    for input in ["", "a b", "a|b", "!a", "a NEAR b"] {
+        assert!(query(input).single_term().is_none(), "{input}");
+    }
+}
+
+#[test]
+fn empty_query_has_nothing_to_collect() {
+    let q = query("");
+    assert!(q.filters().is_empty());
+    assert!(q.words().is_empty());
+}
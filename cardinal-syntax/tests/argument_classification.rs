@@ -21,10 +21,10 @@ fn dotted_range_requires_digits_otherwise_bare() {
     let (_, arg) = filter_kind(&expr);
     assert!(matches!(arg.as_ref().unwrap().kind, ArgumentKind::Bare));
 
-    let expr = parse_ok("size:..10");
+    let expr = parse_raw("size:..10");
     filter_arg_is_range_dots(&expr, None, Some("10"));
 
-    let expr = parse_ok("size:1..");
+    let expr = parse_raw("size:1..");
     filter_arg_is_range_dots(&expr, Some("1"), None);
 }
 
@@ -0,0 +1,52 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn attribute_argument(input: &str) -> ArgumentKind {
+    let expr = parse_ok(input);
+    filter_is_kind(&expr, &FilterKind::Attribute);
+    let (_, arg) = filter_kind(&expr);
+    arg.as_ref().expect("missing argument").kind.clone()
+}
+
+#[test]
+fn single_attribute_letter() {
+    assert_eq!(
+        attribute_argument("attrib:H"),
+        ArgumentKind::Attributes(vec![AttributeFlag::Hidden])
+    );
+}
+
+#[test]
+fn multiple_attribute_letters_keep_order() {
+    assert_eq!(
+        attribute_argument("attrib:RHS"),
+        ArgumentKind::Attributes(vec![
+            AttributeFlag::ReadOnly,
+            AttributeFlag::Hidden,
+            AttributeFlag::System,
+        ])
+    );
+}
+
+#[test]
+fn lowercase_letters_are_accepted() {
+    assert_eq!(
+        attribute_argument("attrib:rh"),
+        ArgumentKind::Attributes(vec![AttributeFlag::ReadOnly, AttributeFlag::Hidden])
+    );
+}
+
+#[test]
+fn unknown_letters_are_preserved() {
+    assert_eq!(
+        attribute_argument("attrib:hq"),
+        ArgumentKind::Attributes(vec![AttributeFlag::Hidden, AttributeFlag::Other('Q')])
+    );
+}
+
+#[test]
+fn non_letter_attributes_stay_bare() {
+    assert_eq!(attribute_argument("attrib:0x20"), ArgumentKind::Bare);
+    assert_eq!(attribute_argument("attrib:\"RH\""), ArgumentKind::Phrase);
+}
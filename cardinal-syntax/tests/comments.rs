@@ -0,0 +1,59 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn parse_commented(input: &str) -> Expr {
+    let options = ParseOptions {
+        allow_comments: true,
+        ..Default::default()
+    };
+    parse_query_with(input, &options).unwrap().expr
+}
+
+#[test]
+fn comment_runs_to_end_of_line() {
+    word_is(&parse_commented("report # find reports"), "report");
+
+    let expr = parse_commented("report # weekly\next:pdf # only pdfs\n# whole line\nsummary");
+    let parts = as_and(&expr);
+    assert_eq!(parts.len(), 3);
+    word_is(&parts[0], "report");
+    filter_is_kind(&parts[1], &FilterKind::Ext);
+    filter_arg_raw(&parts[1], "pdf");
+    word_is(&parts[2], "summary");
+}
+
+#[test]
+fn hash_inside_phrase_is_literal() {
+    phrase_is(&parse_commented("\"a#b\""), "a#b");
+
+    let expr = parse_commented("\"C# notes\" # language notes");
+    phrase_is(&expr, "C# notes");
+}
+
+#[test]
+fn comment_only_query_is_empty() {
+    assert!(is_empty(&parse_commented("# nothing to see")));
+}
+
+#[test]
+fn comments_are_off_by_default() {
+    word_is(&parse_raw("report#1"), "report#1");
+    let parts = parse_raw("report # find");
+    let parts = as_and(&parts);
+    word_is(&parts[1], "#");
+    assert_eq!(
+        parse_query_with("report # find", &ParseOptions::default()).unwrap(),
+        parse_query("report # find").unwrap()
+    );
+}
+
+#[test]
+fn error_positions_ignore_stripped_comment() {
+    let options = ParseOptions {
+        allow_comments: true,
+        ..Default::default()
+    };
+    let err = parse_query_with("a # é comment\n\"open", &options).unwrap_err();
+    assert_eq!(err.position, "a # é comment\n".len());
+}
@@ -0,0 +1,56 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn strict(known: &[&str]) -> ParseOptions {
+    ParseOptions {
+        known_custom_filters: known.iter().map(|name| name.to_string()).collect(),
+        reject_unknown_filters: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn known_custom_filter_parses_as_custom() {
+    let options = strict(&["proj"]);
+    let expr = parse_query_with("proj:cardinal report", &options)
+        .unwrap()
+        .expr;
+    let parts = as_and(&expr);
+    filter_is_custom(&parts[0], "proj");
+    filter_arg_raw(&parts[0], "cardinal");
+    word_is(&parts[1], "report");
+
+    // Names are matched case-insensitively but keep their spelling.
+    let expr = parse_query_with("PROJ:", &options).unwrap().expr;
+    filter_is_custom(&expr, "PROJ");
+}
+
+#[test]
+fn unknown_filter_is_rejected_in_strict_mode() {
+    let err = parse_query_with("report bogus:value", &strict(&["proj"])).unwrap_err();
+    assert!(err.message.contains("bogus"), "{err}");
+    assert_eq!(err.position, "report ".len());
+
+    let err = parse_query_with("<a | bogus:>", &strict(&[])).unwrap_err();
+    assert_eq!(err.position, "<a | ".len());
+}
+
+#[test]
+fn builtin_filters_and_regex_are_always_known() {
+    let options = strict(&[]);
+    let expr = parse_query_with("ext:rs size:>1mb regex:^main", &options)
+        .unwrap()
+        .expr;
+    assert_eq!(as_and(&expr).len(), 3);
+}
+
+#[test]
+fn unknown_filters_are_accepted_by_default() {
+    let options = ParseOptions {
+        known_custom_filters: ["proj".to_string()].into(),
+        ..Default::default()
+    };
+    filter_is_custom(&parse_query_with("bogus:", &options).unwrap().expr, "bogus");
+    filter_is_custom(&parse_raw("bogus:"), "bogus");
+}
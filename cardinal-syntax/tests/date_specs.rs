@@ -0,0 +1,66 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn argument(input: &str) -> ArgumentKind {
+    let expr = parse_ok(input);
+    let (_, arg) = filter_kind(&expr);
+    arg.as_ref().expect("missing argument").kind.clone()
+}
+
+#[test]
+fn relative_spans_are_structured() {
+    let cases = [
+        ("dm:past7days", 7, DateUnit::Days),
+        ("dm:last3months", 3, DateUnit::Months),
+        ("dc:past2weeks", 2, DateUnit::Weeks),
+        ("da:Last1Year", 1, DateUnit::Years),
+        ("dm:past1day", 1, DateUnit::Days),
+    ];
+    for (input, n, unit) in cases {
+        assert_eq!(
+            argument(input),
+            ArgumentKind::Date(DateSpec::Relative { n, unit }),
+            "{input}"
+        );
+        // The argument text stays as typed.
+        filter_arg_raw(&parse_ok(input), input.split_once(':').unwrap().1);
+    }
+}
+
+#[test]
+fn keywords_keep_their_variant() {
+    assert_eq!(
+        argument("dm:today"),
+        ArgumentKind::Date(DateSpec::Keyword(DateKeyword::Today))
+    );
+    assert_eq!(
+        argument("dc:LastWeek"),
+        ArgumentKind::Date(DateSpec::Keyword(DateKeyword::LastWeek))
+    );
+    assert_eq!(
+        argument("dm:pastmonth"),
+        ArgumentKind::Date(DateSpec::Keyword(DateKeyword::PastMonth))
+    );
+}
+
+#[test]
+fn other_date_values_are_unchanged() {
+    assert_eq!(argument("dm:2024/01/01"), ArgumentKind::Bare);
+    for input in [
+        "dm:pastdays",
+        "dm:past7",
+        "dm:past7fortnights",
+        "dm:last-3days",
+    ] {
+        assert_eq!(argument(input), ArgumentKind::Bare, "{input}");
+    }
+    filter_arg_is_range_hyphen(&parse_ok("dc:2014/8/1-2014/8/31"), "2014/8/1", "2014/8/31");
+    filter_arg_is_comparison(&parse_ok("dm:>today"), ComparisonOp::Gt, "today");
+}
+
+#[test]
+fn only_date_filters_parse_date_specs() {
+    assert_eq!(argument("size:today"), ArgumentKind::Bare);
+    assert_eq!(argument("parent:past7days"), ArgumentKind::Bare);
+}
@@ -0,0 +1,69 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn dimensions_argument(input: &str) -> ArgumentKind {
+    let expr = parse_ok(input);
+    filter_is_kind(&expr, &FilterKind::Dimensions);
+    let (_, arg) = filter_kind(&expr);
+    arg.as_ref().expect("missing argument").kind.clone()
+}
+
+#[test]
+fn plain_dimensions_split_into_width_and_height() {
+    assert_eq!(
+        dimensions_argument("dimensions:1920x1080"),
+        ArgumentKind::Dimensions {
+            op: None,
+            width: 1920,
+            height: 1080,
+        }
+    );
+    assert_eq!(
+        dimensions_argument("dimensions:800X600"),
+        ArgumentKind::Dimensions {
+            op: None,
+            width: 800,
+            height: 600,
+        }
+    );
+}
+
+#[test]
+fn comparison_dimensions_keep_operator() {
+    assert_eq!(
+        dimensions_argument("dimensions:>1920x1080"),
+        ArgumentKind::Dimensions {
+            op: Some(ComparisonOp::Gt),
+            width: 1920,
+            height: 1080,
+        }
+    );
+    assert_eq!(
+        dimensions_argument("dimensions:<=640x480"),
+        ArgumentKind::Dimensions {
+            op: Some(ComparisonOp::Lte),
+            width: 640,
+            height: 480,
+        }
+    );
+}
+
+#[test]
+fn malformed_dimensions_stay_bare() {
+    for input in [
+        "dimensions:1920x",
+        "dimensions:x1080",
+        "dimensions:widexhigh",
+        "dimensions:+1x2",
+        "dimensions:99999999999x1",
+    ] {
+        assert_eq!(dimensions_argument(input), ArgumentKind::Bare, "{input}");
+    }
+}
+
+#[test]
+fn other_filters_do_not_parse_dimensions() {
+    let error = parse_err("width:1920x1080");
+    assert_eq!(error.position, "width:".len());
+}
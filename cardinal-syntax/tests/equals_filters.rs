@@ -0,0 +1,68 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn equals_options() -> ParseOptions {
+    ParseOptions {
+        equals_filters: true,
+        ..Default::default()
+    }
+}
+
+fn parse_eq(input: &str) -> Expr {
+    optimize_query(parse_query_with(input, &equals_options()).unwrap()).expr
+}
+
+#[test]
+fn equals_form_matches_colon_form() {
+    for (equals, colon) in [
+        ("ext=txt", "ext:txt"),
+        ("type=picture", "type:picture"),
+        ("ext=jpg;png report", "ext:jpg;png report"),
+        ("dm=2024-01-01..2024-12-31", "dm:2024-01-01..2024-12-31"),
+        ("folder= report", "folder: report"),
+        ("regex=^foo", "regex:^foo"),
+    ] {
+        assert_eq!(parse_eq(equals), parse_ok(colon), "{equals}");
+    }
+}
+
+#[test]
+fn comparison_after_equals_stays_a_comparison() {
+    let expr = parse_eq("size=>1gb");
+    filter_is_kind(&expr, &FilterKind::Size);
+    filter_arg_raw(&expr, ">1gb");
+    let (_, argument) = filter_kind(&expr);
+    match &argument.as_ref().unwrap().kind {
+        ArgumentKind::Comparison(value) => assert_eq!(value.op, ComparisonOp::Gt),
+        other => panic!("expected comparison, got {other:?}"),
+    }
+    assert_eq!(parse_eq("size:>=1gb"), parse_ok("size:>=1gb"));
+}
+
+#[test]
+fn equals_form_stops_previous_filter_argument() {
+    assert_eq!(parse_eq("video: size=>1gb"), parse_ok("video: size:>1gb"));
+}
+
+#[test]
+fn unknown_names_stay_words() {
+    word_is(&parse_eq("a=b"), "a=b");
+    word_is(&parse_eq("foo=bar.txt"), "foo=bar.txt");
+    word_is(&parse_eq("=ext"), "=ext");
+
+    let options = ParseOptions {
+        equals_filters: true,
+        known_custom_filters: ["proj".to_string()].into(),
+        ..Default::default()
+    };
+    let expr = parse_query_with("proj=cardinal", &options).unwrap().expr;
+    filter_is_custom(&expr, "proj");
+}
+
+#[test]
+fn words_with_equals_stay_words_when_option_is_off() {
+    word_is(&parse_ok("ext=txt"), "ext=txt");
+    word_is(&parse_ok("type=picture"), "type=picture");
+    word_is(&parse_ok("size=1gb"), "size=1gb");
+}
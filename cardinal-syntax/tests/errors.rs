@@ -27,3 +27,37 @@ fn error_positions_are_byte_offsets() {
     let err = parse_err("<a b");
     assert!(err.position > 0);
 }
+
+fn nested(depth: usize) -> String {
+    format!("{}a{}", "(".repeat(depth), ")".repeat(depth))
+}
+
+#[test]
+fn deep_nesting_is_an_error_not_an_overflow() {
+    let err = parse_err(&nested(100_000));
+    assert!(err.message.contains("nested deeper than"), "{err}");
+    // Points at the first group past the limit.
+    assert_eq!(err.position, cardinal_syntax::DEFAULT_MAX_DEPTH);
+
+    let (_, errors) = cardinal_syntax::parse_query_recovering(&nested(100_000));
+    assert!(!errors.is_empty());
+
+    let expr = parse_ok(&nested(cardinal_syntax::DEFAULT_MAX_DEPTH));
+    assert!(matches!(
+        expr,
+        cardinal_syntax::Expr::Term(cardinal_syntax::Term::Word(ref w)) if w == "a"
+    ));
+}
+
+#[test]
+fn max_depth_is_configurable() {
+    use cardinal_syntax::{ParseOptions, parse_query_with};
+    let options = ParseOptions {
+        max_depth: 3,
+        ..Default::default()
+    };
+    assert!(parse_query_with(&nested(3), &options).is_ok());
+    assert!(parse_query_with("<a (b <c>)> d", &options).is_ok());
+    let err = parse_query_with("<a (b <c (d)>)>", &options).unwrap_err();
+    assert_eq!(err.position, 9);
+}
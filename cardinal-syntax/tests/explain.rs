@@ -0,0 +1,51 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn explained(input: &str) -> String {
+    explain(&parse_raw(input))
+}
+
+#[test]
+fn or_binds_tighter_than_whitespace() {
+    assert_eq!(explained("foo bar|baz"), "(foo AND (bar OR baz))");
+    assert_eq!(explained("a|b c|d"), "((a OR b) AND (c OR d))");
+    assert_eq!(explained("a b c"), "(a AND b AND c)");
+    assert_eq!(explained("report"), "report");
+}
+
+#[test]
+fn explicit_operators_and_groups() {
+    assert_eq!(explained("a AND b OR c"), "(a AND (b OR c))");
+    assert_eq!(explained("<a b>|c"), "((a AND b) OR c)");
+    assert_eq!(explained("!a b"), "(NOT a AND b)");
+    assert_eq!(explained("!<a|b> c"), "(NOT (a OR b) AND c)");
+    assert_eq!(explained("error NEAR/2 timeout"), "(error NEAR/2 timeout)");
+}
+
+#[test]
+fn leaves_render_readably() {
+    assert_eq!(
+        explained(r#"ext:jpg;png "summer holiday"|size:>1GB"#),
+        r#"(ext:jpg;png AND ("summer holiday" OR size:>1GB))"#
+    );
+    assert_eq!(
+        explained(r#"datemodified:today parent:"/Users/demo/My Files" folder:"#),
+        r#"(dm:today AND parent:"/Users/demo/My Files" AND folder:)"#
+    );
+    assert_eq!(
+        explained("regex:^Report wholeword:x"),
+        "(regex:^Report AND ww:x)"
+    );
+    assert_eq!(
+        explained(r"d:\Downloads\ proj:x"),
+        r"(D:\Downloads\ AND proj:x)"
+    );
+}
+
+#[test]
+fn empty_operands_are_spelled_out() {
+    assert_eq!(explained("a||b"), "(a OR EMPTY OR b)");
+    let optimized = parse_ok("a||b");
+    assert_eq!(explain(&optimized), "EMPTY");
+}
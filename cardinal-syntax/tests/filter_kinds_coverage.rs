@@ -35,6 +35,7 @@ fn maps_known_filter_names() {
         ("parent", FilterKind::Parent),
         ("infolder", FilterKind::InFolder),
         ("nosubfolders", FilterKind::NoSubfolders),
+        ("path", FilterKind::Path),
         ("child", FilterKind::Child),
         ("attrib", FilterKind::Attribute),
         ("attribdupe", FilterKind::AttributeDuplicate),
@@ -57,6 +58,14 @@ fn maps_known_filter_names() {
         ("case", FilterKind::CaseSensitive),
         ("content", FilterKind::Content),
         ("nowholefilename", FilterKind::NoWholeFilename),
+        ("nocase", FilterKind::NoCase),
+        ("wholeword", FilterKind::WholeWord),
+        ("ww", FilterKind::WholeWord),
+        ("nowholeword", FilterKind::NoWholeWord),
+        ("wholefilename", FilterKind::WholeFilename),
+        ("wfn", FilterKind::WholeFilename),
+        ("diacritics", FilterKind::Diacritics),
+        ("nodiacritics", FilterKind::NoDiacritics),
     ];
 
     for (name, expected) in cases {
@@ -74,13 +83,35 @@ fn preserves_custom_names() {
         other => panic!("{other:?}"),
     }
 
-    let f = parse_filter("D", None);
+    // Single letters followed by an ordinary argument are not drives.
+    let f = parse_filter("D", Some("report"));
     match f.kind {
         FilterKind::Custom(n) => assert_eq!(n, "D"),
         other => panic!("{other:?}"),
     }
 }
 
+#[test]
+fn single_letters_before_terminators_are_drives() {
+    assert_eq!(parse_filter("D", None).kind, FilterKind::Drive('D'));
+    assert_eq!(parse_filter("e", None).kind, FilterKind::Drive('E'));
+
+    let f = parse_filter("D", Some(r"\Downloads\"));
+    assert_eq!(f.kind, FilterKind::Drive('D'));
+    assert_eq!(f.argument.unwrap().raw, r"\Downloads\");
+
+    let f = parse_filter("C", Some("/Users"));
+    assert_eq!(f.kind, FilterKind::Drive('C'));
+    assert_eq!(f.argument.unwrap().raw, "/Users");
+
+    // Built-in two and three letter names are untouched.
+    assert_eq!(parse_filter("doc", None).kind, FilterKind::Doc);
+    assert_eq!(
+        parse_filter("dm", Some("today")).kind,
+        FilterKind::DateModified
+    );
+}
+
 #[test]
 fn argument_shapes_overview() {
     // list
@@ -56,6 +56,43 @@ fn phrase_argument_is_detected() {
     }
 }
 
+#[test]
+fn quoted_arguments_are_taken_verbatim() {
+    for (query, kind, raw) in [
+        (r#"content:"a:b c""#, FilterKind::Content, "a:b c"),
+        (
+            r#"parent:"</weird>/path""#,
+            FilterKind::Parent,
+            "</weird>/path",
+        ),
+        (
+            r#"infolder:"/a:b/<c>|d""#,
+            FilterKind::InFolder,
+            "/a:b/<c>|d",
+        ),
+        (r#"ext:"jpg;png""#, FilterKind::Ext, "jpg;png"),
+    ] {
+        let expr = parse_ok(query);
+        filter_is_kind(&expr, &kind);
+        filter_arg_raw(&expr, raw);
+        let (_, arg) = filter_kind(&expr);
+        assert_eq!(arg.as_ref().unwrap().kind, ArgumentKind::Phrase, "{query}");
+    }
+}
+
+#[test]
+fn quoted_arguments_keep_group_closers() {
+    let expr = parse_ok(r#"(content:"a)b") report"#);
+    let parts = as_and(&expr);
+    word_is(&parts[0], "report");
+    filter_arg_raw(&parts[1], "a)b");
+
+    let expr = parse_ok(r#"<parent:"a>b" z>"#);
+    let parts = as_and(&expr);
+    word_is(&parts[0], "z");
+    filter_arg_raw(&parts[1], "a>b");
+}
+
 #[test]
 fn filter_can_appear_anywhere_in_and_chain() {
     let expr = parse_ok("video: size:>1gb report");
@@ -10,9 +10,9 @@ fn angle_bracket_group_with_or() {
     let g = &parts[0];
     let or_parts = as_or(g);
     assert_eq!(or_parts.len(), 2);
-    filter_is_custom(&or_parts[0], "D");
+    filter_is_kind(&or_parts[0], &cardinal_syntax::FilterKind::Drive('D'));
     filter_arg_none(&or_parts[0]);
-    filter_is_custom(&or_parts[1], "E");
+    filter_is_kind(&or_parts[1], &cardinal_syntax::FilterKind::Drive('E'));
     filter_arg_none(&or_parts[1]);
 
     word_is(&parts[1], "*.mp3");
@@ -55,3 +55,54 @@ fn reports_unmatched_opening() {
     let err = parse_err("<foo bar");
     assert!(err.message.contains("expected '>'"));
 }
+
+#[test]
+fn empty_or_operands_inside_groups_match_top_level() {
+    for (grouped, top_level) in [
+        ("<foo||bar>", "foo||bar"),
+        ("<|foo>", "|foo"),
+        ("<foo|>", "foo|"),
+        ("<|>", "|"),
+        ("(foo||bar)", "foo||bar"),
+        ("<foo | | bar>", "foo | | bar"),
+    ] {
+        assert_eq!(parse_raw(grouped), parse_raw(top_level), "{grouped}");
+        assert_eq!(parse_ok(grouped), parse_ok(top_level), "{grouped}");
+    }
+
+    let expr = parse_raw("<foo||bar>");
+    let parts = as_or(&expr);
+    assert_eq!(parts.len(), 3);
+    word_is(&parts[0], "foo");
+    assert!(is_empty(&parts[1]));
+    word_is(&parts[2], "bar");
+
+    let expr = parse_raw("<|foo>");
+    let parts = as_or(&expr);
+    assert!(is_empty(&parts[0]));
+    word_is(&parts[1], "foo");
+
+    let expr = parse_raw("<foo|>");
+    let parts = as_or(&expr);
+    word_is(&parts[0], "foo");
+    assert!(is_empty(&parts[1]));
+}
+
+#[test]
+fn empty_or_operand_stays_inside_its_group() {
+    let expr = parse_raw("x <foo|> y");
+    let parts = as_and(&expr);
+    assert_eq!(parts.len(), 3);
+    word_is(&parts[0], "x");
+    let alternatives = as_or(&parts[1]);
+    word_is(&alternatives[0], "foo");
+    assert!(is_empty(&alternatives[1]));
+    word_is(&parts[2], "y");
+
+    // The empty alternative matches everything, leaving only the siblings.
+    let expr = parse_ok("x <foo|> y");
+    let parts = as_and(&expr);
+    assert_eq!(parts.len(), 2);
+    word_is(&parts[0], "x");
+    word_is(&parts[1], "y");
+}
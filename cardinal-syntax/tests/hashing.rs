@@ -0,0 +1,70 @@
+use cardinal_syntax::*;
+use std::collections::{BTreeSet, HashSet};
+
+#[test]
+fn equal_queries_collide_in_a_hash_set() {
+    let mut seen = HashSet::new();
+    for input in [
+        "foo bar",
+        "size:>1gb",
+        "size:1mb..10mb",
+        "ext:jpg;png",
+        "dimensions:>=1920x1080",
+        "attrib:rh",
+        "a NEAR/2 b",
+        "!<a|b>",
+    ] {
+        assert!(seen.insert(parse_query(input).unwrap()), "{input}");
+        assert!(!seen.insert(parse_query(input).unwrap()), "{input}");
+    }
+    // Same tree from different spellings.
+    assert!(!seen.insert(parse_query("foo  bar").unwrap()));
+    assert!(!seen.insert(parse_query("<foo> bar").unwrap()));
+    assert_eq!(seen.len(), 8);
+}
+
+#[test]
+fn unequal_arguments_hash_apart() {
+    let mut seen = HashSet::new();
+    for input in [
+        "size:1mb..10mb",
+        "size:1mb..",
+        "size:..10mb",
+        "size:>1mb",
+        "size:>=1mb",
+        "size:1mb",
+        "size:\"1mb\"",
+        "dc:2024/1/1-2024/12/31",
+        "dc:2024/1/1..2024/12/31",
+        "ext:jpg;png",
+        "ext:png;jpg",
+    ] {
+        assert!(seen.insert(parse_query(input).unwrap()), "{input}");
+    }
+
+    let mut open_end = parse_query("size:1mb..10mb").unwrap();
+    let Expr::Term(Term::Filter(filter)) = &mut open_end.expr else {
+        panic!("expected filter");
+    };
+    let ArgumentKind::Range(range) = &mut filter.argument.as_mut().unwrap().kind else {
+        panic!("expected range");
+    };
+    range.inclusive_end = false;
+    assert!(seen.insert(open_end));
+}
+
+#[test]
+fn queries_sort_deterministically() {
+    let inputs = ["b", "a", "size:>1gb", "a b", "a", "!a"];
+    let mut forward: Vec<Query> = inputs
+        .iter()
+        .map(|input| parse_query(input).unwrap())
+        .collect();
+    let mut backward: Vec<Query> = forward.iter().rev().cloned().collect();
+    forward.sort();
+    backward.sort();
+    assert_eq!(forward, backward);
+
+    let unique: BTreeSet<Query> = forward.into_iter().collect();
+    assert_eq!(unique.len(), 5);
+}
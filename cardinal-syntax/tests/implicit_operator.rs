@@ -0,0 +1,72 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn parse_with(input: &str, implicit_operator: ImplicitOp) -> Expr {
+    let options = ParseOptions {
+        implicit_operator,
+        ..Default::default()
+    };
+    parse_query_with(input, &options).unwrap().expr
+}
+
+#[test]
+fn whitespace_follows_the_implicit_operator() {
+    let expr = parse_with("foo bar", ImplicitOp::And);
+    let parts = as_and(&expr);
+    assert_eq!(parts.len(), 2);
+    word_is(&parts[0], "foo");
+    word_is(&parts[1], "bar");
+    assert_eq!(expr, parse_raw("foo bar"));
+
+    let expr = parse_with("foo bar", ImplicitOp::Or);
+    let parts = as_or(&expr);
+    assert_eq!(parts.len(), 2);
+    word_is(&parts[0], "foo");
+    word_is(&parts[1], "bar");
+
+    word_is(&parse_with("foo", ImplicitOp::Or), "foo");
+}
+
+#[test]
+fn explicit_and_still_binds_under_implicit_or() {
+    // (a AND b) OR c
+    let expr = parse_with("a AND b c", ImplicitOp::Or);
+    let parts = as_or(&expr);
+    assert_eq!(parts.len(), 2);
+    let both = as_and(&parts[0]);
+    word_is(&both[0], "a");
+    word_is(&both[1], "b");
+    word_is(&parts[1], "c");
+
+    let expr = parse_with("ext:rs AND src tests", ImplicitOp::Or);
+    let parts = as_or(&expr);
+    assert_eq!(parts.len(), 2);
+    filter_is_kind(&as_and(&parts[0])[0], &FilterKind::Ext);
+    word_is(&parts[1], "tests");
+}
+
+#[test]
+fn explicit_operators_parse_the_same_in_both_modes() {
+    for input in ["a|b", "a | b", "a OR b", "a AND b", "!a", "<a|b> AND c"] {
+        assert_eq!(
+            parse_with(input, ImplicitOp::Or),
+            parse_with(input, ImplicitOp::And),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn groups_use_the_implicit_operator_too() {
+    let expr = parse_with("<a b> c", ImplicitOp::And);
+    assert_eq!(as_and(&as_and(&expr)[0]).len(), 2);
+
+    let expr = parse_with("<a b> c", ImplicitOp::Or);
+    let parts = as_or(&expr);
+    assert_eq!(parts.len(), 2);
+    let inner = as_or(&parts[0]);
+    word_is(&inner[0], "a");
+    word_is(&inner[1], "b");
+    word_is(&parts[1], "c");
+}
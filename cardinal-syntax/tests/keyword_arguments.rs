@@ -0,0 +1,33 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+#[test]
+fn keyword_spelled_arguments_stay_literal() {
+    for (input, kind, raw) in [
+        ("ext:and", FilterKind::Ext, "and"),
+        ("ext:AND", FilterKind::Ext, "AND"),
+        ("name:NOT", FilterKind::Custom("name".into()), "NOT"),
+        ("type:or", FilterKind::Type, "or"),
+        ("folder:NOT", FilterKind::Folder, "NOT"),
+    ] {
+        let expr = parse_ok(input);
+        filter_is_kind(&expr, &kind);
+        filter_arg_raw(&expr, raw);
+    }
+}
+
+#[test]
+fn keyword_arguments_do_not_join_neighbours() {
+    let expr = parse_raw("ext:and report");
+    let parts = as_and(&expr);
+    assert_eq!(parts.len(), 2);
+    filter_arg_raw(&parts[0], "and");
+    word_is(&parts[1], "report");
+
+    let expr = parse_raw("type:OR NOT draft");
+    let parts = as_and(&expr);
+    assert_eq!(parts.len(), 2);
+    filter_arg_raw(&parts[0], "OR");
+    word_is(as_not(&parts[1]), "draft");
+}
@@ -0,0 +1,71 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn argument_kind(expr: &Expr) -> ArgumentKind {
+    let (_, arg) = filter_kind(expr);
+    arg.as_ref().expect("missing argument").kind.clone()
+}
+
+fn list(values: &[&str]) -> ArgumentKind {
+    ArgumentKind::List(values.iter().map(|value| value.to_string()).collect())
+}
+
+#[test]
+fn comma_lists_split_name_filters() {
+    assert_eq!(
+        argument_kind(&parse_ok("ext:jpg,png")),
+        list(&["jpg", "png"])
+    );
+    assert_eq!(
+        argument_kind(&parse_ok("type:picture,video")),
+        list(&["picture", "video"])
+    );
+}
+
+#[test]
+fn mixed_separators_split_together() {
+    assert_eq!(
+        argument_kind(&parse_ok("ext:jpg;png,gif")),
+        list(&["jpg", "png", "gif"])
+    );
+}
+
+#[test]
+fn trailing_and_repeated_separators_are_trimmed() {
+    assert_eq!(
+        argument_kind(&parse_ok("ext:jpg,,png;")),
+        list(&["jpg", "png"])
+    );
+    assert_eq!(argument_kind(&parse_ok("ext:jpg,")), ArgumentKind::Bare);
+}
+
+#[test]
+fn single_value_stays_bare() {
+    assert_eq!(argument_kind(&parse_ok("ext:jpg")), ArgumentKind::Bare);
+}
+
+#[test]
+fn commas_in_other_filters_are_kept() {
+    let expr = parse_ok("parent:/Users/demo/a,b");
+    assert_eq!(argument_kind(&expr), ArgumentKind::Bare);
+    let expr = parse_ok("content:one,two");
+    assert_eq!(argument_kind(&expr), ArgumentKind::Bare);
+}
+
+#[test]
+fn separators_are_configurable() {
+    let options = ParseOptions {
+        list_separators: vec![';'],
+        ..Default::default()
+    };
+    let query = parse_query_with("ext:jpg,png", &options).unwrap();
+    assert_eq!(argument_kind(&query.expr), ArgumentKind::Bare);
+
+    let options = ParseOptions {
+        list_separators: Vec::new(),
+        ..Default::default()
+    };
+    let query = parse_query_with("ext:jpg;png", &options).unwrap();
+    assert_eq!(argument_kind(&query.expr), ArgumentKind::Bare);
+}
@@ -17,6 +17,7 @@ fn extract_root(expr: &Expr) -> &'static str {
         Expr::Not(_) => "Not",
         Expr::And(_) => "And",
         Expr::Or(_) => "Or",
+        Expr::Near { .. } => "Near",
     }
 }
 
@@ -0,0 +1,124 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn as_filter(expr: &Expr) -> &Filter {
+    match as_term(expr) {
+        Term::Filter(filter) => filter,
+        other => panic!("expected Filter, got: {other:?}"),
+    }
+}
+
+#[test]
+fn modifiers_parse_to_dedicated_kinds() {
+    for (input, kind) in [
+        ("case:", FilterKind::CaseSensitive),
+        ("nocase:", FilterKind::NoCase),
+        ("wholeword:", FilterKind::WholeWord),
+        ("WW:", FilterKind::WholeWord),
+        ("nowholeword:", FilterKind::NoWholeWord),
+        ("wholefilename:", FilterKind::WholeFilename),
+        ("wfn:", FilterKind::WholeFilename),
+        ("nowholefilename:", FilterKind::NoWholeFilename),
+        ("diacritics:", FilterKind::Diacritics),
+        ("nodiacritics:", FilterKind::NoDiacritics),
+    ] {
+        let expr = parse_ok(input);
+        let filter = as_filter(&expr);
+        assert_eq!(filter.kind, kind, "{input}");
+        assert!(filter.kind.is_modifier(), "{input}");
+        assert_eq!(
+            filter.modifier_scope(),
+            Some(ModifierScope::Toggle),
+            "{input}"
+        );
+    }
+}
+
+#[test]
+fn modifier_with_argument_is_scoped_to_it() {
+    let expr = parse_ok("ww:report");
+    let filter = as_filter(&expr);
+    assert_eq!(filter.kind, FilterKind::WholeWord);
+    assert_eq!(filter.argument.as_ref().unwrap().raw, "report");
+    assert_eq!(filter.modifier_scope(), Some(ModifierScope::Term));
+
+    let expr = parse_ok("diacritics:\"café crème\"");
+    let filter = as_filter(&expr);
+    assert_eq!(filter.kind, FilterKind::Diacritics);
+    assert_eq!(filter.argument.as_ref().unwrap().raw, "café crème");
+    assert_eq!(filter.modifier_scope(), Some(ModifierScope::Term));
+}
+
+#[test]
+fn toggles_compose_with_following_terms() {
+    let expr = parse_raw("wholeword: report ext:pdf");
+    let parts = as_and(&expr);
+    assert_eq!(parts.len(), 3);
+    filter_is_kind(&parts[0], &FilterKind::WholeWord);
+    filter_arg_none(&parts[0]);
+    word_is(&parts[1], "report");
+    filter_is_kind(&parts[2], &FilterKind::Ext);
+
+    let expr = parse_raw("nocase: wfn:Report.txt | ww:draft");
+    let parts = as_and(&expr);
+    filter_is_kind(&parts[0], &FilterKind::NoCase);
+    let alternatives = as_or(&parts[1]);
+    filter_is_kind(&alternatives[0], &FilterKind::WholeFilename);
+    filter_arg_raw(&alternatives[0], "Report.txt");
+    filter_is_kind(&alternatives[1], &FilterKind::WholeWord);
+}
+
+#[test]
+fn other_filters_are_not_modifiers() {
+    for input in ["ext:txt", "size:>1mb", "content:x", "proj:"] {
+        let expr = parse_ok(input);
+        let filter = as_filter(&expr);
+        assert!(!filter.kind.is_modifier(), "{input}");
+        assert_eq!(filter.modifier_scope(), None, "{input}");
+    }
+}
+
+#[test]
+fn toggles_can_trail_other_terms() {
+    let expr = parse_raw("report wfn:");
+    let parts = as_and(&expr);
+    word_is(&parts[0], "report");
+    let toggle = as_filter(&parts[1]);
+    assert_eq!(toggle.kind, FilterKind::WholeFilename);
+    assert_eq!(toggle.modifier_scope(), Some(ModifierScope::Toggle));
+
+    let expr = parse_raw("wholeword: nowholefilename:report");
+    let parts = as_and(&expr);
+    assert_eq!(as_filter(&parts[0]).kind, FilterKind::WholeWord);
+    let scoped = as_filter(&parts[1]);
+    assert_eq!(scoped.kind, FilterKind::NoWholeFilename);
+    assert_eq!(scoped.modifier_scope(), Some(ModifierScope::Term));
+}
+
+#[test]
+fn optimizer_keeps_terms_on_their_side_of_toggles() {
+    let expr = parse_ok("ext:txt report case: ext:pdf draft");
+    let parts = as_and(&expr);
+    word_is(&parts[0], "report");
+    filter_is_kind(&parts[1], &FilterKind::Ext);
+    filter_is_kind(&parts[2], &FilterKind::CaseSensitive);
+    word_is(&parts[3], "draft");
+    filter_is_kind(&parts[4], &FilterKind::Ext);
+
+    // A group holding a toggle keeps its own scope.
+    let expr = parse_ok("report (wfn: draft) notes");
+    let parts = as_and(&expr);
+    assert_eq!(parts.len(), 3);
+    word_is(&parts[0], "report");
+    let group = as_and(&parts[1]);
+    filter_is_kind(&group[0], &FilterKind::WholeFilename);
+    word_is(&group[1], "draft");
+    word_is(&parts[2], "notes");
+
+    // Arguments scope a modifier to their own term, so those still move.
+    let expr = parse_ok("ww:draft report");
+    let parts = as_and(&expr);
+    word_is(&parts[0], "report");
+    filter_is_kind(&parts[1], &FilterKind::WholeWord);
+}
@@ -0,0 +1,118 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn as_near(expr: &Expr) -> (&Expr, &Expr, u32) {
+    match expr {
+        Expr::Near {
+            left,
+            right,
+            distance,
+        } => (left, right, *distance),
+        other => panic!("expected Near, got: {other:?}"),
+    }
+}
+
+#[test]
+fn near_with_explicit_distance() {
+    let expr = parse_ok("a NEAR/2 b");
+    let (left, right, distance) = as_near(&expr);
+    word_is(left, "a");
+    word_is(right, "b");
+    assert_eq!(distance, 2);
+}
+
+#[test]
+fn bare_near_uses_default_distance() {
+    let expr = parse_ok("a NEAR b");
+    let (left, right, distance) = as_near(&expr);
+    word_is(left, "a");
+    word_is(right, "b");
+    assert_eq!(distance, DEFAULT_NEAR_DISTANCE);
+}
+
+#[test]
+fn or_binds_tighter_than_near() {
+    let expr = parse_ok("a|b NEAR/1 c");
+    let (left, right, _) = as_near(&expr);
+    let or_parts = as_or(left);
+    word_is(&or_parts[0], "a");
+    word_is(&or_parts[1], "b");
+    word_is(right, "c");
+
+    let expr = parse_ok("a NEAR/1 b|c");
+    let (left, right, _) = as_near(&expr);
+    word_is(left, "a");
+    let or_parts = as_or(right);
+    word_is(&or_parts[0], "b");
+    word_is(&or_parts[1], "c");
+}
+
+#[test]
+fn near_takes_neighbouring_terms_only() {
+    let expr = parse_ok("x a NEAR b y");
+    let parts = as_and(&expr);
+    assert_eq!(parts.len(), 3);
+    word_is(&parts[0], "x");
+    let (left, right, _) = as_near(&parts[1]);
+    word_is(left, "a");
+    word_is(right, "b");
+    word_is(&parts[2], "y");
+}
+
+#[test]
+fn near_chains_left_to_right() {
+    let expr = parse_ok("a NEAR/1 b NEAR/4 c");
+    let (left, right, distance) = as_near(&expr);
+    assert_eq!(distance, 4);
+    word_is(right, "c");
+    let (inner_left, inner_right, inner_distance) = as_near(left);
+    word_is(inner_left, "a");
+    word_is(inner_right, "b");
+    assert_eq!(inner_distance, 1);
+}
+
+#[test]
+fn near_composes_with_groups() {
+    let expr = parse_ok("<error|warn> NEAR/2 timeout");
+    let (left, right, _) = as_near(&expr);
+    assert_eq!(as_or(left).len(), 2);
+    word_is(right, "timeout");
+
+    let expr = parse_ok("x (a NEAR b)");
+    let parts = as_and(&expr);
+    word_is(&parts[0], "x");
+    as_near(&parts[1]);
+}
+
+#[test]
+fn optimizer_keeps_near_nodes() {
+    let expr = parse_ok("(a b) NEAR/2 (c)");
+    let (left, right, distance) = as_near(&expr);
+    assert_eq!(as_and(left).len(), 2);
+    word_is(right, "c");
+    assert_eq!(distance, 2);
+}
+
+#[test]
+fn near_lookalikes_stay_words() {
+    word_is(&parse_ok("near"), "near");
+    word_is(&parse_ok("NEAR.txt"), "NEAR.txt");
+    word_is(&parse_ok("NEARBY"), "NEARBY");
+    let expr = parse_ok("a near b");
+    assert_eq!(as_and(&expr).len(), 3);
+    let expr = parse_ok("a NEAR/x b");
+    word_is(&as_and(&expr)[1], "NEAR/x");
+}
+
+#[test]
+fn near_missing_operands_are_empty() {
+    let expr = parse_raw("NEAR b");
+    let (left, right, _) = as_near(&expr);
+    assert!(is_empty(left));
+    word_is(right, "b");
+    let expr = parse_raw("a NEAR");
+    let (left, right, _) = as_near(&expr);
+    word_is(left, "a");
+    assert!(is_empty(right));
+}
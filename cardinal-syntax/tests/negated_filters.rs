@@ -0,0 +1,64 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn folded(input: &str) -> Expr {
+    fold_negated_filters(parse_query(input).expect("parse ok")).expr
+}
+
+fn as_filter(expr: &Expr) -> &Filter {
+    match as_term(expr) {
+        Term::Filter(filter) => filter,
+        other => panic!("expected Filter, got: {other:?}"),
+    }
+}
+
+#[test]
+fn negated_filter_folds_into_flag() {
+    let expr = folded("!ext:tmp");
+    let filter = as_filter(&expr);
+    assert!(filter.negated);
+    assert_eq!(filter.kind, FilterKind::Ext);
+    filter_arg_raw(&expr, "tmp");
+}
+
+#[test]
+fn parser_keeps_expression_level_not() {
+    let expr = parse_raw("!ext:tmp");
+    assert!(!as_filter(as_not(&expr)).negated);
+}
+
+#[test]
+fn boolean_subtrees_keep_their_not() {
+    let expr = folded("!(a b)");
+    let parts = as_and(as_not(&expr));
+    word_is(&parts[0], "a");
+    word_is(&parts[1], "b");
+
+    let expr = folded("!<ext:tmp|ext:bak>");
+    let parts = as_or(as_not(&expr));
+    assert!(parts.iter().all(|part| !as_filter(part).negated));
+}
+
+#[test]
+fn negated_words_keep_their_not() {
+    let expr = folded("report !draft");
+    let parts = as_and(&expr);
+    word_is(as_not(&parts[1]), "draft");
+}
+
+#[test]
+fn folds_filters_nested_in_boolean_chains() {
+    let expr = folded("report !ext:tmp !folder: | size:>1mb");
+    let parts = as_and(&expr);
+    word_is(&parts[0], "report");
+    assert!(as_filter(&parts[1]).negated);
+    let alternatives = as_or(&parts[2]);
+    assert!(as_filter(&alternatives[0]).negated);
+    assert!(!as_filter(&alternatives[1]).negated);
+}
+
+#[test]
+fn explain_shows_folded_negation() {
+    assert_eq!(explain(&folded("a !ext:tmp")), "(a AND NOT ext:tmp)");
+}
@@ -0,0 +1,98 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn argument(input: &str) -> ArgumentKind {
+    let expr = parse_ok(input);
+    let (_, arg) = filter_kind(&expr);
+    arg.as_ref().expect("missing argument").kind.clone()
+}
+
+#[test]
+fn integer_comparisons_expose_their_value() {
+    let cases = [
+        ("width:>4000", FilterKind::Width, ComparisonOp::Gt, 4000),
+        ("height:<=2000", FilterKind::Height, ComparisonOp::Lte, 2000),
+        ("bitdepth:>=24", FilterKind::BitDepth, ComparisonOp::Gte, 24),
+        ("width:>4,000", FilterKind::Width, ComparisonOp::Gt, 4000),
+    ];
+    for (input, kind, op, value) in cases {
+        let expr = parse_ok(input);
+        filter_is_kind(&expr, &kind);
+        let ArgumentKind::Comparison(comparison) = argument(input) else {
+            panic!("expected comparison for {input}");
+        };
+        assert_eq!(comparison.op, op, "{input}");
+        assert_eq!(comparison.as_u64(), Some(value), "{input}");
+        // The typed value sits next to the text, which stays as typed.
+        filter_arg_raw(&expr, input.split_once(':').unwrap().1);
+    }
+}
+
+#[test]
+fn bare_integer_stays_bare() {
+    let expr = parse_ok("bitdepth:24");
+    filter_is_kind(&expr, &FilterKind::BitDepth);
+    assert_eq!(argument("bitdepth:24"), ArgumentKind::Bare);
+    filter_arg_raw(&expr, "24");
+}
+
+#[test]
+fn integer_ranges_expose_their_endpoints() {
+    let ArgumentKind::Range(range) = argument("width:1000..2000") else {
+        panic!("expected range");
+    };
+    assert_eq!(range.as_u64(), Some((Some(1000), Some(2000))));
+
+    // Unoptimized, the optimizer turns open ranges into comparisons.
+    let expr = parse_raw("height:..1080");
+    let (_, arg) = filter_kind(&expr);
+    let ArgumentKind::Range(range) = &arg.as_ref().unwrap().kind else {
+        panic!("expected range");
+    };
+    assert_eq!(range.as_u64(), Some((None, Some(1080))));
+}
+
+#[test]
+fn non_integer_values_are_errors_at_the_argument() {
+    for input in [
+        "width:>x",
+        "width:>abc",
+        "height:<=2k",
+        "bitdepth:>-8",
+        "width:1k..2k",
+        "height:+1..10",
+        "bitdepth:deep",
+    ] {
+        let error = parse_err(input);
+        let (name, value) = input.split_once(':').unwrap();
+        assert_eq!(
+            error.message,
+            format!("{name}: expects a whole number, found '{value}'"),
+            "{input}"
+        );
+        assert_eq!(error.position, name.len() + 1, "{input}");
+    }
+
+    let error = parse_err("photo width:>abc");
+    assert_eq!(error.position, "photo width:".len());
+}
+
+#[test]
+fn quoted_integer_filter_values_stay_phrases() {
+    assert_eq!(argument("width:\"abc\""), ArgumentKind::Phrase);
+}
+
+#[test]
+fn other_numeric_filters_keep_units() {
+    let ArgumentKind::Comparison(comparison) = argument("size:>1GB") else {
+        panic!("expected comparison");
+    };
+    assert_eq!(comparison.value, "1GB");
+    assert_eq!(comparison.as_u64(), None);
+
+    let ArgumentKind::Range(range) = argument("size:1mb..10mb") else {
+        panic!("expected range");
+    };
+    assert_eq!(range.as_u64(), None);
+}
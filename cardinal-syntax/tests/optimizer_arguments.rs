@@ -12,10 +12,10 @@ fn block_07_arguments_shapes() {
     filter_arg_is_range_dots(&a2, Some("1"), Some("10"));
     let a3 = parse_ok("size:..10");
     filter_is_kind(&a3, &FilterKind::Size);
-    filter_arg_is_range_dots(&a3, None, Some("10"));
+    filter_arg_is_comparison(&a3, ComparisonOp::Lte, "10");
     let a4 = parse_ok("size:1..");
     filter_is_kind(&a4, &FilterKind::Size);
-    filter_arg_is_range_dots(&a4, Some("1"), None);
+    filter_arg_is_comparison(&a4, ComparisonOp::Gte, "1");
     let a5 = parse_ok("dc:2021/01/01-2021/02/01");
     filter_is_kind(&a5, &FilterKind::DateCreated);
     let a6 = parse_ok("dm:2020/1/1-2020/12/31");
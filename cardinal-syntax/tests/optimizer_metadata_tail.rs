@@ -73,3 +73,41 @@ fn metadata_tail_preserves_non_metadata_order() {
     filter_is_kind(&p[l - 2], &FilterKind::DateModified);
     filter_is_kind(&p[l - 1], &FilterKind::DateCreated);
 }
+
+#[test]
+fn content_filters_run_after_other_filters() {
+    let e = parse_ok("content:todo ext:rs report content:fixme size:>1kb");
+    let p = as_and(&e);
+    word_is(&p[0], "report");
+    filter_is_kind(&p[1], &FilterKind::Ext);
+    filter_is_kind(&p[2], &FilterKind::Size);
+    filter_is_kind(&p[3], &FilterKind::Content);
+    filter_is_kind(&p[4], &FilterKind::Content);
+    assert_eq!(
+        p[3],
+        Expr::Term(Term::Filter(Filter {
+            kind: FilterKind::Content,
+            argument: Some(FilterArgument {
+                raw: "todo".into(),
+                kind: ArgumentKind::Bare,
+            }),
+            negated: false,
+        }))
+    );
+
+    let e = parse_ok("ext:rs content:todo");
+    let p = as_and(&e);
+    filter_is_kind(&p[0], &FilterKind::Ext);
+    filter_is_kind(&p[1], &FilterKind::Content);
+}
+
+#[test]
+fn duplicate_filters_run_last() {
+    let e = parse_ok("dupe: content:todo report sizedupe: ext:rs");
+    let p = as_and(&e);
+    word_is(&p[0], "report");
+    filter_is_kind(&p[1], &FilterKind::Ext);
+    filter_is_kind(&p[2], &FilterKind::Content);
+    filter_is_kind(&p[3], &FilterKind::Duplicate);
+    filter_is_kind(&p[4], &FilterKind::SizeDuplicate);
+}
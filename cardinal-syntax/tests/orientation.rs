@@ -0,0 +1,56 @@
+mod common;
+use cardinal_syntax::*;
+use common::*;
+
+fn orientation_argument(input: &str) -> ArgumentKind {
+    let expr = parse_ok(input);
+    filter_is_kind(&expr, &FilterKind::Orientation);
+    let (_, arg) = filter_kind(&expr);
+    arg.as_ref().expect("missing argument").kind.clone()
+}
+
+#[test]
+fn known_orientations_are_classified() {
+    assert_eq!(
+        orientation_argument("orientation:horizontal"),
+        ArgumentKind::Orientation(Orientation::Horizontal)
+    );
+    assert_eq!(
+        orientation_argument("orientation:vertical"),
+        ArgumentKind::Orientation(Orientation::Vertical)
+    );
+    assert_eq!(
+        orientation_argument("orientation:square"),
+        ArgumentKind::Orientation(Orientation::Square)
+    );
+}
+
+#[test]
+fn orientation_names_ignore_case() {
+    assert_eq!(
+        orientation_argument("orientation:HORIZONTAL"),
+        ArgumentKind::Orientation(Orientation::Horizontal)
+    );
+    assert_eq!(
+        orientation_argument("ORIENTATION:Square"),
+        ArgumentKind::Orientation(Orientation::Square)
+    );
+}
+
+#[test]
+fn unknown_orientations_stay_bare() {
+    assert_eq!(
+        orientation_argument("orientation:diagonal"),
+        ArgumentKind::Bare
+    );
+    assert_eq!(
+        orientation_argument("orientation:\"vertical\""),
+        ArgumentKind::Phrase
+    );
+    // Other filters keep orientation names as plain text.
+    let expr = parse_ok("folder:square");
+    assert_eq!(
+        filter_kind(&expr).1.as_ref().unwrap().kind,
+        ArgumentKind::Bare
+    );
+}
@@ -31,11 +31,11 @@ fn parses_unc_paths_and_child_filter() {
 #[test]
 fn parses_windows_drive_roots_and_dirs() {
     let expr = parse_ok("D:");
-    filter_is_custom(&expr, "D");
+    filter_is_kind(&expr, &cardinal_syntax::FilterKind::Drive('D'));
     filter_arg_none(&expr);
 
     let expr = parse_ok(r"D:\\Music\\");
-    filter_is_custom(&expr, "D");
+    filter_is_kind(&expr, &cardinal_syntax::FilterKind::Drive('D'));
     filter_arg_raw(&expr, r"\\Music\\");
 }
 
@@ -49,3 +49,67 @@ fn parses_env_expanded_style_segments_as_words() {
         other => panic!("unexpected {other:?}"),
     }
 }
+
+#[test]
+fn path_filters_keep_embedded_colons() {
+    use cardinal_syntax::FilterKind;
+
+    for (input, kind, raw) in [
+        ("infolder:/a:b/c", FilterKind::InFolder, "/a:b/c"),
+        ("infolder:a:b", FilterKind::InFolder, "a:b"),
+        (r"parent:C:\Windows", FilterKind::Parent, r"C:\Windows"),
+        (
+            r"parent:C:\Win:dows\x",
+            FilterKind::Parent,
+            r"C:\Win:dows\x",
+        ),
+        (
+            r"nosubfolders:\\srv\share:x",
+            FilterKind::NoSubfolders,
+            r"\\srv\share:x",
+        ),
+        ("path:/Volumes/a:b/c", FilterKind::Path, "/Volumes/a:b/c"),
+    ] {
+        let expr = parse_raw(input);
+        filter_is_kind(&expr, &kind);
+        filter_arg_raw(&expr, raw);
+    }
+}
+
+#[test]
+fn path_filter_arguments_stop_at_boundaries() {
+    use cardinal_syntax::FilterKind;
+
+    let expr = parse_raw(r"parent:C:\Windows ext:dll");
+    let parts = as_and(&expr);
+    filter_arg_raw(&parts[0], r"C:\Windows");
+    filter_is_kind(&parts[1], &FilterKind::Ext);
+
+    let expr = parse_raw("infolder:/a:b|nosubfolders:/c:d");
+    let parts = as_or(&expr);
+    filter_arg_raw(&parts[0], "/a:b");
+    filter_arg_raw(&parts[1], "/c:d");
+
+    let expr = parse_raw("<infolder:/a:b> report");
+    let parts = as_and(&expr);
+    filter_arg_raw(&parts[0], "/a:b");
+    word_is(&parts[1], "report");
+
+    // A space still separates the filter from the next one.
+    let expr = parse_raw("parent: ext:txt");
+    let parts = as_and(&expr);
+    filter_arg_none(&parts[0]);
+    filter_is_kind(&parts[1], &FilterKind::Ext);
+}
+
+#[test]
+fn other_filters_still_stop_at_the_next_filter() {
+    use cardinal_syntax::FilterKind;
+
+    let expr = parse_raw("ext:a:b");
+    let parts = as_and(&expr);
+    filter_is_kind(&parts[0], &FilterKind::Ext);
+    filter_arg_none(&parts[0]);
+    filter_is_kind(&parts[1], &FilterKind::Custom("a".into()));
+    filter_arg_raw(&parts[1], "b");
+}
@@ -61,3 +61,37 @@ fn mixing_words_and_phrases_in_and() {
     phrase_is(&parts[1], "bar baz");
     word_is(&parts[2], "qux");
 }
+
+#[test]
+fn wildcard_words_are_flagged() {
+    let term = |query: &str| match parse_ok(query) {
+        Expr::Term(term) => term,
+        other => panic!("unexpected: {other:?}"),
+    };
+    for query in ["*.mp3", "a?b", "src/*", "report*"] {
+        assert!(term(query).is_wildcard(), "{query}");
+    }
+    for query in ["report", "file.txt", "\"*.mp3\"", "regex:a*b", "ext:mp3"] {
+        assert!(!term(query).is_wildcard(), "{query}");
+    }
+}
+
+#[test]
+fn wildcard_stars_record_separator_scope() {
+    let stars = |query: &str| match parse_ok(query) {
+        Expr::Term(term) => term
+            .wildcard_stars()
+            .into_iter()
+            .map(|star| (star.offset, star.crosses_separators))
+            .collect::<Vec<_>>(),
+        other => panic!("unexpected: {other:?}"),
+    };
+    assert_eq!(stars("src/*/test.rs"), [(4, false)]);
+    assert_eq!(stars("src/**/test.rs"), [(4, true)]);
+    assert_eq!(stars("*.rs"), [(0, false)]);
+    assert_eq!(stars("a*/b/**/c*"), [(1, false), (5, true), (9, false)]);
+    assert_eq!(stars("src/***"), [(4, true)]);
+    assert_eq!(stars("données/*"), [(9, false)]);
+    assert!(stars("a?b").is_empty());
+    assert!(stars("\"src/*\"").is_empty());
+}
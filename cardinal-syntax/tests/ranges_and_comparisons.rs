@@ -16,12 +16,40 @@ fn dotted_open_ended_ranges() {
         ("size:1mb..", Some("1mb"), None),
     ];
     for (q, s, e) in cases {
-        let expr = parse_ok(q);
+        let expr = parse_raw(q);
         filter_is_kind(&expr, &FilterKind::Size);
         filter_arg_is_range_dots(&expr, s, e);
     }
 }
 
+#[test]
+fn optimizer_turns_open_ended_ranges_into_comparisons() {
+    let cases = [
+        ("size:..10mb", ComparisonOp::Lte, "10mb"),
+        ("size:1mb..", ComparisonOp::Gte, "1mb"),
+        ("dm:..2024/12/31", ComparisonOp::Lte, "2024/12/31"),
+        ("width:1920..", ComparisonOp::Gte, "1920"),
+    ];
+    for (q, op, value) in cases {
+        let expr = parse_ok(q);
+        filter_arg_is_comparison(&expr, op, value);
+        // The argument text stays as typed.
+        filter_arg_raw(&expr, q.split_once(':').unwrap().1);
+    }
+
+    let expr = parse_ok("report !size:..10mb");
+    let parts = as_and(&expr);
+    filter_arg_is_comparison(as_not(&parts[1]), ComparisonOp::Lte, "10mb");
+}
+
+#[test]
+fn optimizer_keeps_ranges_with_both_endpoints() {
+    let expr = parse_ok("size:1mb..10mb");
+    filter_arg_is_range_dots(&expr, Some("1mb"), Some("10mb"));
+    let expr = parse_ok("dc:2014/8/1-2014/8/31");
+    filter_arg_is_range_hyphen(&expr, "2014/8/1", "2014/8/31");
+}
+
 #[test]
 fn hyphenated_ranges_for_dates() {
     let expr = parse_ok("dc:2014/8/1-2014/8/31");
@@ -58,3 +86,72 @@ fn lone_operator_is_treated_as_bare_argument() {
     filter_is_kind(&expr, &FilterKind::Size);
     filter_arg_raw(&expr, ">");
 }
+
+#[test]
+fn parsed_ranges_include_both_endpoints() {
+    for q in ["size:1mb..10mb", "size:..10mb", "dc:2014/8/1-2014/8/31"] {
+        let expr = parse_raw(q);
+        let (_, argument) = filter_kind(&expr);
+        let ArgumentKind::Range(range) = &argument.as_ref().unwrap().kind else {
+            panic!("expected range for {q}");
+        };
+        assert!(range.inclusive_start, "{q}");
+        assert!(range.inclusive_end, "{q}");
+    }
+}
+
+#[test]
+fn optimizer_keeps_exclusive_endpoints_strict() {
+    for (q, inclusive, op) in [
+        ("size:..10mb", true, ComparisonOp::Lte),
+        ("size:..10mb", false, ComparisonOp::Lt),
+        ("size:1mb..", true, ComparisonOp::Gte),
+        ("size:1mb..", false, ComparisonOp::Gt),
+    ] {
+        let mut query = parse_query(q).unwrap();
+        let Expr::Term(Term::Filter(filter)) = &mut query.expr else {
+            panic!("expected filter");
+        };
+        let ArgumentKind::Range(range) = &mut filter.argument.as_mut().unwrap().kind else {
+            panic!("expected range");
+        };
+        range.inclusive_start = inclusive;
+        range.inclusive_end = inclusive;
+        let expr = optimize_query(query).expr;
+        filter_arg_is_comparison(&expr, op, q[5..].trim_matches('.'));
+    }
+}
+
+#[test]
+fn numeric_values_drop_digit_group_separators() {
+    let expr = parse_raw("size:>1,000");
+    filter_arg_raw(&expr, ">1,000");
+    filter_arg_is_comparison(&expr, ComparisonOp::Gt, "1000");
+
+    let expr = parse_raw("size:1_000_000");
+    filter_arg_raw(&expr, "1_000_000");
+    let (_, argument) = filter_kind(&expr);
+    assert!(matches!(
+        argument.as_ref().unwrap().kind,
+        ArgumentKind::Bare
+    ));
+    assert_eq!(strip_digit_separators("1_000_000"), "1000000");
+
+    let expr = parse_raw("size:1,000kb..2_000kb");
+    filter_arg_is_range_dots(&expr, Some("1000kb"), Some("2000kb"));
+
+    let expr = parse_raw("dimensions:>=1,920x1_080");
+    let (_, argument) = filter_kind(&expr);
+    assert_eq!(
+        argument.as_ref().unwrap().kind,
+        ArgumentKind::Dimensions {
+            op: Some(ComparisonOp::Gte),
+            width: 1920,
+            height: 1080
+        }
+    );
+
+    // Text filters keep their value verbatim.
+    let expr = parse_raw("parent:>1,000");
+    filter_arg_is_comparison(&expr, ComparisonOp::Gt, "1,000");
+}
@@ -0,0 +1,52 @@
+use cardinal_syntax::*;
+
+fn messages(errors: &[ParseError]) -> Vec<(&str, usize)> {
+    errors
+        .iter()
+        .map(|err| (err.message.as_str(), err.position))
+        .collect()
+}
+
+#[test]
+fn reports_every_error_in_one_pass() {
+    let input = "(foo <bar \"baz";
+    let (_, errors) = parse_query_recovering(input);
+    assert_eq!(
+        messages(&errors),
+        [
+            ("missing closing quote", 10),
+            ("expected '>'", input.len()),
+            ("expected ')'", input.len()),
+        ]
+    );
+    assert_eq!(parse_query(input).unwrap_err(), errors[0]);
+}
+
+#[test]
+fn failed_terms_become_empty_placeholders() {
+    let (query, errors) = parse_query_recovering("foo ) bar");
+    assert_eq!(messages(&errors), [("unexpected closing delimiter", 4)]);
+    assert_eq!(
+        query.expr,
+        Expr::And(vec![
+            Expr::Term(Term::Word("foo".into())),
+            Expr::Empty,
+            Expr::Term(Term::Word("bar".into())),
+        ])
+    );
+}
+
+#[test]
+fn happy_path_matches_parse_query() {
+    for input in [
+        "",
+        "foo bar",
+        "a|b !c",
+        "<ext:jpg;png | folder:> size:>1mb",
+        "parent:\"/Users/demo\" regex:^foo$",
+    ] {
+        let (query, errors) = parse_query_recovering(input);
+        assert!(errors.is_empty(), "{input}: {errors:?}");
+        assert_eq!(query, parse_query(input).unwrap(), "{input}");
+    }
+}
@@ -13,12 +13,15 @@ fn keyword_boundaries_do_not_consume_neighbors() {
         "barORbaz",
         "bar ORbaz",
         "barOR baz",
+        "fooNEAR bar",
+        "foo NEARbar",
     ];
 
     fn assert_no_keyword_nodes(expr: &Expr) {
         match expr {
             Expr::Not(_) => panic!("unexpected NOT node from keyword boundary"),
             Expr::Or(_) => panic!("unexpected OR node from keyword boundary"),
+            Expr::Near { .. } => panic!("unexpected NEAR node from keyword boundary"),
             Expr::And(parts) => parts.iter().for_each(assert_no_keyword_nodes),
             Expr::Term(_) | Expr::Empty => {}
         }
@@ -87,8 +90,8 @@ fn filters_interleave_with_terms_and_groups() {
     // <D:|E:>
     let or_parts = as_or(&parts[2]);
     assert_eq!(or_parts.len(), 2);
-    filter_is_custom(&or_parts[0], "D");
-    filter_is_custom(&or_parts[1], "E");
+    filter_is_kind(&or_parts[0], &FilterKind::Drive('D'));
+    filter_is_kind(&or_parts[1], &FilterKind::Drive('E'));
     // baz
     word_is(&parts[3], "baz");
     // filters land at the tail
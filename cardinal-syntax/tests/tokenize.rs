@@ -0,0 +1,90 @@
+use cardinal_syntax::{Token, TokenKind, tokenize};
+
+fn spans(input: &str) -> Vec<(TokenKind, &str)> {
+    tokenize(input)
+        .into_iter()
+        .map(|Token { kind, span }| (kind, &input[span]))
+        .collect()
+}
+
+#[test]
+fn tokenizes_filters_phrases_and_groups() {
+    let tokens = tokenize(r#"folder: dm:today "x y" (a|b)"#);
+    let expected = [
+        (TokenKind::FilterName, 0..6),
+        (TokenKind::Colon, 6..7),
+        (TokenKind::Whitespace, 7..8),
+        (TokenKind::FilterName, 8..10),
+        (TokenKind::Colon, 10..11),
+        (TokenKind::Argument, 11..16),
+        (TokenKind::Whitespace, 16..17),
+        (TokenKind::Phrase, 17..22),
+        (TokenKind::Whitespace, 22..23),
+        (TokenKind::GroupOpen, 23..24),
+        (TokenKind::Word, 24..25),
+        (TokenKind::Operator, 25..26),
+        (TokenKind::Word, 26..27),
+        (TokenKind::GroupClose, 27..28),
+    ]
+    .map(|(kind, span)| Token { kind, span });
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn keywords_are_operators() {
+    assert_eq!(
+        spans("!a OR NOT b NEAR/3 c"),
+        [
+            (TokenKind::Operator, "!"),
+            (TokenKind::Word, "a"),
+            (TokenKind::Whitespace, " "),
+            (TokenKind::Operator, "OR"),
+            (TokenKind::Whitespace, " "),
+            (TokenKind::Operator, "NOT"),
+            (TokenKind::Whitespace, " "),
+            (TokenKind::Word, "b"),
+            (TokenKind::Whitespace, " "),
+            (TokenKind::Operator, "NEAR/3"),
+            (TokenKind::Whitespace, " "),
+            (TokenKind::Word, "c"),
+        ]
+    );
+}
+
+#[test]
+fn quoted_arguments_and_regex_patterns() {
+    assert_eq!(
+        spans(r#"parent:"/a b" regex: ^x$"#),
+        [
+            (TokenKind::FilterName, "parent"),
+            (TokenKind::Colon, ":"),
+            (TokenKind::Phrase, r#""/a b""#),
+            (TokenKind::Whitespace, " "),
+            (TokenKind::FilterName, "regex"),
+            (TokenKind::Colon, ":"),
+            (TokenKind::Whitespace, " "),
+            (TokenKind::Argument, "^x$"),
+        ]
+    );
+}
+
+#[test]
+fn malformed_input_still_covers_every_byte() {
+    for input in [r#"ext:"rs"#, "regex:", "a) (b", r#""open"#, "ä|ö"] {
+        let tokens = tokenize(input);
+        let mut end = 0;
+        for token in &tokens {
+            assert_eq!(token.span.start, end, "{input}: {tokens:?}");
+            end = token.span.end;
+        }
+        assert_eq!(end, input.len(), "{input}: {tokens:?}");
+    }
+    assert_eq!(
+        spans(r#"ext:"rs"#),
+        [
+            (TokenKind::FilterName, "ext"),
+            (TokenKind::Colon, ":"),
+            (TokenKind::Phrase, r#""rs"#),
+        ]
+    );
+}
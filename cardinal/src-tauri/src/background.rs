@@ -17,13 +17,16 @@ use std::{
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use tauri::{AppHandle, Emitter};
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct StatusBarUpdate {
     pub scanned_files: usize,
     pub processed_events: usize,
+    /// Indexing progress in percent, `None` while a first walk has nothing to
+    /// compare against.
+    pub progress: Option<u8>,
 }
 
 #[derive(Serialize, Clone)]
@@ -36,7 +39,6 @@ pub struct IconPayload {
 pub struct BackgroundLoopChannels {
     pub finish_rx: Receiver<Sender<Option<SearchCache>>>,
     pub search_rx: Receiver<SearchJob>,
-    pub result_tx: Sender<Result<SearchOutcome>>,
     pub node_info_rx: Receiver<Vec<SlabIndex>>,
     pub node_info_results_tx: Sender<Vec<SearchResultNode>>,
     pub icon_viewport_rx: Receiver<(u64, Vec<SlabIndex>)>,
@@ -48,6 +50,7 @@ pub fn emit_status_bar_update(
     app_handle: &AppHandle,
     scanned_files: usize,
     processed_events: usize,
+    progress: Option<u8>,
 ) {
     app_handle
         .emit(
@@ -55,11 +58,36 @@ pub fn emit_status_bar_update(
             StatusBarUpdate {
                 scanned_files,
                 processed_events,
+                progress,
             },
         )
         .unwrap();
 }
 
+/// Estimates walk progress from the node count of the previous index. Stays
+/// below 100 until the walk actually finishes, as the tree may have grown.
+pub fn estimate_progress(previous_total: Option<usize>, scanned: usize) -> Option<u8> {
+    let previous_total = previous_total.filter(|&total| total > 0)?;
+    Some((scanned.saturating_mul(100) / previous_total).min(99) as u8)
+}
+
+/// Takes the searches already queued behind `job` without waiting for more and
+/// returns the newest, answering the superseded ones as cancelled. A burst of
+/// keystrokes that piled up while a search ran thus only runs the last query,
+/// and the loop stays free to serve its other channels.
+fn newest_search(mut job: SearchJob, search_rx: &Receiver<SearchJob>) -> SearchJob {
+    for mut other in search_rx.try_iter() {
+        if other.id > job.id {
+            std::mem::swap(&mut job, &mut other);
+        }
+        let _ = other.reply_tx.send(Ok(SearchOutcome {
+            nodes: None,
+            highlights: Vec::new(),
+        }));
+    }
+    job
+}
+
 struct EventSnapshot {
     path: PathBuf,
     event_id: u64,
@@ -82,12 +110,12 @@ pub fn run_background_event_loop(
     mut event_watcher: EventWatcher,
     channels: BackgroundLoopChannels,
     watch_root: &str,
+    ignore_paths: &[PathBuf],
     fse_latency_secs: f64,
 ) {
     let BackgroundLoopChannels {
         finish_rx,
         search_rx,
-        result_tx,
         node_info_rx,
         node_info_results_tx,
         icon_viewport_rx,
@@ -104,14 +132,18 @@ pub fn run_background_event_loop(
                 return;
             }
             recv(search_rx) -> job => {
+                let job = newest_search(job.expect("Search channel closed"), &search_rx);
                 let SearchJob {
                     query,
                     options,
                     cancellation_token,
-                } = job.expect("Search channel closed");
+                    reply_tx,
+                    ..
+                } = job;
                 let opts = SearchOptions::from(options);
-                let payload = cache.search_with_options(&query, opts, cancellation_token);
-                result_tx.send(payload).expect("Failed to send result");
+                let payload = cache.search_with_options(&query, &opts, cancellation_token);
+                // The caller may have given up waiting.
+                let _ = reply_tx.send(payload);
             }
             recv(node_info_rx) -> results => {
                 let results = results.expect("Node info channel closed");
@@ -156,6 +188,7 @@ pub fn run_background_event_loop(
                     &mut cache,
                     &mut event_watcher,
                     watch_root,
+                    ignore_paths,
                     fse_latency_secs,
                     &mut history_ready,
                 );
@@ -164,7 +197,12 @@ pub fn run_background_event_loop(
                 let events = events.expect("Event stream closed");
                 processed_events += events.len();
 
-                emit_status_bar_update(app_handle, cache.get_total_files(), processed_events);
+                emit_status_bar_update(
+                    app_handle,
+                    cache.get_total_files(),
+                    processed_events,
+                    Some(100),
+                );
 
                 let mut snapshots = Vec::with_capacity(events.len());
                 for event in events.iter() {
@@ -189,6 +227,7 @@ pub fn run_background_event_loop(
                         &mut cache,
                         &mut event_watcher,
                         watch_root,
+                        ignore_paths,
                         fse_latency_secs,
                         &mut history_ready,
                     );
@@ -207,12 +246,17 @@ fn perform_rescan(
     cache: &mut SearchCache,
     event_watcher: &mut EventWatcher,
     watch_root: &str,
+    ignore_paths: &[PathBuf],
     fse_latency_secs: f64,
     history_ready: &mut bool,
 ) {
+    if !event_watcher.stop(Duration::from_secs(5)) {
+        warn!("Event watcher did not stop before rescan");
+    }
     *event_watcher = EventWatcher::noop();
     update_app_state(app_handle, AppLifecycleState::Initializing);
-    emit_status_bar_update(app_handle, 0, 0);
+    let previous_total = Some(cache.get_total_files());
+    emit_status_bar_update(app_handle, 0, 0, estimate_progress(previous_total, 0));
     *history_ready = false;
 
     let walk_data = cache.walk_data();
@@ -223,7 +267,12 @@ fn perform_rescan(
                 let dirs = walk_data.num_dirs.load(Ordering::Relaxed);
                 let files = walk_data.num_files.load(Ordering::Relaxed);
                 let total = dirs + files;
-                emit_status_bar_update(app_handle, total, 0);
+                emit_status_bar_update(
+                    app_handle,
+                    total,
+                    0,
+                    estimate_progress(previous_total, total),
+                );
                 std::thread::sleep(Duration::from_millis(100));
             }
         });
@@ -232,12 +281,16 @@ fn perform_rescan(
         walking_done.store(true, Ordering::Relaxed);
         stopped
     });
+    if !stopped {
+        emit_status_bar_update(app_handle, cache.get_total_files(), 0, Some(100));
+    }
 
     *event_watcher = if stopped {
         EventWatcher::noop()
     } else {
         EventWatcher::spawn(
-            watch_root.to_string(),
+            vec![watch_root.to_string()],
+            ignore_paths.to_vec(),
             cache.last_event_id(),
             fse_latency_secs,
         )
@@ -276,3 +329,62 @@ fn forward_new_events(app_handle: &AppHandle, snapshots: &[EventSnapshot]) {
 
     let _ = app_handle.emit("fs_events_batch", new_events);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate_progress, newest_search};
+    use crate::commands::{SearchJob, SearchOptionsPayload};
+    use crossbeam_channel::{Receiver, unbounded};
+    use search_cache::SearchOutcome;
+    use search_cancel::CancellationToken;
+
+    fn job(id: u64) -> (SearchJob, Receiver<anyhow::Result<SearchOutcome>>) {
+        let (reply_tx, reply_rx) = unbounded();
+        let job = SearchJob {
+            id,
+            query: format!("query {id}"),
+            options: SearchOptionsPayload::default(),
+            cancellation_token: CancellationToken::noop(),
+            reply_tx,
+        };
+        (job, reply_rx)
+    }
+
+    #[test]
+    fn queued_searches_collapse_into_the_newest() {
+        let (search_tx, search_rx) = unbounded();
+        let (first, first_reply) = job(1);
+        let (second, second_reply) = job(3);
+        let (third, third_reply) = job(2);
+        search_tx.send(second).unwrap();
+        search_tx.send(third).unwrap();
+
+        let newest = newest_search(first, &search_rx);
+        assert_eq!(newest.id, 3);
+        for reply in [first_reply, third_reply] {
+            assert!(reply.try_recv().unwrap().unwrap().nodes.is_none());
+        }
+        assert!(second_reply.try_recv().is_err());
+        assert!(search_rx.is_empty());
+    }
+
+    #[test]
+    fn lone_search_is_returned_immediately() {
+        let (_search_tx, search_rx) = unbounded();
+        let (lone, reply) = job(7);
+        assert_eq!(newest_search(lone, &search_rx).id, 7);
+        assert!(reply.try_recv().is_err());
+    }
+
+    #[test]
+    fn progress_is_estimated_from_previous_total() {
+        assert_eq!(estimate_progress(Some(2_000), 0), Some(0));
+        assert_eq!(estimate_progress(Some(2_000), 500), Some(25));
+        assert_eq!(estimate_progress(Some(2_000), 1_999), Some(99));
+        // The tree grew since the last index.
+        assert_eq!(estimate_progress(Some(2_000), 2_500), Some(99));
+        // First run, nothing to compare against.
+        assert_eq!(estimate_progress(None, 500), None);
+        assert_eq!(estimate_progress(Some(0), 500), None);
+    }
+}
@@ -8,37 +8,62 @@ use crate::{
 };
 use anyhow::Result;
 use base64::{Engine as _, engine::general_purpose};
-use crossbeam_channel::{Receiver, Sender};
+use block2::RcBlock;
+use crossbeam_channel::{Receiver, Sender, bounded};
+use objc2_app_kit::{
+    NSPasteboard, NSPasteboardTypeString, NSRunningApplication, NSWorkspace,
+    NSWorkspaceOpenConfiguration,
+};
+use objc2_foundation::{NSArray, NSError, NSString, NSURL};
 use search_cache::{SearchOptions, SearchOutcome, SearchResultNode, SlabIndex, SlabNodeMetadata};
 use search_cancel::CancellationToken;
 use serde::{Deserialize, Serialize};
-use std::{process::Command, sync::atomic::Ordering};
+use std::{
+    path::PathBuf,
+    process::Command,
+    sync::atomic::{AtomicU64, Ordering},
+};
 use tauri::{AppHandle, Manager, State};
 use tracing::{info, warn};
 
-#[derive(Debug, Clone, Copy, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchOptionsPayload {
     #[serde(default)]
     pub case_insensitive: bool,
+    #[serde(default)]
+    pub excluded_prefixes: Vec<PathBuf>,
 }
 
 impl From<SearchOptionsPayload> for SearchOptions {
-    fn from(SearchOptionsPayload { case_insensitive }: SearchOptionsPayload) -> Self {
-        SearchOptions { case_insensitive }
+    fn from(
+        SearchOptionsPayload {
+            case_insensitive,
+            excluded_prefixes,
+        }: SearchOptionsPayload,
+    ) -> Self {
+        SearchOptions {
+            case_insensitive,
+            excluded_prefixes,
+            ..Default::default()
+        }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct SearchJob {
+    /// From [`SearchState::begin_query`], newer queries have larger ids.
+    pub id: u64,
     pub query: String,
     pub options: SearchOptionsPayload,
     pub cancellation_token: CancellationToken,
+    pub reply_tx: Sender<Result<SearchOutcome>>,
 }
 
 pub struct SearchState {
     search_tx: Sender<SearchJob>,
-    result_rx: Receiver<Result<SearchOutcome>>,
+    /// Id of the newest `search` call, see [`SearchState::begin_query`].
+    latest_query: AtomicU64,
 
     node_info_tx: Sender<Vec<SlabIndex>>,
     node_info_results_rx: Receiver<Vec<SearchResultNode>>,
@@ -51,7 +76,6 @@ impl SearchState {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         search_tx: Sender<SearchJob>,
-        result_rx: Receiver<Result<SearchOutcome>>,
         node_info_tx: Sender<Vec<SlabIndex>>,
         node_info_results_rx: Receiver<Vec<SearchResultNode>>,
         icon_viewport_tx: Sender<(u64, Vec<SlabIndex>)>,
@@ -59,13 +83,24 @@ impl SearchState {
     ) -> Self {
         Self {
             search_tx,
-            result_rx,
+            latest_query: AtomicU64::new(0),
             node_info_tx,
             node_info_results_rx,
             icon_viewport_tx,
             rescan_tx,
         }
     }
+
+    /// Assigns the id of a new query, superseding every query started before it.
+    fn begin_query(&self) -> u64 {
+        self.latest_query.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// `outcome` of query `id`, or `None` once a newer query has started so the
+    /// frontend never sees stale results.
+    fn latest_outcome(&self, id: u64, outcome: SearchOutcome) -> Option<SearchOutcome> {
+        (self.latest_query.load(Ordering::SeqCst) == id).then_some(outcome)
+    }
 }
 
 #[derive(Serialize)]
@@ -147,36 +182,42 @@ pub async fn search(
     state: State<'_, SearchState>,
 ) -> Result<SearchResponse, String> {
     let options = options.unwrap_or_default();
+    let id = state.begin_query();
     let cancellation_token = CancellationToken::new(version);
+    let (reply_tx, reply_rx) = bounded(1);
     state
         .search_tx
         .send(SearchJob {
+            id,
             query,
             options,
             cancellation_token,
+            reply_tx,
         })
         .map_err(|e| format!("Failed to send search request: {e:?}"))?;
 
-    let search_result = state
-        .result_rx
+    let outcome = reply_rx
         .recv()
         .map_err(|e| format!("Failed to receive search result: {e:?}"))?
-        .map(|res| {
-            let SearchOutcome { nodes, highlights } = res;
-            let results = match nodes {
-                Some(list) => list,
-                None => {
-                    info!("Search {version} was cancelled");
-                    Vec::new()
-                }
-            };
-            SearchResponse {
-                results,
-                highlights,
-            }
+        .map_err(|e| format!("Failed to process search result: {e:?}"))?;
+    let Some(SearchOutcome { nodes, highlights }) = state.latest_outcome(id, outcome) else {
+        info!("Search {version} was superseded");
+        return Ok(SearchResponse {
+            results: Vec::new(),
+            highlights: Vec::new(),
         });
-
-    search_result.map_err(|e| format!("Failed to process search result: {e:?}"))
+    };
+    let results = match nodes {
+        Some(list) => list,
+        None => {
+            info!("Search {version} was cancelled");
+            Vec::new()
+        }
+    };
+    Ok(SearchResponse {
+        results,
+        highlights,
+    })
 }
 
 #[tauri::command]
@@ -200,7 +241,7 @@ pub async fn get_nodes_info(
 
     let node_infos = nodes
         .into_iter()
-        .map(|SearchResultNode { path, metadata }| {
+        .map(|SearchResultNode { path, metadata, .. }| {
             let path = path.to_string_lossy().into_owned();
             let icon = fs_icon::icon_of_path_ns(&path).map(|data| {
                 format!(
@@ -264,6 +305,61 @@ pub fn open_path(path: String) -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+pub async fn open_with(path: String, app_bundle_id: String) -> Result<(), String> {
+    if std::fs::symlink_metadata(&path).is_err() {
+        return Err(format!("Path does not exist: {path}"));
+    }
+    let workspace = NSWorkspace::sharedWorkspace();
+    let app_url = workspace
+        .URLForApplicationWithBundleIdentifier(&NSString::from_str(&app_bundle_id))
+        .ok_or_else(|| format!("No application found for bundle id {app_bundle_id:?}"))?;
+    let urls = NSArray::from_retained_slice(&[NSURL::fileURLWithPath(&NSString::from_str(&path))]);
+    // The handler runs on a background queue once the application has opened the file or failed to.
+    let (done_tx, done_rx) = bounded(1);
+    workspace.openURLs_withApplicationAtURL_configuration_completionHandler(
+        &urls,
+        &app_url,
+        &NSWorkspaceOpenConfiguration::configuration(),
+        Some(&RcBlock::new(
+            move |_app: *mut NSRunningApplication, error: *mut NSError| {
+                let error =
+                    unsafe { error.as_ref() }.map(|error| error.localizedDescription().to_string());
+                let _ = done_tx.send(error);
+            },
+        )),
+    );
+    match done_rx
+        .recv()
+        .map_err(|e| format!("Failed to receive open result: {e:?}"))?
+    {
+        Some(error) => Err(format!(
+            "Failed to open {path} with {app_bundle_id}: {error}"
+        )),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn copy_path(path: String) -> Result<(), String> {
+    let text = clipboard_text(&path)?;
+    let pasteboard = NSPasteboard::generalPasteboard();
+    pasteboard.clearContents();
+    if !pasteboard.setString_forType(&NSString::from_str(text), unsafe { NSPasteboardTypeString }) {
+        return Err("Failed to write path to the clipboard".to_string());
+    }
+    Ok(())
+}
+
+/// The text [`copy_path`] puts on the clipboard: the path exactly as given, without quoting or
+/// escaping, so it pastes back as the same path.
+fn clipboard_text(path: &str) -> Result<&str, String> {
+    if path.trim().is_empty() {
+        return Err("Cannot copy an empty path".to_string());
+    }
+    Ok(path)
+}
+
 #[tauri::command]
 pub fn request_app_exit(app_handle: AppHandle) -> Result<(), String> {
     EXIT_REQUESTED.store(true, Ordering::Relaxed);
@@ -309,3 +405,54 @@ pub fn toggle_main_window(app: AppHandle) {
         warn!("Toggle requested but main window is unavailable");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::unbounded;
+
+    fn outcome() -> SearchOutcome {
+        SearchOutcome {
+            nodes: Some(Vec::new()),
+            highlights: vec!["report".to_string()],
+        }
+    }
+
+    #[test]
+    fn results_of_superseded_queries_are_dropped() {
+        let state = SearchState::new(
+            unbounded().0,
+            unbounded().0,
+            unbounded().1,
+            unbounded().0,
+            unbounded().0,
+        );
+        let first = state.begin_query();
+        let second = state.begin_query();
+        assert!(second > first);
+
+        assert!(state.latest_outcome(first, outcome()).is_none());
+        assert!(state.latest_outcome(second, outcome()).is_some());
+
+        let third = state.begin_query();
+        assert!(state.latest_outcome(second, outcome()).is_none());
+        assert!(state.latest_outcome(third, outcome()).is_some());
+    }
+
+    #[test]
+    fn clipboard_text_keeps_paths_verbatim() {
+        for path in [
+            "/Users/me/Documents/report.pdf",
+            "/Users/me/My Files/a 'quoted' name.txt",
+            "/Users/me/文档/résumé.txt",
+        ] {
+            assert_eq!(clipboard_text(path), Ok(path));
+        }
+    }
+
+    #[test]
+    fn clipboard_text_rejects_empty_paths() {
+        assert!(clipboard_text("").is_err());
+        assert!(clipboard_text("  \n").is_err());
+    }
+}
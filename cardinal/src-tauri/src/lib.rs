@@ -6,20 +6,22 @@ mod window_controls;
 
 use anyhow::{Context, Result};
 use background::{
-    BackgroundLoopChannels, IconPayload, emit_status_bar_update, run_background_event_loop,
+    BackgroundLoopChannels, IconPayload, emit_status_bar_update, estimate_progress,
+    run_background_event_loop,
 };
 use cardinal_sdk::EventWatcher;
 use commands::{
-    SearchJob, SearchState, activate_main_window, close_quicklook, get_app_status, get_nodes_info,
-    hide_main_window, open_in_finder, open_path, request_app_exit, search, start_logic,
-    toggle_main_window, toggle_quicklook, trigger_rescan, update_icon_viewport, update_quicklook,
+    SearchJob, SearchState, activate_main_window, close_quicklook, copy_path, get_app_status,
+    get_nodes_info, hide_main_window, open_in_finder, open_path, open_with, request_app_exit,
+    search, start_logic, toggle_main_window, toggle_quicklook, trigger_rescan,
+    update_icon_viewport, update_quicklook,
 };
 use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, bounded, unbounded};
 use lifecycle::{
     APP_QUIT, AppLifecycleState, EXIT_REQUESTED, emit_app_state, load_app_state, update_app_state,
 };
 use once_cell::sync::OnceCell;
-use search_cache::{SearchCache, SearchOutcome, SearchResultNode, SlabIndex, WalkData};
+use search_cache::{SearchCache, SearchResultNode, SlabIndex, WalkData, read_cache_node_count};
 use std::{
     path::{Path, PathBuf},
     sync::{
@@ -47,7 +49,6 @@ pub fn run() -> Result<()> {
 
     let (finish_tx, finish_rx) = bounded::<Sender<Option<SearchCache>>>(1);
     let (search_tx, search_rx) = unbounded::<SearchJob>();
-    let (result_tx, result_rx) = unbounded::<Result<SearchOutcome>>();
     let (node_info_tx, node_info_rx) = unbounded::<Vec<SlabIndex>>();
     let (node_info_results_tx, node_info_results_rx) = unbounded::<Vec<SearchResultNode>>();
     let (icon_viewport_tx, icon_viewport_rx) = unbounded::<(u64, Vec<SlabIndex>)>();
@@ -97,7 +98,6 @@ pub fn run() -> Result<()> {
     let app = builder
         .manage(SearchState::new(
             search_tx,
-            result_rx,
             node_info_tx,
             node_info_results_rx,
             icon_viewport_tx.clone(),
@@ -111,6 +111,8 @@ pub fn run() -> Result<()> {
             trigger_rescan,
             open_in_finder,
             open_path,
+            open_with,
+            copy_path,
             toggle_quicklook,
             close_quicklook,
             update_quicklook,
@@ -131,7 +133,6 @@ pub fn run() -> Result<()> {
     let channels = BackgroundLoopChannels {
         finish_rx,
         search_rx,
-        result_tx,
         node_info_rx,
         node_info_results_tx,
         icon_viewport_rx,
@@ -221,11 +222,15 @@ fn run_logic_thread(
     ) {
         Ok(cached) => {
             info!("Loaded existing cache");
-            emit_status_bar_update(app_handle, cached.get_total_files(), 0);
+            emit_status_bar_update(app_handle, cached.get_total_files(), 0, Some(100));
             cached
         }
         Err(e) => {
             info!("Walking filesystem: {:?}", e);
+            // Still readable when only the payload is unusable.
+            let previous_total = read_cache_node_count(db_path)
+                .ok()
+                .map(|count| count as usize);
             let walk_data = WalkData::new(Some(ignore_paths.clone()), false, Some(&APP_QUIT));
             let walking_done = AtomicBool::new(false);
             let cache = std::thread::scope(|s| {
@@ -234,7 +239,12 @@ fn run_logic_thread(
                         let dirs = walk_data.num_dirs.load(Ordering::Relaxed);
                         let files = walk_data.num_files.load(Ordering::Relaxed);
                         let total = dirs + files;
-                        emit_status_bar_update(app_handle, total, 0);
+                        emit_status_bar_update(
+                            app_handle,
+                            total,
+                            0,
+                            estimate_progress(previous_total, total),
+                        );
                         std::thread::sleep(Duration::from_millis(100));
                     }
                 });
@@ -260,14 +270,15 @@ fn run_logic_thread(
                 return;
             };
 
-            emit_status_bar_update(app_handle, cache.get_total_files(), 0);
+            emit_status_bar_update(app_handle, cache.get_total_files(), 0, Some(100));
 
             cache
         }
     };
 
     let event_watcher = EventWatcher::spawn(
-        WATCH_ROOT.to_string(),
+        vec![WATCH_ROOT.to_string()],
+        ignore_paths.clone(),
         cache.last_event_id(),
         FSE_LATENCY_SECS,
     )
@@ -282,6 +293,7 @@ fn run_logic_thread(
         event_watcher,
         channels,
         WATCH_ROOT,
+        &ignore_paths,
         FSE_LATENCY_SECS,
     );
 
@@ -302,7 +314,7 @@ fn flush_cache_to_file_once(finish_tx: &Sender<Sender<Option<SearchCache>>>, db_
             .unwrap();
         if let Some(cache) = cache_rx.recv().context("cache_tx is closed").unwrap() {
             cache
-                .flush_to_file(db_path)
+                .flush_incremental(db_path)
                 .context("Failed to write cache to file")
                 .unwrap();
 
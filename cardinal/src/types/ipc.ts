@@ -3,6 +3,8 @@ import type { SlabIndex } from './slab';
 export type StatusBarUpdatePayload = {
   scannedFiles: number;
   processedEvents: number;
+  // 0-100 while indexing, null when there is no previous index to compare against.
+  progress: number | null;
 };
 
 export type IconUpdateWirePayload = {
@@ -0,0 +1,142 @@
+use crate::icon_of_path_ns_sized;
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+type Renderer = Box<dyn Fn(&str, u32) -> Option<Vec<u8>> + Send + Sync>;
+
+/// Bundle extensions whose icon is specific to the bundle rather than its type.
+const BUNDLE_EXTENSIONS: &[&str] = &["app", "appex", "prefpane", "bundle", "framework", "plugin"];
+
+#[derive(PartialEq, Eq, Hash)]
+pub(crate) enum IconKey {
+    Extension(String),
+    Path(String),
+}
+
+impl IconKey {
+    pub(crate) fn of(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some(ext)
+                if !BUNDLE_EXTENSIONS
+                    .iter()
+                    .any(|b| ext.eq_ignore_ascii_case(b)) =>
+            {
+                Self::Extension(ext.to_ascii_lowercase())
+            }
+            _ => Self::Path(path.to_string()),
+        }
+    }
+}
+
+/// Memoizes NSWorkspace icons rendered at a fixed size.
+///
+/// Icons are keyed by file extension, so every `.txt` shares one PNG. Extensionless paths
+/// (folders included) and app bundles have their own icons and are keyed by full path.
+/// Failed lookups are remembered as well.
+pub struct IconCache {
+    size: u32,
+    icons: Mutex<HashMap<IconKey, Option<Vec<u8>>>>,
+    render: Renderer,
+}
+
+impl IconCache {
+    pub fn new(size: u32) -> Self {
+        Self::with_renderer(size, Box::new(icon_of_path_ns_sized))
+    }
+
+    fn with_renderer(size: u32, render: Renderer) -> Self {
+        Self {
+            size,
+            icons: Mutex::new(HashMap::new()),
+            render,
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Option<Vec<u8>> {
+        let key = IconKey::of(path);
+        if let Some(icon) = self.lock().get(&key) {
+            return icon.clone();
+        }
+        // Render without holding the lock, racing lookups for the same key just render twice.
+        let icon = (self.render)(path, self.size);
+        self.lock().entry(key).or_insert(icon).clone()
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<IconKey, Option<Vec<u8>>>> {
+        self.icons.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    fn counting_cache() -> (IconCache, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let cache = IconCache::with_renderer(
+            32,
+            Box::new(move |path, _| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                Some(path.as_bytes().to_vec())
+            }),
+        );
+        (cache, calls)
+    }
+
+    #[test]
+    fn same_extension_renders_once() {
+        let (cache, calls) = counting_cache();
+        let first = cache.get("/tmp/a.txt").unwrap();
+        let second = cache.get("/Users/someone/B.TXT").unwrap();
+        assert_eq!(first, second);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn extensionless_paths_and_bundles_are_keyed_by_path() {
+        let (cache, calls) = counting_cache();
+        assert_ne!(cache.get("/tmp/Makefile"), cache.get("/tmp/LICENSE"));
+        assert_ne!(
+            cache.get("/Applications/Safari.app"),
+            cache.get("/Applications/Mail.app")
+        );
+        cache.get("/Applications/Mail.app");
+        assert_eq!(calls.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn clear_forces_rerender() {
+        let (cache, calls) = counting_cache();
+        cache.get("/tmp/a.txt");
+        cache.clear();
+        cache.get("/tmp/a.txt");
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn real_icons_are_shared_by_extension() {
+        let dir = std::env::temp_dir();
+        let (a, b) = (
+            dir.join("fs_icon_cache_a.txt"),
+            dir.join("fs_icon_cache_b.txt"),
+        );
+        std::fs::write(&a, "a").unwrap();
+        std::fs::write(&b, "b").unwrap();
+        let cache = IconCache::new(32);
+        let icon_a = cache.get(a.to_str().unwrap()).unwrap();
+        let icon_b = cache.get(b.to_str().unwrap()).unwrap();
+        assert_eq!(icon_a, icon_b);
+    }
+}
@@ -1,4 +1,5 @@
 use block2::RcBlock;
+use cache::IconKey;
 use crossbeam_channel::bounded;
 use objc2::{AnyThread, rc::Retained};
 use objc2_app_kit::{NSBitmapImageFileType, NSBitmapImageRep, NSImage, NSWorkspace};
@@ -9,7 +10,11 @@ use objc2_quick_look_thumbnailing::{
     QLThumbnailGenerationRequest, QLThumbnailGenerationRequestRepresentationTypes,
     QLThumbnailGenerator, QLThumbnailRepresentation,
 };
-use std::ffi::c_void;
+use std::{collections::HashMap, ffi::c_void};
+
+mod cache;
+
+pub use cache::IconCache;
 
 pub fn scale_with_aspect_ratio(
     width: f64,
@@ -23,6 +28,17 @@ pub fn scale_with_aspect_ratio(
     (width * ratio, height * ratio)
 }
 
+/// Largest icon edge, in points, that the `*_sized` functions render. Larger requests are clamped.
+pub const MAX_ICON_SIZE: u32 = 1024;
+
+// Zoom in and you will see that the small icon in Finder is 32x32.
+const NS_ICON_SIZE: u32 = 32;
+const QL_THUMBNAIL_SIZE: u32 = 64;
+
+fn clamp_icon_size(size: u32) -> f64 {
+    f64::from(size.clamp(1, MAX_ICON_SIZE))
+}
+
 pub fn icon_of_path(path: &str) -> Option<Vec<u8>> {
     if let Some(data) = icon_of_path_ql(path) {
         return Some(data);
@@ -30,22 +46,53 @@ pub fn icon_of_path(path: &str) -> Option<Vec<u8>> {
     icon_of_path_ns(path)
 }
 
-// https://stackoverflow.com/questions/73062803/resizing-nsimage-keeping-aspect-ratio-reducing-the-image-size-while-trying-to-sc
+/// Like [`icon_of_path`], but the icon fits in a `size`x`size` box, keeping its aspect ratio.
+pub fn icon_of_path_sized(path: &str, size: u32) -> Option<Vec<u8>> {
+    if let Some(data) = icon_of_path_ql_sized(path, size) {
+        return Some(data);
+    }
+    icon_of_path_ns_sized(path, size)
+}
+
 pub fn icon_of_path_ns(path: &str) -> Option<Vec<u8>> {
+    icon_of_path_ns_sized(path, NS_ICON_SIZE)
+}
+
+// https://stackoverflow.com/questions/73062803/resizing-nsimage-keeping-aspect-ratio-reducing-the-image-size-while-trying-to-sc
+pub fn icon_of_path_ns_sized(path: &str, size: u32) -> Option<Vec<u8>> {
+    ns_icon(&NSWorkspace::sharedWorkspace(), path, size)
+}
+
+/// NSWorkspace icons for many paths at once, in input order.
+///
+/// The workspace handle is fetched once and paths sharing an extension are rendered once,
+/// with the same keying as [`IconCache`].
+pub fn icons_of_paths(paths: &[&str], size: u32) -> Vec<Option<Vec<u8>>> {
+    let workspace = NSWorkspace::sharedWorkspace();
+    let mut rendered = HashMap::new();
+    paths
+        .iter()
+        .map(|path| {
+            rendered
+                .entry(IconKey::of(path))
+                .or_insert_with(|| ns_icon(&workspace, path, size))
+                .clone()
+        })
+        .collect()
+}
+
+fn ns_icon(workspace: &NSWorkspace, path: &str, size: u32) -> Option<Vec<u8>> {
+    let target = clamp_icon_size(size);
     objc2::rc::autoreleasepool(|_| -> Option<Vec<u8>> {
         let path_ns = NSString::from_str(path);
-        let image = NSWorkspace::sharedWorkspace().iconForFile(&path_ns);
+        let image = workspace.iconForFile(&path_ns);
 
         let png_data: Retained<NSData> = (|| -> Option<_> {
             unsafe {
                 // https://stackoverflow.com/questions/66270656/macos-determine-real-size-of-icon-returned-from-iconforfile-method
                 for image in image.representations().iter() {
                     let size = image.size();
-                    if size.width > 31.0
-                        && size.height > 31.0
-                        && size.width < 33.0
-                        && size.height < 33.0
-                    {
+                    if (size.width - target).abs() < 1.0 && (size.height - target).abs() < 1.0 {
                         // println!("representation: {}x{}", size.width, size.height);
                         let new_image = NSImage::imageWithSize_flipped_drawingHandler(
                             NSSize::new(size.width, size.height),
@@ -65,14 +112,11 @@ pub fn icon_of_path_ns(path: &str) -> Option<Vec<u8>> {
                     }
                 }
             }
-            // zoom in and you will see that the small icon in Finder is 32x32, here we keep it at 64x64 for better visibility
             let (new_width, new_height) = {
-                let width = 32.0;
-                let height = 32.0;
                 // keep aspect ratio
                 let old_width = image.size().width;
                 let old_height = image.size().height;
-                scale_with_aspect_ratio(old_width, old_height, width, height)
+                scale_with_aspect_ratio(old_width, old_height, target, target)
             };
             unsafe {
                 let new_image = NSImage::imageWithSize_flipped_drawingHandler(
@@ -117,14 +161,42 @@ pub fn image_dimension(image_path: &str) -> Option<(f64, f64)> {
 }
 
 pub fn icon_of_path_ql(path: &str) -> Option<Vec<u8>> {
+    icon_of_path_ql_sized(path, QL_THUMBNAIL_SIZE)
+}
+
+pub fn icon_of_path_ql_sized(path: &str, size: u32) -> Option<Vec<u8>> {
+    ql_thumbnail(
+        path,
+        size,
+        QLThumbnailGenerationRequestRepresentationTypes::LowQualityThumbnail,
+    )
+}
+
+/// Full quality preview of an image file fitting in a `size`x`size` box, for grid views.
+/// Falls back to the NSWorkspace icon for non-images or when QuickLook fails.
+pub fn thumbnail_of_path(path: &str, size: u32) -> Option<Vec<u8>> {
+    if let Some(data) = ql_thumbnail(
+        path,
+        size,
+        QLThumbnailGenerationRequestRepresentationTypes::Thumbnail,
+    ) {
+        return Some(data);
+    }
+    icon_of_path_ns_sized(path, size)
+}
+
+fn ql_thumbnail(
+    path: &str,
+    size: u32,
+    representation_types: QLThumbnailGenerationRequestRepresentationTypes,
+) -> Option<Vec<u8>> {
     // We only get QLThumbnail for image, get NSWorkspace icon for other file types.
     // Therefore we just error out when image_dimension is not found.
     let (width, height) = image_dimension(path)?;
+    let target = clamp_icon_size(size);
     objc2::rc::autoreleasepool(|_| -> Option<Vec<u8>> {
-        const THUMBNAIL_SIZE: f64 = 64.0;
         const THUMBNAIL_SCALE: f64 = 1.0;
-        let (width, height) =
-            scale_with_aspect_ratio(width, height, THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+        let (width, height) = scale_with_aspect_ratio(width, height, target, target);
         // use a slightly larger thumbnail size with 0.5 scale
         let path_url = NSURL::fileURLWithPath(&NSString::from_str(path));
         let generator = unsafe { QLThumbnailGenerator::sharedGenerator() };
@@ -137,7 +209,7 @@ pub fn icon_of_path_ql(path: &str) -> Option<Vec<u8>> {
                         &path_url,
                         NSSize::new(width, height),
                         THUMBNAIL_SCALE,
-                        QLThumbnailGenerationRequestRepresentationTypes::LowQualityThumbnail,
+                        representation_types,
                     );
                 generator.generateBestRepresentationForRequest_completionHandler(
                     &request,
@@ -195,6 +267,78 @@ mod tests {
         icon_of_path_ql(&pwd).expect("should fail for non-image file");
     }
 
+    fn rendered_dimension(data: &[u8], name: &str) -> (f64, f64) {
+        let file = std::env::temp_dir().join(name);
+        std::fs::write(&file, data).unwrap();
+        image_dimension(file.to_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_icon_of_path_sized_follows_requested_size() {
+        let pwd = std::env::current_dir()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned();
+        for size in [32u32, 128] {
+            let data = icon_of_path_sized(&pwd, size).unwrap();
+            let (width, height) = rendered_dimension(&data, &format!("icon_{size}.png"));
+            // Retina displays may render at 2x.
+            let expected = f64::from(size);
+            assert!(
+                (expected..=expected * 2.0).contains(&width)
+                    && (expected..=expected * 2.0).contains(&height),
+                "{size}px request rendered as {width}x{height}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_thumbnail_of_path_keeps_aspect_ratio() {
+        let data = thumbnail_of_path("tests/fixtures/wide_40x20.png", 16).unwrap();
+        let (width, height) = rendered_dimension(&data, "thumbnail_wide.png");
+        assert!(width <= 32.0 && height <= 16.0, "got {width}x{height}");
+        assert!((width / height - 2.0).abs() < 0.2, "got {width}x{height}");
+    }
+
+    #[test]
+    fn test_thumbnail_of_path_falls_back_to_icon() {
+        let pwd = std::env::current_dir()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned();
+        assert!(thumbnail_of_path(&pwd, 64).is_some());
+    }
+
+    #[test]
+    fn test_icons_of_paths_matches_input_order() {
+        let dir = std::env::temp_dir().join("fs_icon_batch");
+        std::fs::create_dir_all(dir.join("folder")).unwrap();
+        for name in ["a.txt", "b.txt", "c.rs"] {
+            std::fs::write(dir.join(name), name).unwrap();
+        }
+        let mut listing: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path().to_string_lossy().into_owned())
+            .collect();
+        listing.sort();
+        let paths: Vec<&str> = listing.iter().map(String::as_str).collect();
+
+        let icons = icons_of_paths(&paths, 32);
+        assert_eq!(icons.len(), paths.len());
+        assert!(icons.iter().all(Option::is_some));
+        // Sorted: a.txt, b.txt, c.rs, folder
+        assert_eq!(icons[0], icons[1]);
+        assert_eq!(icons[0], icon_of_path_ns_sized(paths[0], 32));
+        assert_eq!(icons[3], icon_of_path_ns_sized(paths[3], 32));
+    }
+
+    #[test]
+    fn test_icon_size_is_clamped() {
+        assert_eq!(clamp_icon_size(0), 1.0);
+        assert_eq!(clamp_icon_size(64), 64.0);
+        assert_eq!(clamp_icon_size(u32::MAX), f64::from(MAX_ICON_SIZE));
+    }
+
     #[test]
     fn test_icon_dimension() {
         let (width, height) = image_dimension("../cardinal/mac-icon_1024x1024.png").unwrap();
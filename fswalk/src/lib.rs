@@ -2,12 +2,16 @@ use rayon::{iter::ParallelBridge, prelude::ParallelIterator};
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::{
+    collections::HashSet,
     fs::{self, Metadata},
     io::{Error, ErrorKind},
     num::NonZeroU64,
     os::unix::fs::MetadataExt,
     path::{Path, PathBuf},
-    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    },
     time::UNIX_EPOCH,
 };
 
@@ -25,6 +29,10 @@ pub struct NodeMetadata {
     pub size: u64,
     pub ctime: Option<NonZeroU64>,
     pub mtime: Option<NonZeroU64>,
+    /// Owner and permission bits, as in `st_uid`, `st_gid` and `st_mode`.
+    pub uid: u32,
+    pub gid: u32,
+    pub mode: u32,
 }
 
 impl From<Metadata> for NodeMetadata {
@@ -52,6 +60,9 @@ impl NodeMetadata {
             size,
             ctime,
             mtime,
+            uid: metadata.uid(),
+            gid: metadata.gid(),
+            mode: metadata.mode(),
         }
     }
 }
@@ -80,15 +91,60 @@ impl From<fs::FileType> for NodeFileType {
     }
 }
 
-#[derive(Debug)]
+type IncludeFilter<'w> = Box<dyn Fn(&Path) -> bool + Sync + 'w>;
+type ProgressCallback<'w> = Box<dyn Fn(usize, usize) + Sync + 'w>;
+
+/// Number of walked entries between two progress callback invocations.
+const PROGRESS_INTERVAL: usize = 1024;
+
 pub struct WalkData<'w> {
     pub num_files: AtomicUsize,
     pub num_dirs: AtomicUsize,
+    /// Bytes of every file whose metadata was read, a file reachable through several hard
+    /// links counts once per link.
+    pub apparent_size: AtomicU64,
+    /// Like [`Self::apparent_size`] but each physical file counts once, only kept with
+    /// [`Self::with_hard_link_tracking`].
+    pub physical_size: AtomicU64,
     /// Cancellation will be checked periodically.
     cancel: Option<&'w AtomicBool>,
     ignore_directories: Option<Vec<PathBuf>>,
     /// If set, metadata will be collected for each file node(folder node will get free metadata).
     need_metadata: bool,
+    /// Unrecoverable errors hit during the walk, e.g. unreadable folders.
+    errors: Mutex<Vec<(PathBuf, ErrorKind)>>,
+    /// If set, file nodes whose path doesn't pass the filter are left out of the tree.
+    /// Directories are always kept so matching leaves stay reachable.
+    include_filter: Option<IncludeFilter<'w>>,
+    /// If set, invoked with `(num_dirs, num_files)` every [`PROGRESS_INTERVAL`] entries and
+    /// once more when the walk finishes.
+    progress: Option<ProgressCallback<'w>>,
+    progress_ticks: AtomicUsize,
+    /// If set, entries whose name starts with `.` are left out and hidden folders aren't
+    /// descended into. The walk root is never skipped.
+    skip_hidden: bool,
+    /// `(dev, ino)` of the multiply linked files counted so far, `None` unless hard links are
+    /// tracked.
+    seen_inodes: Option<Mutex<HashSet<(u64, u64)>>>,
+}
+
+impl std::fmt::Debug for WalkData<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WalkData")
+            .field("num_files", &self.num_files)
+            .field("num_dirs", &self.num_dirs)
+            .field("cancel", &self.cancel)
+            .field("ignore_directories", &self.ignore_directories)
+            .field("need_metadata", &self.need_metadata)
+            .field("errors", &self.errors)
+            .field("include_filter", &self.include_filter.is_some())
+            .field("progress", &self.progress.is_some())
+            .field("skip_hidden", &self.skip_hidden)
+            .field("apparent_size", &self.apparent_size)
+            .field("physical_size", &self.physical_size)
+            .field("track_hard_links", &self.seen_inodes.is_some())
+            .finish()
+    }
 }
 
 impl<'w> WalkData<'w> {
@@ -96,9 +152,17 @@ impl<'w> WalkData<'w> {
         Self {
             num_files: AtomicUsize::new(0),
             num_dirs: AtomicUsize::new(0),
+            apparent_size: AtomicU64::new(0),
+            physical_size: AtomicU64::new(0),
             cancel: None,
             ignore_directories: None,
             need_metadata,
+            errors: Mutex::new(Vec::new()),
+            include_filter: None,
+            progress: None,
+            progress_ticks: AtomicUsize::new(0),
+            skip_hidden: false,
+            seen_inodes: None,
         }
     }
 
@@ -110,12 +174,120 @@ impl<'w> WalkData<'w> {
         Self {
             num_files: AtomicUsize::new(0),
             num_dirs: AtomicUsize::new(0),
+            apparent_size: AtomicU64::new(0),
+            physical_size: AtomicU64::new(0),
             cancel,
             ignore_directories,
             need_metadata,
+            errors: Mutex::new(Vec::new()),
+            include_filter: None,
+            progress: None,
+            progress_ticks: AtomicUsize::new(0),
+            skip_hidden: false,
+            seen_inodes: None,
         }
     }
 
+    pub fn with_include_filter(mut self, filter: impl Fn(&Path) -> bool + Sync + 'w) -> Self {
+        self.include_filter = Some(Box::new(filter));
+        self
+    }
+
+    pub fn with_progress(mut self, progress: impl Fn(usize, usize) + Sync + 'w) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    pub fn with_skip_hidden(mut self, skip_hidden: bool) -> Self {
+        self.skip_hidden = skip_hidden;
+        self
+    }
+
+    /// Remembers the `(dev, ino)` of files with more than one link, so hard links to an already
+    /// counted file don't add to [`Self::physical_size`] again. Sizes are only known for files
+    /// whose metadata is read, see `need_metadata`.
+    pub fn with_hard_link_tracking(mut self, track_hard_links: bool) -> Self {
+        self.seen_inodes = track_hard_links.then(|| Mutex::new(HashSet::new()));
+        self
+    }
+
+    /// Paths that couldn't be walked along with the reason, in no particular order.
+    pub fn errors(&self) -> Vec<(PathBuf, ErrorKind)> {
+        self.errors.lock().unwrap().clone()
+    }
+
+    fn record_error(&self, path: &Path, error: &Error) {
+        self.errors
+            .lock()
+            .unwrap()
+            .push((path.to_path_buf(), error.kind()));
+    }
+
+    fn add_dir(&self) {
+        self.num_dirs.fetch_add(1, Ordering::Relaxed);
+        self.tick_progress();
+    }
+
+    fn add_file(&self) {
+        self.num_files.fetch_add(1, Ordering::Relaxed);
+        self.tick_progress();
+    }
+
+    fn add_file_size(&self, metadata: &Metadata) {
+        if metadata.is_dir() {
+            return;
+        }
+        let size = metadata.size();
+        self.apparent_size.fetch_add(size, Ordering::Relaxed);
+        let Some(seen_inodes) = &self.seen_inodes else {
+            return;
+        };
+        // A file with a single link can't show up again.
+        if metadata.nlink() > 1
+            && !seen_inodes
+                .lock()
+                .unwrap()
+                .insert((metadata.dev(), metadata.ino()))
+        {
+            return;
+        }
+        self.physical_size.fetch_add(size, Ordering::Relaxed);
+    }
+
+    fn tick_progress(&self) {
+        if self.progress.is_some()
+            && (self.progress_ticks.fetch_add(1, Ordering::Relaxed) + 1) % PROGRESS_INTERVAL == 0
+        {
+            self.report_progress();
+        }
+    }
+
+    fn report_progress(&self) {
+        if let Some(progress) = &self.progress {
+            progress(
+                self.num_dirs.load(Ordering::Relaxed),
+                self.num_files.load(Ordering::Relaxed),
+            );
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .map(|x| x.load(Ordering::Relaxed))
+            .unwrap_or_default()
+    }
+
+    fn should_include(&self, path: &Path) -> bool {
+        self.include_filter
+            .as_ref()
+            .map(|filter| filter(path))
+            .unwrap_or(true)
+    }
+
+    fn should_skip_entry(&self, entry: &fs::DirEntry) -> bool {
+        self.skip_hidden && entry.file_name().as_encoded_bytes().starts_with(b".")
+    }
+
     fn should_ignore(&self, path: &Path) -> bool {
         self.ignore_directories
             .as_ref()
@@ -125,7 +297,9 @@ impl<'w> WalkData<'w> {
 }
 
 pub fn walk_it(dir: &Path, walk_data: &WalkData) -> Option<Node> {
-    walk(dir, walk_data)
+    let node = walk(dir, walk_data);
+    walk_data.report_progress();
+    node
 }
 
 fn walk(path: &Path, walk_data: &WalkData) -> Option<Node> {
@@ -143,12 +317,13 @@ fn walk(path: &Path, walk_data: &WalkData) -> Option<Node> {
                 // doesn't traverse symlink
                 path.symlink_metadata().ok()
             } else {
+                walk_data.record_error(path, &e);
                 None
             }
         }
     };
     let children = if metadata.as_ref().map(|x| x.is_dir()).unwrap_or_default() {
-        walk_data.num_dirs.fetch_add(1, Ordering::Relaxed);
+        walk_data.add_dir();
         let read_dir = fs::read_dir(path);
         match read_dir {
             Ok(entries) => entries
@@ -157,14 +332,10 @@ fn walk(path: &Path, walk_data: &WalkData) -> Option<Node> {
                 .filter_map(|entry| {
                     match &entry {
                         Ok(entry) => {
-                            if walk_data
-                                .cancel
-                                .map(|x| x.load(Ordering::Relaxed))
-                                .unwrap_or_default()
-                            {
+                            if walk_data.is_cancelled() {
                                 return None;
                             }
-                            if walk_data.should_ignore(path) {
+                            if walk_data.should_ignore(path) || walk_data.should_skip_entry(entry) {
                                 return None;
                             }
                             // doesn't traverse symlink
@@ -172,7 +343,10 @@ fn walk(path: &Path, walk_data: &WalkData) -> Option<Node> {
                                 if data.is_dir() {
                                     return walk(&entry.path(), walk_data);
                                 } else {
-                                    walk_data.num_files.fetch_add(1, Ordering::Relaxed);
+                                    if !walk_data.should_include(&entry.path()) {
+                                        return None;
+                                    }
+                                    walk_data.add_file();
                                     let name = entry
                                         .file_name()
                                         .to_string_lossy()
@@ -186,7 +360,11 @@ fn walk(path: &Path, walk_data: &WalkData) -> Option<Node> {
                                             .then_some(entry)
                                             .and_then(|entry| {
                                                 // doesn't traverse symlink
-                                                entry.metadata().ok().map(NodeMetadata::from)
+                                                entry.metadata().ok()
+                                            })
+                                            .map(|metadata| {
+                                                walk_data.add_file_size(&metadata);
+                                                NodeMetadata::from(metadata)
                                             }),
                                     });
                                 }
@@ -196,6 +374,7 @@ fn walk(path: &Path, walk_data: &WalkData) -> Option<Node> {
                             if handle_error_and_retry(failed) {
                                 return walk(path, walk_data);
                             }
+                            walk_data.record_error(path, failed);
                         }
                     }
                     None
@@ -205,19 +384,19 @@ fn walk(path: &Path, walk_data: &WalkData) -> Option<Node> {
                 if handle_error_and_retry(&failed) {
                     return walk(path, walk_data);
                 } else {
+                    walk_data.record_error(path, &failed);
                     vec![]
                 }
             }
         }
     } else {
-        walk_data.num_files.fetch_add(1, Ordering::Relaxed);
+        walk_data.add_file();
+        if let Some(metadata) = &metadata {
+            walk_data.add_file_size(metadata);
+        }
         vec![]
     };
-    if walk_data
-        .cancel
-        .map(|x| x.load(Ordering::Relaxed))
-        .unwrap_or_default()
-    {
+    if walk_data.is_cancelled() {
         return None;
     }
     let name = path
@@ -233,6 +412,108 @@ fn walk(path: &Path, walk_data: &WalkData) -> Option<Node> {
     })
 }
 
+/// Walks `dir` like [`walk_it`] but calls `visit` once per entry instead of building a tree,
+/// the root included. Entries are visited in no particular order and from several threads.
+/// Metadata follows the same rules as the tree: always present for folders, only with
+/// `need_metadata` for files.
+pub fn walk_each(
+    dir: &Path,
+    walk_data: &WalkData,
+    visit: impl Fn(&Path, Option<NodeMetadata>) + Sync,
+) {
+    walk_entries(dir, walk_data, &visit);
+    walk_data.report_progress();
+}
+
+/// Every entry under `dir` (the root included) with its metadata, in no particular order.
+pub fn walk_flat(dir: &Path, walk_data: &WalkData) -> Vec<(PathBuf, Option<NodeMetadata>)> {
+    let entries = Mutex::new(Vec::new());
+    walk_each(dir, walk_data, |path, metadata| {
+        entries.lock().unwrap().push((path.to_path_buf(), metadata));
+    });
+    entries.into_inner().unwrap()
+}
+
+fn walk_entries(
+    path: &Path,
+    walk_data: &WalkData,
+    visit: &(impl Fn(&Path, Option<NodeMetadata>) + Sync),
+) {
+    if walk_data.should_ignore(path) || walk_data.is_cancelled() {
+        return;
+    }
+    // doesn't traverse symlink
+    let metadata = match path.symlink_metadata() {
+        Ok(metadata) => Some(metadata),
+        Err(e) if e.kind() == ErrorKind::NotFound => return,
+        Err(e) => {
+            if handle_error_and_retry(&e) {
+                path.symlink_metadata().ok()
+            } else {
+                walk_data.record_error(path, &e);
+                None
+            }
+        }
+    };
+    let is_dir = metadata.as_ref().is_some_and(|x| x.is_dir());
+    if is_dir {
+        walk_data.add_dir();
+    } else {
+        walk_data.add_file();
+        if let Some(metadata) = &metadata {
+            walk_data.add_file_size(metadata);
+        }
+    }
+    visit(path, metadata.map(NodeMetadata::from));
+    if !is_dir {
+        return;
+    }
+    let entries = loop {
+        match fs::read_dir(path) {
+            Ok(entries) => break entries,
+            Err(failed) if handle_error_and_retry(&failed) => {}
+            Err(failed) => {
+                walk_data.record_error(path, &failed);
+                return;
+            }
+        }
+    };
+    entries.par_bridge().for_each(|entry| {
+        if walk_data.is_cancelled() {
+            return;
+        }
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(failed) => {
+                walk_data.record_error(path, &failed);
+                return;
+            }
+        };
+        if walk_data.should_skip_entry(&entry) {
+            return;
+        }
+        // doesn't traverse symlink
+        let Ok(file_type) = entry.file_type() else {
+            return;
+        };
+        let entry_path = entry.path();
+        if file_type.is_dir() {
+            walk_entries(&entry_path, walk_data, visit);
+        } else if walk_data.should_include(&entry_path) {
+            walk_data.add_file();
+            let metadata = walk_data
+                .need_metadata
+                .then(|| entry.metadata().ok())
+                .flatten()
+                .map(|metadata| {
+                    walk_data.add_file_size(&metadata);
+                    NodeMetadata::from(metadata)
+                });
+            visit(&entry_path, metadata);
+        }
+    });
+}
+
 fn handle_error_and_retry(failed: &Error) -> bool {
     failed.kind() == std::io::ErrorKind::Interrupted
 }
@@ -363,6 +644,27 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_metadata_records_owner_and_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new("fswalk_owner").unwrap();
+        let root = tmp.path();
+        let file = root.join("owned.txt");
+        fs::File::create(&file).unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o640)).unwrap();
+        let walk_data = WalkData::simple(true);
+        let node = walk_it(root, &walk_data).unwrap();
+        let metadata = node
+            .children
+            .iter()
+            .find(|c| &*c.name == "owned.txt")
+            .and_then(|c| c.metadata)
+            .unwrap();
+        assert_eq!(metadata.uid, unsafe { libc::geteuid() });
+        assert_eq!(metadata.mode & 0o777, 0o640);
+    }
+
     #[test]
     fn test_symlink_not_traversed() {
         let tmp = TempDir::new("fswalk_symlink").unwrap();
@@ -392,6 +694,236 @@ mod tests {
         assert!(!handle_error_and_retry(&not_found));
     }
 
+    #[test]
+    fn test_unreadable_dir_is_recorded() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new("fswalk_errors").unwrap();
+        let root = tmp.path();
+        let locked = root.join("locked");
+        fs::create_dir(&locked).unwrap();
+        fs::File::create(locked.join("hidden.txt")).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        // Privileged users can read it anyway, nothing to record then.
+        let readable = fs::read_dir(&locked).is_ok();
+
+        let walk_data = WalkData::simple(false);
+        let node = walk_it(root, &walk_data).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        if readable {
+            return;
+        }
+
+        let locked_node = node.children.iter().find(|c| &*c.name == "locked");
+        assert!(locked_node.is_some_and(|n| n.children.is_empty()));
+        assert_eq!(
+            walk_data.errors(),
+            vec![(locked, ErrorKind::PermissionDenied)]
+        );
+    }
+
+    #[test]
+    fn test_include_filter_keeps_matching_leaves_and_dirs() {
+        let tmp = TempDir::new("fswalk_include_filter").unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("docs/nested")).unwrap();
+        fs::create_dir(root.join("media")).unwrap();
+        fs::File::create(root.join("top.txt")).unwrap();
+        fs::File::create(root.join("top.log")).unwrap();
+        fs::File::create(root.join("docs/readme.txt")).unwrap();
+        fs::File::create(root.join("docs/nested/deep.txt")).unwrap();
+        fs::File::create(root.join("docs/nested/deep.md")).unwrap();
+        fs::File::create(root.join("media/song.mp3")).unwrap();
+
+        let walk_data = WalkData::simple(false)
+            .with_include_filter(|path| path.extension().is_some_and(|ext| ext == "txt"));
+        let node = walk_it(root, &walk_data).unwrap();
+
+        fn collect(node: &Node, prefix: &Path, acc: &mut Vec<PathBuf>) {
+            for child in &node.children {
+                let path = prefix.join(&*child.name);
+                acc.push(path.clone());
+                collect(child, &path, acc);
+            }
+        }
+        let mut paths = Vec::new();
+        collect(&node, Path::new(""), &mut paths);
+        let expected: Vec<PathBuf> = [
+            "docs",
+            "docs/nested",
+            "docs/nested/deep.txt",
+            "docs/readme.txt",
+            "media",
+            "top.txt",
+        ]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+        assert_eq!(paths, expected);
+        assert_eq!(walk_data.num_files.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_skip_hidden_leaves_out_dot_entries() {
+        let tmp = TempDir::new("fswalk_skip_hidden").unwrap();
+        let root = tmp.path().join(".root");
+        fs::create_dir_all(root.join(".hidden_dir")).unwrap();
+        fs::File::create(root.join(".hidden_dir/x")).unwrap();
+        fs::File::create(root.join(".hidden_file")).unwrap();
+        fs::File::create(root.join("visible.txt")).unwrap();
+
+        let walk_data = WalkData::simple(false).with_skip_hidden(true);
+        let node = walk_it(&root, &walk_data).unwrap();
+        assert_eq!(&*node.name, ".root");
+        let names: Vec<&str> = node.children.iter().map(|c| &*c.name).collect();
+        assert_eq!(names, ["visible.txt"]);
+        assert_eq!(walk_data.num_dirs.load(Ordering::Relaxed), 1);
+        assert_eq!(walk_data.num_files.load(Ordering::Relaxed), 1);
+
+        let flat_data = WalkData::simple(false).with_skip_hidden(true);
+        let mut flat: Vec<PathBuf> = walk_flat(&root, &flat_data)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        flat.sort();
+        assert_eq!(flat, [root.clone(), root.join("visible.txt")]);
+
+        let walk_data = WalkData::simple(false);
+        let node = walk_it(&root, &walk_data).unwrap();
+        let names: Vec<&str> = node.children.iter().map(|c| &*c.name).collect();
+        assert_eq!(names, [".hidden_dir", ".hidden_file", "visible.txt"]);
+        assert_eq!(node.children[0].children.len(), 1);
+    }
+
+    #[test]
+    fn test_hard_links_count_once_physically() {
+        let tmp = TempDir::new("fswalk_hard_links").unwrap();
+        let root = tmp.path();
+        fs::write(root.join("data.bin"), vec![0u8; 1000]).unwrap();
+        fs::hard_link(root.join("data.bin"), root.join("link.bin")).unwrap();
+        fs::write(root.join("small.txt"), b"0123456789").unwrap();
+
+        let sizes = |walk_data: &WalkData| {
+            (
+                walk_data.apparent_size.load(Ordering::Relaxed),
+                walk_data.physical_size.load(Ordering::Relaxed),
+            )
+        };
+        let walk_data = WalkData::simple(true).with_hard_link_tracking(true);
+        walk_it(root, &walk_data).unwrap();
+        assert_eq!(sizes(&walk_data), (2010, 1010));
+
+        let flat_data = WalkData::simple(true).with_hard_link_tracking(true);
+        walk_flat(root, &flat_data);
+        assert_eq!(sizes(&flat_data), (2010, 1010));
+
+        let untracked = WalkData::simple(true);
+        walk_it(root, &untracked).unwrap();
+        assert_eq!(sizes(&untracked), (2010, 0));
+
+        // Without metadata there is nothing to add up.
+        let no_metadata = WalkData::simple(false).with_hard_link_tracking(true);
+        walk_it(root, &no_metadata).unwrap();
+        assert_eq!(sizes(&no_metadata), (0, 0));
+    }
+
+    #[test]
+    fn test_progress_callback_reports_final_totals() {
+        use std::sync::Mutex;
+
+        let tmp = TempDir::new("fswalk_progress").unwrap();
+        let root = tmp.path();
+        for d in 0..4u32 {
+            let dir = root.join(format!("d{d}"));
+            fs::create_dir(&dir).unwrap();
+            for f in 0..600u32 {
+                fs::File::create(dir.join(format!("f{f}.txt"))).unwrap();
+            }
+        }
+
+        let reports = Mutex::new(Vec::new());
+        let walk_data = WalkData::simple(false)
+            .with_progress(|dirs, files| reports.lock().unwrap().push((dirs, files)));
+        walk_it(root, &walk_data).unwrap();
+        drop(walk_data);
+
+        let reports = reports.into_inner().unwrap();
+        // 2405 entries: one report per PROGRESS_INTERVAL plus the final one.
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports.last(), Some(&(5, 2400)));
+    }
+
+    #[test]
+    fn test_walk_flat_matches_tree_walk() {
+        let tmp = TempDir::new("fswalk_flat").unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("a/b/c")).unwrap();
+        fs::create_dir(root.join("empty")).unwrap();
+        fs::create_dir(root.join("skip")).unwrap();
+        for file in [
+            "top.txt",
+            "a/one.txt",
+            "a/b/two.log",
+            "a/b/c/three.txt",
+            "skip/x.txt",
+        ] {
+            fs::write(root.join(file), b"x").unwrap();
+        }
+        fn collect(node: &Node, prefix: &Path, acc: &mut Vec<PathBuf>) {
+            let path = prefix.join(&*node.name);
+            for child in &node.children {
+                collect(child, &path, acc);
+            }
+            acc.push(path);
+        }
+        let new_walk_data = || {
+            WalkData::new(Some(vec![root.join("skip")]), true, None)
+                .with_include_filter(|path| path.extension().is_none_or(|ext| ext == "txt"))
+        };
+
+        let tree_data = new_walk_data();
+        let tree = walk_it(root, &tree_data).unwrap();
+        let mut tree_paths = vec![];
+        collect(&tree, root.parent().unwrap(), &mut tree_paths);
+        tree_paths.sort();
+
+        let flat_data = new_walk_data();
+        let flat = walk_flat(root, &flat_data);
+        let mut flat_paths: Vec<PathBuf> = flat.iter().map(|(path, _)| path.clone()).collect();
+        flat_paths.sort();
+
+        assert_eq!(flat.len(), 8);
+        assert_eq!(flat_paths, tree_paths);
+        assert_eq!(
+            flat_data.num_dirs.load(Ordering::Relaxed),
+            tree_data.num_dirs.load(Ordering::Relaxed)
+        );
+        assert_eq!(
+            flat_data.num_files.load(Ordering::Relaxed),
+            tree_data.num_files.load(Ordering::Relaxed)
+        );
+        for (path, metadata) in &flat {
+            let expected = if path.is_dir() {
+                NodeFileType::Dir
+            } else {
+                NodeFileType::File
+            };
+            assert_eq!(metadata.map(|m| m.r#type), Some(expected), "{path:?}");
+        }
+    }
+
+    #[test]
+    fn test_walk_each_visits_root_file() {
+        let tmp = TempDir::new("fswalk_each_file").unwrap();
+        let file = tmp.path().join("single.txt");
+        fs::write(&file, b"x").unwrap();
+        let visited = Mutex::new(vec![]);
+        walk_each(&file, &WalkData::simple(false), |path, _| {
+            visited.lock().unwrap().push(path.to_path_buf());
+        });
+        assert_eq!(visited.into_inner().unwrap(), vec![file]);
+    }
+
     #[test]
     fn test_large_number_of_files_counts() {
         let tmp = TempDir::new("fswalk_many").unwrap();
@@ -1,4 +1,5 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use search_cache::SortKey;
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -8,4 +9,36 @@ pub struct Cli {
     pub refresh: bool,
     #[clap(long, default_value = "/")]
     pub path: PathBuf,
+    #[clap(long)]
+    /// Print each query's results as a JSON array of `{path, size, modified, type}` on one line.
+    pub json: bool,
+    #[clap(long)]
+    /// Run this query before reading queries from stdin.
+    pub query: Option<String>,
+    #[clap(long, requires = "query")]
+    /// Exit after running `--query` instead of entering the interactive loop.
+    pub once: bool,
+    #[clap(long, value_enum)]
+    /// Order results of every query by this key, ascending unless `--reverse` is given.
+    pub sort: Option<SortBy>,
+    #[clap(long, requires = "sort")]
+    /// Sort in descending order.
+    pub reverse: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SortBy {
+    Name,
+    Size,
+    Mtime,
+}
+
+impl From<SortBy> for SortKey {
+    fn from(sort: SortBy) -> Self {
+        match sort {
+            SortBy::Name => SortKey::Name,
+            SortBy::Size => SortKey::Size,
+            SortBy::Mtime => SortKey::DateModified,
+        }
+    }
 }
@@ -1,11 +1,12 @@
 mod cli;
+mod output;
 
 use anyhow::{Context, Result};
 use cardinal_sdk::EventWatcher;
 use clap::Parser;
 use cli::Cli;
 use crossbeam_channel::{Sender, bounded, unbounded};
-use search_cache::{HandleFSEError, SearchCache, SearchResultNode};
+use search_cache::{HandleFSEError, SearchCache, SearchOptions, SearchResultNode, SortKey};
 use search_cancel::CancellationToken;
 use std::{
     io::Write,
@@ -25,12 +26,12 @@ fn main() -> Result<()> {
     }
 
     let cli = Cli::parse();
-    let path = cli.path;
+    let path = cli.path.clone();
     let mut cache = if cli.refresh {
-        println!("Walking filesystem...");
+        eprintln!("Walking filesystem...");
         SearchCache::walk_fs_with_ignore(path, vec![PathBuf::from(IGNORE_PATH)])
     } else {
-        println!("Try reading cache...");
+        eprintln!("Try reading cache...");
         SearchCache::try_read_persistent_cache(
             &path,
             Path::new(CACHE_PATH),
@@ -38,21 +39,27 @@ fn main() -> Result<()> {
             None,
         )
         .unwrap_or_else(|e| {
-            println!("Failed to read cache: {e:?}. Re-walking filesystem...");
+            eprintln!("Failed to read cache: {e:?}. Re-walking filesystem...");
             SearchCache::walk_fs_with_ignore(path, vec![PathBuf::from(IGNORE_PATH)])
         })
     };
 
-    println!("Cache is: {cache:?}");
+    eprintln!("Cache is: {cache:?}");
 
     let (finish_tx, finish_rx) = bounded::<Sender<SearchCache>>(1);
     let (search_tx, search_rx) = unbounded::<String>();
     let (search_result_tx, search_result_rx) = unbounded::<Result<Vec<SearchResultNode>>>();
 
+    let sort = cli.sort.map(SortKey::from);
+    let ascending = !cli.reverse;
     std::thread::spawn(move || {
-        let (dev, mut event_watcher) =
-            EventWatcher::spawn("/".to_string(), cache.last_event_id(), 0.1);
-        println!("Processing changes of dev:{dev} during preparation.");
+        let (dev, mut event_watcher) = EventWatcher::spawn(
+            vec!["/".to_string()],
+            vec![PathBuf::from(IGNORE_PATH)],
+            cache.last_event_id(),
+            0.1,
+        );
+        eprintln!("Processing changes of dev:{dev} during preparation.");
         loop {
             crossbeam_channel::select! {
                 recv(finish_rx) -> tx => {
@@ -62,7 +69,16 @@ fn main() -> Result<()> {
                 }
                 recv(search_rx) -> query => {
                     let query = query.expect("search_tx is closed");
-                    let files = cache.query_files(query, CancellationToken::noop()).map(|x| x.unwrap());
+                    // Fetch metadata of the results, the walk doesn't collect it.
+                    let files = cache
+                        .search_with_options(&query, &SearchOptions::default(), CancellationToken::noop())
+                        .map(|outcome| {
+                            let mut files = cache.expand_file_nodes(&outcome.nodes.unwrap());
+                            if let Some(sort) = sort {
+                                sort.sort(&mut files, ascending);
+                            }
+                            files
+                        });
                     search_result_tx
                         .send(files)
                         .expect("search_result_tx is closed");
@@ -70,35 +86,29 @@ fn main() -> Result<()> {
                 recv(event_watcher) -> events => {
                     let events = events.expect("event_stream is closed");
                     if let Err(HandleFSEError::Rescan) = cache.handle_fs_events(events) {
-                        println!("!!!!!!!!!! Rescan triggered !!!!!!!!");
+                        eprintln!("!!!!!!!!!! Rescan triggered !!!!!!!!");
                         // Here we clear event_watcher first as rescan may take a lot of time
                         #[allow(unused_assignments)]
                         {
                             event_watcher = EventWatcher::noop();
                         }
                         cache.rescan();
-                        event_watcher = EventWatcher::spawn("/".to_string(), cache.last_event_id(), 0.1).1;
+                        event_watcher = EventWatcher::spawn(
+                            vec!["/".to_string()],
+                            vec![PathBuf::from(IGNORE_PATH)],
+                            cache.last_event_id(),
+                            0.1,
+                        )
+                        .1;
                     }
                 }
             }
         }
-        println!("fsevent processing is done");
+        eprintln!("fsevent processing is done");
     });
 
-    let stdin = std::io::stdin();
-    let mut stdout = std::io::stdout();
-    loop {
-        print!("> ");
-        stdout.flush().unwrap();
-        let mut line = String::new();
-        stdin.read_line(&mut line).unwrap();
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        } else if line == "/bye" {
-            break;
-        }
-
+    // Returns whether the search succeeded, errors are only for a broken search thread.
+    let run_query = |line: &str| -> Result<bool> {
         search_tx
             .send(line.to_string())
             .context("search_tx is closed")?;
@@ -107,23 +117,52 @@ fn main() -> Result<()> {
             .context("search_result_rx is closed")?;
         match search_result {
             Ok(path_set) => {
-                for (i, path) in path_set.into_iter().enumerate() {
-                    println!("[{i}] {:?} {:?}", path.path, path.metadata);
-                }
+                output::print_results(&path_set, cli.json)?;
+                Ok(true)
             }
             Err(e) => {
                 eprintln!("Failed to search: {e:?}");
+                Ok(false)
+            }
+        }
+    };
+
+    let mut one_shot_succeeded = true;
+    if let Some(query) = &cli.query {
+        one_shot_succeeded = run_query(query)?;
+    }
+
+    if !cli.once {
+        let stdin = std::io::stdin();
+        let mut stdout = std::io::stdout();
+        loop {
+            if !cli.json {
+                print!("> ");
+                stdout.flush().unwrap();
+            }
+            let mut line = String::new();
+            if stdin.read_line(&mut line).context("Failed to read query")? == 0 {
+                // stdin closed, e.g. queries were piped in.
+                break;
+            }
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            } else if line == "/bye" {
+                break;
             }
+            run_query(line)?;
         }
     }
 
     let (cache_tx, cache_rx) = bounded::<SearchCache>(1);
     finish_tx.send(cache_tx).context("cache_tx is closed")?;
     let cache = cache_rx.recv().context("cache_tx is closed")?;
-    println!("start writing cache: {cache:?}");
+    eprintln!("start writing cache: {cache:?}");
     cache
         .flush_to_file(Path::new(CACHE_PATH))
         .context("Failed to write cache to file")?;
 
+    anyhow::ensure!(!cli.once || one_shot_succeeded, "--query search failed");
     Ok(())
 }
@@ -0,0 +1,46 @@
+use fswalk::NodeFileType;
+use search_cache::SearchResultNode;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Serialize)]
+struct JsonResult<'a> {
+    path: &'a Path,
+    size: Option<u64>,
+    /// Seconds since the Unix epoch.
+    modified: Option<u32>,
+    r#type: &'static str,
+}
+
+impl<'a> From<&'a SearchResultNode> for JsonResult<'a> {
+    fn from(node: &'a SearchResultNode) -> Self {
+        let metadata = node.metadata.as_ref();
+        Self {
+            path: &node.path,
+            size: metadata.as_ref().map(|metadata| metadata.size()),
+            modified: metadata
+                .as_ref()
+                .and_then(|metadata| metadata.mtime())
+                .map(|mtime| mtime.get()),
+            r#type: match node.metadata.file_type_hint() {
+                NodeFileType::File => "file",
+                NodeFileType::Dir => "dir",
+                NodeFileType::Symlink => "symlink",
+                NodeFileType::Unknown => "unknown",
+            },
+        }
+    }
+}
+
+/// Prints the results of one query, either human readable or as a single-line JSON array.
+pub fn print_results(results: &[SearchResultNode], json: bool) -> anyhow::Result<()> {
+    if json {
+        let results: Vec<JsonResult> = results.iter().map(JsonResult::from).collect();
+        println!("{}", serde_json::to_string(&results)?);
+    } else {
+        for (i, node) in results.iter().enumerate() {
+            println!("[{i}] {:?} {:?}", node.path, node.metadata);
+        }
+    }
+    Ok(())
+}
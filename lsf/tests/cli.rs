@@ -0,0 +1,130 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+use tempfile::TempDir;
+
+/// A working directory for one `lsf` run, with the files to index under `corpus/`.
+struct Workdir {
+    dir: TempDir,
+}
+
+impl Workdir {
+    fn new(files: &[(&str, &[u8])]) -> Self {
+        let dir = TempDir::new().unwrap();
+        // lsf flushes its cache to `target/cache.zstd` relative to the working directory.
+        std::fs::create_dir(dir.path().join("target")).unwrap();
+        let workdir = Self { dir };
+        std::fs::create_dir(workdir.corpus()).unwrap();
+        for (name, contents) in files {
+            std::fs::write(workdir.corpus().join(name), contents).unwrap();
+        }
+        workdir
+    }
+
+    fn corpus(&self) -> PathBuf {
+        self.dir.path().join("corpus")
+    }
+
+    /// Runs lsf on a fresh walk of the corpus, piping `queries` on stdin, and returns stdout.
+    fn run(&self, args: &[&str], queries: &str) -> String {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_lsf"))
+            .current_dir(self.dir.path())
+            .arg("--refresh")
+            .arg("--path")
+            .arg(self.corpus())
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        // With `--once` lsf may exit before reading stdin, so the write can hit a closed pipe.
+        let _ = child.stdin.take().unwrap().write_all(queries.as_bytes());
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    }
+}
+
+fn json_lines(stdout: &str) -> Vec<serde_json::Value> {
+    stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn file_names(results: &serde_json::Value) -> Vec<&str> {
+    results
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| result["path"].as_str().unwrap().rsplit('/').next().unwrap())
+        .collect()
+}
+
+#[test]
+fn json_mode_prints_one_array_per_piped_query() {
+    let workdir = Workdir::new(&[("notes.txt", b"hello"), ("main.rs", b"fn main() {}")]);
+    let stdout = workdir.run(&["--json"], "notes.txt\n\nmain\n");
+
+    let arrays = json_lines(&stdout);
+    assert_eq!(arrays.len(), 2, "one array per non-empty query: {stdout}");
+
+    let notes = arrays[0].as_array().unwrap();
+    assert_eq!(notes.len(), 1);
+    let notes = &notes[0];
+    assert!(
+        notes["path"]
+            .as_str()
+            .unwrap()
+            .ends_with("corpus/notes.txt")
+    );
+    assert_eq!(notes["size"], 5);
+    assert!(notes["modified"].as_u64().unwrap() > 0);
+    assert_eq!(notes["type"], "file");
+
+    assert_eq!(file_names(&arrays[1]), ["main.rs"]);
+}
+
+#[test]
+fn sort_flags_apply_to_every_query() {
+    let workdir = Workdir::new(&[
+        ("small.txt", b"s"),
+        ("big.txt", &[b'b'; 100]),
+        ("medium.txt", &[b'm'; 10]),
+    ]);
+
+    let stdout = workdir.run(
+        &["--json", "--sort", "size", "--reverse"],
+        "ext:txt\n*.txt\n",
+    );
+    for results in json_lines(&stdout) {
+        assert_eq!(file_names(&results), ["big.txt", "medium.txt", "small.txt"]);
+    }
+
+    let stdout = workdir.run(&["--json", "--sort", "name"], "ext:txt\n");
+    assert_eq!(
+        file_names(&json_lines(&stdout)[0]),
+        ["big.txt", "medium.txt", "small.txt"]
+    );
+}
+
+#[test]
+fn one_shot_query_exits_without_reading_stdin() {
+    let workdir = Workdir::new(&[
+        ("main.rs", b"fn main() {}"),
+        ("lib.rs", b""),
+        ("a.txt", b""),
+    ]);
+    // The queries on stdin must be ignored.
+    let stdout = workdir.run(
+        &["--json", "--sort", "name", "--query", "ext:rs", "--once"],
+        "a.txt\n",
+    );
+    let arrays = json_lines(&stdout);
+    assert_eq!(arrays.len(), 1, "{stdout}");
+    assert_eq!(file_names(&arrays[0]), ["lib.rs", "main.rs"]);
+    assert!(workdir.dir.path().join("target/cache.zstd").exists());
+}
@@ -1,12 +1,85 @@
 #![feature(str_from_raw_parts)]
 use core::str;
+use memchr::memmem;
+use memmap2::Mmap;
 use parking_lot::Mutex;
 use regex::Regex;
 use search_cancel::{CANCEL_CHECK_INTERVAL, CancellationToken};
-use std::collections::BTreeSet;
+use std::{
+    collections::BTreeSet,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
 
 pub struct NamePool {
     inner: Mutex<BTreeSet<Box<str>>>,
+    /// Names loaded by [`NamePool::from_mmap`]. `inner` only holds names pushed
+    /// afterwards that aren't in here.
+    mapped: Option<MappedNames>,
+}
+
+/// A file written by [`NamePool::write_to`]: sorted, unique names, each followed
+/// by a `\0`.
+struct MappedNames {
+    mmap: Mmap,
+    /// Start offset of each name.
+    starts: Vec<usize>,
+}
+
+impl MappedNames {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // Safety: the pool file is only written by `write_to`, callers must not
+        // modify it while it is mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+        let text =
+            str::from_utf8(&mmap).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if !text.is_empty() && !text.ends_with('\0') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "name pool file is truncated",
+            ));
+        }
+        let mut starts = Vec::new();
+        let mut previous: Option<&str> = None;
+        let mut start = 0;
+        for name in text.split_terminator('\0') {
+            if previous.is_some_and(|previous| previous >= name) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "name pool file is not sorted",
+                ));
+            }
+            previous = Some(name);
+            starts.push(start);
+            start += name.len() + 1;
+        }
+        Ok(Self { mmap, starts })
+    }
+
+    fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// The name starting at `start`, one of `starts`.
+    fn name_at(&self, start: usize) -> &str {
+        let len = memchr::memchr(0, &self.mmap[start..]).expect("names are NUL terminated");
+        // Safety: validated as UTF-8 in `open`, and `\0` is a char boundary.
+        unsafe { str::from_utf8_unchecked(&self.mmap[start..start + len]) }
+    }
+
+    fn find(&self, name: &str) -> Option<&str> {
+        let index = self
+            .starts
+            .binary_search_by(|&start| self.name_at(start).cmp(name))
+            .ok()?;
+        Some(self.name_at(self.starts[index]))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &str> {
+        self.starts.iter().map(|&start| self.name_at(start))
+    }
 }
 
 impl std::fmt::Debug for NamePool {
@@ -27,29 +100,76 @@ impl NamePool {
     pub fn new() -> Self {
         Self {
             inner: Mutex::new(BTreeSet::new()),
+            mapped: None,
+        }
+    }
+
+    /// Opens a pool persisted by [`NamePool::write_to`] without reading it into
+    /// memory, so the OS can page the names out while the pool sits idle.
+    ///
+    /// The file must not be modified while the pool is alive.
+    pub fn from_mmap(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            inner: Mutex::new(BTreeSet::new()),
+            mapped: Some(MappedNames::open(path.as_ref())?),
+        })
+    }
+
+    /// Persists every name in a form [`NamePool::from_mmap`] can map. Names
+    /// containing `\0` can't be persisted.
+    ///
+    /// The names are written to a sibling file that then replaces `path`, so
+    /// `path` may be the file this pool is mapped from.
+    pub fn write_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path = path.with_file_name(tmp_name);
+        let written = self.write_names(&tmp_path);
+        if written.is_err() {
+            let _ = fs::remove_file(&tmp_path);
+            return written;
         }
+        // Renaming keeps the old inode, and so the mapped names, alive.
+        fs::rename(&tmp_path, path)
+    }
+
+    fn write_names(&self, path: &Path) -> io::Result<()> {
+        let inner = self.inner.lock();
+        let mapped = self.mapped.iter().flat_map(MappedNames::iter);
+        let names: BTreeSet<&str> = mapped.chain(inner.iter().map(|name| &**name)).collect();
+        let mut writer = BufWriter::new(File::create(path)?);
+        for name in names {
+            if name.contains('\0') {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("name {name:?} contains a NUL byte"),
+                ));
+            }
+            writer.write_all(name.as_bytes())?;
+            writer.write_all(b"\0")?;
+        }
+        writer.flush()
     }
 
     pub fn len(&self) -> usize {
-        self.inner.lock().len()
+        self.mapped.as_ref().map_or(0, MappedNames::len) + self.inner.lock().len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.inner.lock().is_empty()
+        self.len() == 0
     }
 
-    /// This function add a name into last cache line, if the last cache line is
-    /// full, a new cache line will be added.
-    ///
-    /// # Panic
-    ///
-    /// This function will panic if a new CacheLine cannot hold the given name.
+    /// Interns `name`, returning the pool's copy of it.
     ///
-    /// Returns (line_num, str_offset)
-    ///
-    /// One important feature of NamePool is that the returned offset is stable
-    /// and won't be overwritten.
+    /// One important feature of NamePool is that the returned reference is
+    /// stable: pushing the same name again and every search hitting it yield
+    /// the same pointer, so callers can key their own tables by
+    /// [`str::as_ptr`] instead of an offset.
     pub fn push<'c>(&'c self, name: &str) -> &'c str {
+        if let Some(existing) = self.mapped.as_ref().and_then(|mapped| mapped.find(name)) {
+            return existing;
+        }
         let mut inner = self.inner.lock();
         if !inner.contains(name) {
             inner.insert(name.into());
@@ -63,16 +183,51 @@ impl NamePool {
         substr: &'search str,
         cancellation_token: CancellationToken,
     ) -> Option<BTreeSet<&'pool str>> {
-        let mut result = BTreeSet::new();
-        for (i, x) in self.inner.lock().iter().enumerate() {
-            if i % CANCEL_CHECK_INTERVAL == 0 && cancellation_token.is_cancelled() {
-                return None;
-            }
-            if x.contains(substr) {
-                result.insert(unsafe { str::from_raw_parts(x.as_ptr(), x.len()) });
-            }
-        }
-        Some(result)
+        self.search_by(|x| x.contains(substr), cancellation_token)
+    }
+
+    /// Names containing every one of `needles`, in a single scan. Longer needles are checked
+    /// first as they rule names out soonest. No needles match every name.
+    pub fn search_all_substr<'search, 'pool: 'search>(
+        &'pool self,
+        needles: &[&'search str],
+        cancellation_token: CancellationToken,
+    ) -> Option<BTreeSet<&'pool str>> {
+        let mut needles = needles.to_vec();
+        needles.sort_unstable_by_key(|needle| (std::cmp::Reverse(needle.len()), *needle));
+        needles.dedup();
+        let finders: Vec<memmem::Finder> = needles.iter().map(memmem::Finder::new).collect();
+        self.search_by(
+            |x| {
+                finders
+                    .iter()
+                    .all(|finder| finder.find(x.as_bytes()).is_some())
+            },
+            cancellation_token,
+        )
+    }
+
+    /// Names containing `needle` ignoring case across scripts: `résumé` finds `RÉSUMÉ`,
+    /// `ΚΟΣΜΟΣ` finds `κοσμος` and `strasse` finds `Straße`. Accents still have to
+    /// match, there is no Unicode normalization. Names are folded one at a time into a
+    /// reused buffer.
+    pub fn search_substr_case_folded<'search, 'pool: 'search>(
+        &'pool self,
+        needle: &'search str,
+        cancellation_token: CancellationToken,
+    ) -> Option<BTreeSet<&'pool str>> {
+        let mut folded_needle = String::new();
+        fold_case(needle, &mut folded_needle);
+        let finder = memmem::Finder::new(folded_needle.as_bytes());
+        let mut folded = String::new();
+        self.search_by(
+            |x| {
+                folded.clear();
+                fold_case(x, &mut folded);
+                finder.find(folded.as_bytes()).is_some()
+            },
+            cancellation_token,
+        )
     }
 
     pub fn search_suffix<'search, 'pool: 'search>(
@@ -80,16 +235,7 @@ impl NamePool {
         suffix: &'search str,
         cancellation_token: CancellationToken,
     ) -> Option<BTreeSet<&'pool str>> {
-        let mut result = BTreeSet::new();
-        for (i, x) in self.inner.lock().iter().enumerate() {
-            if i % CANCEL_CHECK_INTERVAL == 0 && cancellation_token.is_cancelled() {
-                return None;
-            }
-            if x.ends_with(suffix) {
-                result.insert(unsafe { str::from_raw_parts(x.as_ptr(), x.len()) });
-            }
-        }
-        Some(result)
+        self.search_by(|x| x.ends_with(suffix), cancellation_token)
     }
 
     pub fn search_prefix<'search, 'pool: 'search>(
@@ -97,17 +243,23 @@ impl NamePool {
         prefix: &'search str,
         cancellation_token: CancellationToken,
     ) -> Option<BTreeSet<&'pool str>> {
-        let mut result = BTreeSet::new();
-        for (i, x) in self.inner.lock().iter().enumerate() {
-            if i % CANCEL_CHECK_INTERVAL == 0 && cancellation_token.is_cancelled() {
-                return None;
-            }
-            if x.starts_with(prefix) {
-                result.insert(unsafe { str::from_raw_parts(x.as_ptr(), x.len()) });
-            }
-        }
+        self.search_by(|x| x.starts_with(prefix), cancellation_token)
+    }
 
-        Some(result)
+    /// Names starting with `prefix` and ending with `suffix` in a single scan, like the glob
+    /// `prefix*suffix`: both must fit without sharing characters, so `ab` doesn't match
+    /// prefix `ab` with suffix `b`.
+    pub fn search_prefix_suffix<'search, 'pool: 'search>(
+        &'pool self,
+        prefix: &'search str,
+        suffix: &'search str,
+        cancellation_token: CancellationToken,
+    ) -> Option<BTreeSet<&'pool str>> {
+        let min_len = prefix.len() + suffix.len();
+        self.search_by(
+            |x| x.len() >= min_len && x.starts_with(prefix) && x.ends_with(suffix),
+            cancellation_token,
+        )
     }
 
     pub fn search_regex<'search, 'pool: 'search>(
@@ -115,14 +267,29 @@ impl NamePool {
         pattern: &Regex,
         cancellation_token: CancellationToken,
     ) -> Option<BTreeSet<&'pool str>> {
+        self.search_by(|x| pattern.is_match(x), cancellation_token)
+    }
+
+    /// Names for which `predicate` returns true. Every search goes through here,
+    /// whether the names are mapped or pushed.
+    pub fn search_by(
+        &self,
+        mut predicate: impl FnMut(&str) -> bool,
+        cancellation_token: CancellationToken,
+    ) -> Option<BTreeSet<&str>> {
         let mut result = BTreeSet::new();
-        for (i, x) in self.inner.lock().iter().enumerate() {
+        let inner = self.inner.lock();
+        let mapped = self.mapped.iter().flat_map(MappedNames::iter);
+        // Pushed names live as long as the pool, not just the guard.
+        let pushed = inner
+            .iter()
+            .map(|x| unsafe { str::from_raw_parts(x.as_ptr(), x.len()) });
+        for (i, x) in mapped.chain(pushed).enumerate() {
             if i % CANCEL_CHECK_INTERVAL == 0 && cancellation_token.is_cancelled() {
                 return None;
             }
-            let existing = unsafe { str::from_raw_parts(x.as_ptr(), x.len()) };
-            if pattern.is_match(existing) {
-                result.insert(existing);
+            if predicate(x) {
+                result.insert(x);
             }
         }
         Some(result)
@@ -135,16 +302,24 @@ impl NamePool {
         exact: &'search str,
         cancellation_token: CancellationToken,
     ) -> Option<BTreeSet<&'pool str>> {
-        let mut result = BTreeSet::new();
-        for (i, x) in self.inner.lock().iter().enumerate() {
-            if i % CANCEL_CHECK_INTERVAL == 0 && cancellation_token.is_cancelled() {
-                return None;
-            }
-            if &**x == exact {
-                result.insert(unsafe { str::from_raw_parts(x.as_ptr(), x.len()) });
-            }
+        self.search_by(|x| x == exact, cancellation_token)
+    }
+}
+
+/// Appends `text` to `out` in a caseless form: full lowercase mappings, plus the
+/// foldings lowercasing misses (final sigma, sharp s). No Unicode normalization,
+/// so precomposed and decomposed accents still differ.
+fn fold_case(text: &str, out: &mut String) {
+    if text.is_ascii() {
+        out.extend(text.chars().map(|ch| ch.to_ascii_lowercase()));
+        return;
+    }
+    for ch in text.chars() {
+        match ch {
+            'ς' => out.push('σ'),
+            'ß' | 'ẞ' => out.push_str("ss"),
+            _ => out.extend(ch.to_lowercase()),
         }
-        Some(result)
     }
 }
 
@@ -160,6 +335,14 @@ mod tests {
         guard(pool.search_substr(needle, CancellationToken::noop()))
     }
 
+    fn all_substr<'pool>(pool: &'pool NamePool, needles: &[&str]) -> BTreeSet<&'pool str> {
+        guard(pool.search_all_substr(needles, CancellationToken::noop()))
+    }
+
+    fn folded_search<'pool>(pool: &'pool NamePool, needle: &str) -> BTreeSet<&'pool str> {
+        guard(pool.search_substr_case_folded(needle, CancellationToken::noop()))
+    }
+
     fn suffix_search<'pool>(pool: &'pool NamePool, needle: &str) -> BTreeSet<&'pool str> {
         guard(pool.search_suffix(needle, CancellationToken::noop()))
     }
@@ -168,6 +351,14 @@ mod tests {
         guard(pool.search_prefix(needle, CancellationToken::noop()))
     }
 
+    fn prefix_suffix_search<'pool>(
+        pool: &'pool NamePool,
+        prefix: &str,
+        suffix: &str,
+    ) -> BTreeSet<&'pool str> {
+        guard(pool.search_prefix_suffix(prefix, suffix, CancellationToken::noop()))
+    }
+
     fn exact_search<'pool>(pool: &'pool NamePool, needle: &str) -> BTreeSet<&'pool str> {
         guard(pool.search_exact(needle, CancellationToken::noop()))
     }
@@ -176,6 +367,138 @@ mod tests {
         guard(pool.search_regex(pattern, CancellationToken::noop()))
     }
 
+    #[test]
+    fn test_search_by_predicate() {
+        let pool = NamePool::new();
+        pool.push("alpha");
+        pool.push("beta");
+        pool.push("gamma");
+
+        let result = guard(pool.search_by(|name| name.len() == 5, CancellationToken::noop()));
+        assert_eq!(result.into_iter().collect::<Vec<_>>(), ["alpha", "gamma"]);
+    }
+
+    fn persisted(pool: &NamePool) -> (tempfile::TempDir, NamePool) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("names");
+        pool.write_to(&path).unwrap();
+        let mapped = NamePool::from_mmap(&path).unwrap();
+        (dir, mapped)
+    }
+
+    #[test]
+    fn test_from_mmap_search_substr() {
+        let pool = NamePool::new();
+        for name in ["hello", "world", "hello world", "こんにちは", ""] {
+            pool.push(name);
+        }
+        let (_dir, mapped) = persisted(&pool);
+
+        assert_eq!(mapped.len(), 5);
+        assert_eq!(substr(&mapped, "hello"), substr(&pool, "hello"));
+        assert_eq!(
+            substr(&mapped, "world"),
+            BTreeSet::from(["hello world", "world"])
+        );
+        assert_eq!(exact_search(&mapped, ""), BTreeSet::from([""]));
+        assert_eq!(
+            suffix_search(&mapped, "にちは"),
+            BTreeSet::from(["こんにちは"])
+        );
+    }
+
+    #[test]
+    fn test_from_mmap_push_reuses_mapped_names() {
+        let pool = NamePool::new();
+        pool.push("alpha");
+        pool.push("gamma");
+        let (_dir, mapped) = persisted(&pool);
+
+        let alpha = mapped.push("alpha");
+        assert!(std::ptr::eq(alpha, mapped.push("alpha")));
+        assert_eq!(mapped.len(), 2);
+        assert_eq!(mapped.push("beta"), "beta");
+        assert_eq!(mapped.len(), 3);
+        assert_eq!(
+            prefix_search(&mapped, ""),
+            BTreeSet::from(["alpha", "beta", "gamma"])
+        );
+
+        // Re-persisting merges both halves.
+        let (_dir, remapped) = persisted(&mapped);
+        assert_eq!(prefix_search(&remapped, ""), prefix_search(&mapped, ""));
+    }
+
+    #[test]
+    fn test_write_to_the_mapped_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("names");
+        let pool = NamePool::new();
+        for i in 0..10_000 {
+            pool.push(&format!("name-{i}"));
+        }
+        pool.write_to(&path).unwrap();
+
+        let mapped = NamePool::from_mmap(&path).unwrap();
+        mapped.push("extra");
+        mapped.write_to(&path).unwrap();
+        // The pool still reads its names from the replaced file.
+        assert_eq!(mapped.len(), 10_001);
+        assert_eq!(
+            exact_search(&mapped, "name-9999"),
+            BTreeSet::from(["name-9999"])
+        );
+
+        let remapped = NamePool::from_mmap(&path).unwrap();
+        assert_eq!(prefix_search(&remapped, ""), prefix_search(&mapped, ""));
+        assert_eq!(
+            std::fs::read_dir(dir.path()).unwrap().count(),
+            1,
+            "temporary file left behind"
+        );
+    }
+
+    #[test]
+    fn test_search_results_are_the_pushed_references() {
+        let pool = NamePool::new();
+        let pushed: Vec<&str> = ["alpha", "beta", "alphabet"]
+            .into_iter()
+            .map(|name| pool.push(name))
+            .collect();
+        let (_dir, mapped) = persisted(&pool);
+        let mapped_pushed: Vec<&str> = ["alpha", "beta", "alphabet", "gamma"]
+            .into_iter()
+            .map(|name| mapped.push(name))
+            .collect();
+
+        for (pool, pushed) in [(&pool, &pushed), (&mapped, &mapped_pushed)] {
+            let hits = substr(pool, "alpha");
+            assert_eq!(hits.len(), 2);
+            for hit in hits {
+                assert!(pushed.iter().any(|name| std::ptr::eq(*name, hit)), "{hit}");
+            }
+        }
+        let gamma = guard(mapped.search_exact("gamma", CancellationToken::noop()));
+        assert!(std::ptr::eq(
+            gamma.into_iter().next().unwrap(),
+            mapped_pushed[3]
+        ));
+    }
+
+    #[test]
+    fn test_from_mmap_rejects_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("names");
+        std::fs::write(&path, b"beta\0alpha\0").unwrap();
+        assert!(NamePool::from_mmap(&path).is_err());
+        std::fs::write(&path, b"alpha\0bet").unwrap();
+        assert!(NamePool::from_mmap(&path).is_err());
+
+        let pool = NamePool::new();
+        pool.push("a\0b");
+        assert!(pool.write_to(&path).is_err());
+    }
+
     #[test]
     fn test_search_substr_cancelled_returns_none() {
         let pool = NamePool::new();
@@ -277,6 +600,106 @@ mod tests {
         assert!(result.contains("hello world"));
     }
 
+    #[test]
+    fn test_search_all_substr() {
+        let pool = NamePool::new();
+        pool.push("report 2024.pdf");
+        pool.push("report 2023.pdf");
+        pool.push("2024 budget.xlsx");
+        pool.push("annual report 2024 final.docx");
+        pool.push("notes.txt");
+
+        assert_eq!(
+            all_substr(&pool, &["report", "2024"]),
+            BTreeSet::from(["annual report 2024 final.docx", "report 2024.pdf"])
+        );
+        assert_eq!(
+            all_substr(&pool, &["report", "2024", "final"]),
+            BTreeSet::from(["annual report 2024 final.docx"])
+        );
+        // Same as intersecting the single-needle searches.
+        let both: BTreeSet<&str> = substr(&pool, "2024")
+            .intersection(&substr(&pool, ".pdf"))
+            .copied()
+            .collect();
+        assert_eq!(all_substr(&pool, &[".pdf", "2024"]), both);
+    }
+
+    #[test]
+    fn test_search_substr_case_folded() {
+        let pool = NamePool::new();
+        for name in [
+            "résumé.pdf",
+            "RÉSUMÉ 2024.docx",
+            "resume.txt",
+            "Привет мир.txt",
+            "ПРИВЕТ.md",
+            "пока.md",
+            "κοσμος.png",
+            "ΚΌΣΜΟΣ.png",
+            "Straße.txt",
+            "STRASSE.txt",
+        ] {
+            pool.push(name);
+        }
+
+        // Accented Latin: the accent has to match, only case is ignored.
+        let expected = BTreeSet::from(["RÉSUMÉ 2024.docx", "résumé.pdf"]);
+        assert_eq!(folded_search(&pool, "résumé"), expected);
+        assert_eq!(folded_search(&pool, "RÉSUMÉ"), expected);
+
+        // Cyrillic.
+        let expected = BTreeSet::from(["Привет мир.txt", "ПРИВЕТ.md"]);
+        assert_eq!(folded_search(&pool, "привет"), expected);
+        assert_eq!(folded_search(&pool, "ПрИвЕт"), expected);
+
+        // Greek, including the final sigma.
+        assert_eq!(
+            folded_search(&pool, "ΚΟΣΜΟΣ"),
+            BTreeSet::from(["κοσμος.png"])
+        );
+        assert_eq!(
+            folded_search(&pool, "κόσμος"),
+            BTreeSet::from(["ΚΌΣΜΟΣ.png"])
+        );
+
+        // Sharp s folds to `ss`.
+        let expected = BTreeSet::from(["STRASSE.txt", "Straße.txt"]);
+        assert_eq!(folded_search(&pool, "strasse"), expected);
+        assert_eq!(folded_search(&pool, "STRAẞE"), expected);
+
+        // ASCII still matches like a case-insensitive search.
+        assert_eq!(
+            folded_search(&pool, "RESUME"),
+            BTreeSet::from(["resume.txt"])
+        );
+    }
+
+    #[test]
+    fn test_search_all_substr_no_common_match() {
+        let pool = NamePool::new();
+        pool.push("report 2024.pdf");
+        pool.push("budget 2023.xlsx");
+        pool.push("notes.txt");
+
+        assert!(all_substr(&pool, &["report", "2023"]).is_empty());
+        assert!(all_substr(&pool, &["report", "2024", "missing"]).is_empty());
+    }
+
+    #[test]
+    fn test_search_all_substr_edge_cases() {
+        let pool = NamePool::new();
+        pool.push("abc");
+        pool.push("xyz");
+
+        assert_eq!(all_substr(&pool, &[]), BTreeSet::from(["abc", "xyz"]));
+        assert_eq!(all_substr(&pool, &["", "b"]), BTreeSet::from(["abc"]));
+        // Repeated needles don't have to occur twice.
+        assert_eq!(all_substr(&pool, &["b", "c", "b"]), BTreeSet::from(["abc"]));
+        // Needles may overlap inside the name.
+        assert_eq!(all_substr(&pool, &["ab", "bc"]), BTreeSet::from(["abc"]));
+    }
+
     #[test]
     fn test_search_suffix() {
         let pool = NamePool::new();
@@ -305,6 +728,77 @@ mod tests {
         assert!(result.contains("hello world"));
     }
 
+    #[test]
+    fn test_search_prefix_suffix() {
+        let pool = NamePool::new();
+        pool.push("hello");
+        pool.push("world");
+        pool.push("hello world");
+        pool.push("hello.txt");
+        pool.push("help world");
+
+        let result = prefix_suffix_search(&pool, "hello", "world");
+        assert_eq!(result, BTreeSet::from(["hello world"]));
+
+        let result = prefix_suffix_search(&pool, "hel", "world");
+        assert_eq!(result, BTreeSet::from(["hello world", "help world"]));
+
+        let result = prefix_suffix_search(&pool, "hello", ".txt");
+        assert_eq!(result, BTreeSet::from(["hello.txt"]));
+
+        // Matches the intersection of the separate prefix and suffix searches here.
+        let both: BTreeSet<&str> = prefix_search(&pool, "hel")
+            .intersection(&suffix_search(&pool, "d"))
+            .copied()
+            .collect();
+        assert_eq!(prefix_suffix_search(&pool, "hel", "d"), both);
+
+        // Empty parts behave like the one-sided searches.
+        assert_eq!(
+            prefix_suffix_search(&pool, "hello", ""),
+            prefix_search(&pool, "hello")
+        );
+        assert_eq!(
+            prefix_suffix_search(&pool, "", "world"),
+            suffix_search(&pool, "world")
+        );
+        assert!(prefix_suffix_search(&pool, "world", "hello").is_empty());
+    }
+
+    #[test]
+    fn test_search_prefix_suffix_overlap() {
+        let pool = NamePool::new();
+        pool.push("ab");
+        pool.push("aba");
+        pool.push("abba");
+        pool.push("a");
+
+        // Prefix and suffix may not share characters of the name.
+        assert_eq!(
+            prefix_suffix_search(&pool, "ab", "ba"),
+            BTreeSet::from(["abba"])
+        );
+        assert_eq!(
+            prefix_suffix_search(&pool, "ab", "b"),
+            BTreeSet::<&str>::new()
+        );
+        assert_eq!(
+            prefix_suffix_search(&pool, "a", "a"),
+            BTreeSet::from(["aba", "abba"])
+        );
+    }
+
+    #[test]
+    fn test_search_prefix_suffix_cancelled_returns_none() {
+        let pool = NamePool::new();
+        pool.push("alpha");
+        let token = CancellationToken::new(1);
+        // Move global active version forward so the token becomes cancelled.
+        let _ = CancellationToken::new(2);
+
+        assert!(pool.search_prefix_suffix("al", "ha", token).is_none());
+    }
+
     #[test]
     fn test_search_exact() {
         let pool = NamePool::new();
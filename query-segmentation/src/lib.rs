@@ -16,6 +16,24 @@ pub enum Segment<'s> {
     Exact(&'s str),
 }
 
+impl<'s> Segment<'s> {
+    /// The text of the segment, without its slashes.
+    pub fn value(&self) -> &'s str {
+        match *self {
+            Segment::Substr(value)
+            | Segment::Prefix(value)
+            | Segment::Suffix(value)
+            | Segment::Exact(value) => value,
+        }
+    }
+
+    /// Whether the segment holds a `*` or `?` wildcard, e.g. the middle of `/src/*/test`.
+    /// Such a segment is a pattern matched against whole components, whatever its variant.
+    pub fn is_wildcard(&self) -> bool {
+        self.value().contains(['*', '?'])
+    }
+}
+
 /// Process path-query string into segments.
 pub fn query_segmentation(query: &str) -> Vec<Segment<'_>> {
     #[derive(Clone, Copy)]
@@ -195,4 +213,39 @@ mod tests {
             vec![Segment::Exact("报告"), Segment::Exact("测试")]
         );
     }
+
+    #[test]
+    fn test_wildcard_segments() {
+        let segments = query_segmentation("/src/*/test");
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Exact("src"),
+                Segment::Exact("*"),
+                Segment::Prefix("test")
+            ]
+        );
+        let flags: Vec<bool> = segments.iter().map(Segment::is_wildcard).collect();
+        assert_eq!(flags, vec![false, true, false]);
+
+        let segments = query_segmentation("foo/ba?/baz");
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Suffix("foo"),
+                Segment::Exact("ba?"),
+                Segment::Prefix("baz")
+            ]
+        );
+        let flags: Vec<bool> = segments.iter().map(Segment::is_wildcard).collect();
+        assert_eq!(flags, vec![false, true, false]);
+        assert_eq!(segments[1].value(), "ba?");
+
+        assert!(query_segmentation("*.rs")[0].is_wildcard());
+        assert!(
+            !query_segmentation("/root/bar/")
+                .iter()
+                .any(Segment::is_wildcard)
+        );
+    }
 }
@@ -1,20 +1,30 @@
 use crate::{
-    FileNodes, NameIndex, SearchOptions, SearchResultNode, SlabIndex, SlabNode,
-    SlabNodeMetadataCompact, State, ThinSlab,
+    CustomFilters, FileNodes, NameAndParent, NameIndex, OptionSlabIndex, RankingWeights,
+    SearchOptions, SearchResultNode, SlabIndex, SlabNode, SlabNodeMetadataCompact, State, ThinSlab,
+    TypeTable,
+    fuzzy::fuzzy_words,
     highlight::derive_highlight_terms,
-    persistent::{PersistentStorage, read_cache_from_file, write_cache_to_file},
-    query_preprocessor::expand_query_home_dirs,
+    persistent::{
+        CacheCompression, DirtySet, JournalRecord, PersistentStorage, append_journal_record,
+        new_snapshot_id, read_cache_from_file, replay_journal, write_cache_to_file,
+    },
+    query::QueryContext,
+    query_preprocessor::{expand_query_home_dirs, scope_paths, strip_inert_toggles},
+    ranking::ranking_terms,
 };
-use anyhow::{Context, Result, anyhow};
-use cardinal_sdk::{EventFlag, FsEvent, ScanType, current_event_id};
+use anyhow::{Context, Result, anyhow, ensure};
+use cardinal_sdk::{EventFlag, FsEvent, PairedEvent, ScanType, current_event_id, pair_renames};
 use cardinal_syntax::{optimize_query, parse_query};
-use fswalk::{Node, NodeMetadata, WalkData, walk_it};
-use hashbrown::HashSet;
+use fswalk::{Node, NodeFileType, NodeMetadata, WalkData, walk_it};
+use hashbrown::{HashMap, HashSet};
+use jiff::{Timestamp, Zoned};
 use namepool::NamePool;
 use search_cancel::{CANCEL_CHECK_INTERVAL, CancellationToken};
 use std::{
+    borrow::Cow,
     ffi::OsStr,
     io::ErrorKind,
+    ops::ControlFlow,
     path::{Path, PathBuf},
     sync::{LazyLock, atomic::AtomicBool},
     time::Instant,
@@ -29,6 +39,40 @@ pub struct SearchCache {
     pub(crate) name_index: NameIndex,
     ignore_paths: Option<Vec<PathBuf>>,
     stop: Option<&'static AtomicBool>,
+    /// Reference "now" for relative date filters, the system clock is used if unset.
+    pub(crate) date_reference: Option<Zoned>,
+    pub(crate) type_table: TypeTable,
+    /// Bumped whenever nodes are added or removed, so results held across queries (see
+    /// [`crate::QuerySession`]) can tell they went stale.
+    pub(crate) generation: u64,
+    pub(crate) content_limits: ContentSearchLimits,
+    pub(crate) custom_filters: CustomFilters,
+    /// Sorted results of the last [`Self::query_page`] query, reused by its following pages.
+    page_cache: Option<PageCache>,
+    /// What [`Self::flush_incremental`] has to write on top of the snapshot the cache was read
+    /// from.
+    pub(crate) dirty: DirtySet,
+}
+
+/// Which files `content:` is allowed to read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentSearchLimits {
+    /// Files larger than this many bytes are never matched, `None` reads files of any size.
+    pub max_file_bytes: Option<u64>,
+    /// Skips files with a NUL byte in their first [`BINARY_SNIFF_BYTES`] bytes.
+    pub skip_binary: bool,
+}
+
+/// How much of a file `content:` inspects to decide whether it is binary.
+pub const BINARY_SNIFF_BYTES: u64 = 8 * 1024;
+
+impl Default for ContentSearchLimits {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: Some(64 * 1024 * 1024),
+            skip_binary: true,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -43,6 +87,92 @@ impl SearchOutcome {
     }
 }
 
+/// Order of the results of a search, see [`SearchOptions::order`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ResultOrder {
+    /// Whatever order the index produces them in, the cheapest.
+    #[default]
+    Index,
+    /// By `key`, see [`SortKey::sort`].
+    Sorted { key: SortKey, ascending: bool },
+    /// By relevance under the weights, best first. Ties keep the index order.
+    Ranked(RankingWeights),
+}
+
+/// Metadata results can be sorted by, see [`ResultOrder::Sorted`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Size,
+    DateModified,
+    DateCreated,
+}
+
+impl SortKey {
+    /// Sorts `results` by this key, ties broken by ascending path. Nodes without metadata
+    /// sort before all others when ascending.
+    pub fn sort(self, results: &mut [SearchResultNode], ascending: bool) {
+        results.sort_by(|a, b| self.order(a, b, ascending));
+    }
+
+    /// The order [`Self::sort`] puts `a` and `b` in.
+    fn order(
+        self,
+        a: &SearchResultNode,
+        b: &SearchResultNode,
+        ascending: bool,
+    ) -> std::cmp::Ordering {
+        let order = self.compare(a, b);
+        let order = if ascending { order } else { order.reverse() };
+        order.then_with(|| a.path.cmp(&b.path))
+    }
+
+    fn compare(self, a: &SearchResultNode, b: &SearchResultNode) -> std::cmp::Ordering {
+        match self {
+            SortKey::Name => a.path.file_name().cmp(&b.path.file_name()),
+            SortKey::Size => {
+                let size = |node: &SearchResultNode| node.metadata.as_ref().map(|m| m.size());
+                size(a).cmp(&size(b))
+            }
+            SortKey::DateModified => {
+                let mtime =
+                    |node: &SearchResultNode| node.metadata.as_ref().and_then(|m| m.mtime());
+                mtime(a).cmp(&mtime(b))
+            }
+            SortKey::DateCreated => {
+                let ctime =
+                    |node: &SearchResultNode| node.metadata.as_ref().and_then(|m| m.ctime());
+                ctime(a).cmp(&ctime(b))
+            }
+        }
+    }
+}
+
+/// Where [`SearchCache::query_page`] left off: the last result handed out, whose sort key and
+/// path the next page starts after.
+#[derive(Debug, Clone)]
+pub struct PageCursor {
+    sort: SortKey,
+    ascending: bool,
+    last: SearchResultNode,
+}
+
+/// Every result of a [`SearchCache::query_page`] query in page order, valid while the cache
+/// generation and settings stay the same.
+struct PageCache {
+    query: String,
+    options: SearchOptions,
+    generation: u64,
+    results: Vec<SearchResultNode>,
+}
+
+/// Matches of one query in their final order.
+struct QueryMatches {
+    nodes: Vec<SlabIndex>,
+    /// `nodes` expanded, when ordering them took it.
+    expanded: Option<Vec<SearchResultNode>>,
+}
+
 impl std::fmt::Debug for SearchCache {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SearchCache")
@@ -64,7 +194,11 @@ impl SearchCache {
         cancel: Option<&'static AtomicBool>,
     ) -> Result<Self> {
         read_cache_from_file(cache_path)
-            .and_then(|x| {
+            .and_then(|mut x| {
+                let appendable = replay_journal(cache_path, &mut x)?;
+                Ok((x, appendable))
+            })
+            .and_then(|(x, appendable)| {
                 (x.path == path)
                     .then_some(())
                     .ok_or_else(|| {
@@ -74,21 +208,28 @@ impl SearchCache {
                             &x.path
                         )
                     })
-                    .map(|()| x)
+                    .map(|()| (x, appendable))
             })
             .map(
-                |PersistentStorage {
-                     version: _,
-                     path,
-                     slab_root,
-                     slab,
-                     name_index,
-                     last_event_id,
-                 }| {
+                |(
+                    PersistentStorage {
+                        version: _,
+                        snapshot_id,
+                        path,
+                        slab_root,
+                        slab,
+                        name_index,
+                        last_event_id,
+                    },
+                    appendable,
+                )| {
                     // name pool construction speed is fast enough that caching it doesn't worth it.
                     let name_index = NameIndex::construct_name_pool(name_index);
                     let slab = FileNodes::new(path, slab, slab_root);
-                    Self::new(slab, last_event_id, name_index, ignore_paths, cancel)
+                    let mut cache =
+                        Self::new(slab, last_event_id, name_index, ignore_paths, cancel);
+                    cache.dirty = DirtySet::since(appendable.then_some(snapshot_id));
+                    cache
                 },
             )
     }
@@ -181,40 +322,218 @@ impl SearchCache {
             name_index,
             ignore_paths,
             stop: cancel,
+            date_reference: None,
+            type_table: TypeTable::default(),
+            generation: 0,
+            content_limits: ContentSearchLimits::default(),
+            custom_filters: CustomFilters::default(),
+            page_cache: None,
+            dirty: DirtySet::default(),
         }
     }
 
+    /// Pins the time `dm:today`, `dc:pastweek` and friends are resolved against, together with
+    /// the time zone absolute dates are interpreted in. `None` restores the system clock.
+    pub fn set_date_reference(&mut self, now: Option<Zoned>) {
+        self.date_reference = now;
+        self.page_cache = None;
+    }
+
+    /// Replaces the extension table used by `type:` and the type macros.
+    pub fn set_type_table(&mut self, table: TypeTable) {
+        self.type_table = table;
+        self.page_cache = None;
+    }
+
+    /// Sets the size cap and binary file handling of `content:`.
+    pub fn set_content_limits(&mut self, limits: ContentSearchLimits) {
+        self.content_limits = limits;
+        self.page_cache = None;
+    }
+
+    /// Replaces the handlers evaluating custom filters such as `proj:`.
+    pub fn set_custom_filters(&mut self, filters: CustomFilters) {
+        self.custom_filters = filters;
+        self.page_cache = None;
+    }
+
+    /// Swaps in a freshly walked cache while keeping the caller supplied query settings.
+    fn replace_with_rescanned(&mut self, mut new_cache: Self) {
+        new_cache.date_reference = self.date_reference.take();
+        new_cache.type_table = std::mem::take(&mut self.type_table);
+        new_cache.content_limits = self.content_limits;
+        new_cache.custom_filters = std::mem::take(&mut self.custom_filters);
+        new_cache.generation = self.generation + 1;
+        *self = new_cache;
+    }
+
     pub fn search_empty(&self, cancellation_token: CancellationToken) -> Option<Vec<SlabIndex>> {
         self.name_index.all_indices(cancellation_token)
     }
 
     #[cfg(test)]
     pub fn search(&mut self, line: &str) -> Result<Vec<SlabIndex>> {
-        self.search_with_options(line, SearchOptions::default(), CancellationToken::noop())
+        self.search_with_options(line, &SearchOptions::default(), CancellationToken::noop())
             .map(|outcome| outcome.nodes.unwrap_or_default())
     }
 
+    /// Matching nodes of `line` in the order `options` asks for. Orders other than
+    /// [`ResultOrder::Index`] expand every match to compare them; when the expanded nodes are
+    /// wanted anyway, [`Self::query_files_with_options`] doesn't expand them twice.
+    ///
+    /// A malformed query fails with an error that downcasts to [`cardinal_syntax::ParseError`],
+    /// carrying the message and byte position of the mistake.
     pub fn search_with_options(
         &mut self,
         line: &str,
-        options: SearchOptions,
+        options: &SearchOptions,
         cancellation_token: CancellationToken,
     ) -> Result<SearchOutcome> {
-        let parsed = parse_query(line).map_err(|err| anyhow!("Failed to parse query: {err}"))?;
-        let expanded = expand_query_home_dirs(parsed);
-        let optimized = optimize_query(expanded);
+        let (matches, highlights) = self.run_query(line, options, cancellation_token)?;
+        Ok(SearchOutcome::new(
+            matches.map(|matches| matches.nodes),
+            highlights,
+        ))
+    }
+
+    /// Parses, evaluates, filters and orders `line` once for every entry point, alongside its
+    /// highlight terms. `None` if cancelled.
+    fn run_query(
+        &mut self,
+        line: &str,
+        options: &SearchOptions,
+        cancellation_token: CancellationToken,
+    ) -> Result<(Option<QueryMatches>, Vec<String>)> {
+        let parsed = parse_query(line)
+            .map_err(|err| anyhow::Error::new(err).context("Failed to parse query"))?;
+        let optimized = optimize_query(strip_inert_toggles(expand_query_home_dirs(parsed)));
+        let context = QueryContext {
+            fuzzy_distance: options.fuzzy_distance,
+            ..QueryContext::default()
+        };
         let highlights = derive_highlight_terms(&optimized.expr);
         let search_time = Instant::now();
-        let result = self.evaluate_expr(&optimized.expr, options, cancellation_token);
+        let result = match &options.within {
+            Some(base) => {
+                // Results of an earlier search may point at nodes removed since.
+                let base = base
+                    .iter()
+                    .copied()
+                    .filter(|&index| self.file_nodes.get(index).is_some())
+                    .collect();
+                self.evaluate_within(&optimized.expr, base, options, context, cancellation_token)
+            }
+            None => self.evaluate_expr(&optimized.expr, options, context, cancellation_token),
+        };
         info!("Search time: {:?}", search_time.elapsed());
-        result.map(|nodes| SearchOutcome::new(nodes, highlights))
+        let Some(mut nodes) = result? else {
+            return Ok((None, highlights));
+        };
+        if !options.excluded_prefixes.is_empty() {
+            let scopes = scope_paths(&optimized.expr);
+            let excluded: HashSet<SlabIndex> = options
+                .excluded_prefixes
+                .iter()
+                .filter(|prefix| !scopes.iter().any(|scope| scope.starts_with(prefix)))
+                .filter_map(|prefix| self.node_index_for_raw_path(prefix))
+                .collect();
+            let mut kept = Vec::with_capacity(nodes.len());
+            for (i, node) in nodes.into_iter().enumerate() {
+                if i % CANCEL_CHECK_INTERVAL == 0 && cancellation_token.is_cancelled() {
+                    return Ok((None, highlights));
+                }
+                if !self.is_within(node, &excluded) {
+                    kept.push(node);
+                }
+            }
+            nodes = kept;
+        }
+        if options.fuzzy_distance.is_some() {
+            self.rank_fuzzy_matches(&mut nodes, &fuzzy_words(&optimized.expr), options);
+        }
+        let expanded = match options.order {
+            ResultOrder::Index => None,
+            ResultOrder::Sorted { key, ascending } => {
+                let results = match key {
+                    SortKey::Name => self.expand_file_nodes_inner::<false>(&nodes),
+                    _ => self.expand_file_nodes_inner::<true>(&nodes),
+                };
+                let mut pairs: Vec<_> = nodes.into_iter().zip(results).collect();
+                pairs.sort_by(|(_, a), (_, b)| key.order(a, b, ascending));
+                let (sorted, results) = pairs.into_iter().unzip();
+                nodes = sorted;
+                Some(results)
+            }
+            ResultOrder::Ranked(weights) => {
+                let terms = ranking_terms(&optimized.expr);
+                let now = self
+                    .date_reference
+                    .as_ref()
+                    .map_or_else(Timestamp::now, Zoned::timestamp)
+                    .as_second();
+                let results = if weights.recency == 0.0 {
+                    self.expand_file_nodes_inner::<false>(&nodes)
+                } else {
+                    self.expand_file_nodes_inner::<true>(&nodes)
+                };
+                let mut scored: Vec<_> = nodes
+                    .into_iter()
+                    .zip(results)
+                    .map(|(index, node)| {
+                        let score = weights.score(&node, &terms, now);
+                        (index, node, score)
+                    })
+                    .collect();
+                scored.sort_by(|(_, _, a), (_, _, b)| b.total_cmp(a));
+                let (ranked, results) = scored
+                    .into_iter()
+                    .map(|(index, node, _)| (index, node))
+                    .unzip();
+                nodes = ranked;
+                Some(results)
+            }
+        };
+        Ok((Some(QueryMatches { nodes, expanded }), highlights))
     }
 
-    /// Get the path of the node in the slab.
-    pub fn node_path(&self, index: SlabIndex) -> Option<PathBuf> {
+    /// Absolute path of the node at `index`, rebuilt by following parent links up to the
+    /// watch root. The root itself maps to the watch root; stale indices yield `None`.
+    pub fn path_of(&self, index: SlabIndex) -> Option<PathBuf> {
         self.file_nodes.node_path(index)
     }
 
+    /// Total size of the files under the node at `index`, recursively; a file is its own size.
+    /// Symlinks, entries whose metadata can't be read and stale indices count as zero. File
+    /// metadata missing from the cache is fetched, like `size:` does.
+    pub fn directory_size(&mut self, index: SlabIndex) -> u64 {
+        if self.file_nodes.get(index).is_none() {
+            return 0;
+        }
+        let mut total = 0u64;
+        let mut pending = vec![index];
+        while let Some(index) = pending.pop() {
+            let node = &self.file_nodes[index];
+            match node.metadata.file_type_hint() {
+                NodeFileType::Dir => pending.extend(node.children.iter().copied()),
+                NodeFileType::Symlink => {}
+                NodeFileType::File | NodeFileType::Unknown => {
+                    let metadata = self.ensure_metadata(index);
+                    if let Some(metadata) = metadata.as_ref() {
+                        if metadata.r#type() == NodeFileType::File {
+                            total = total.saturating_add(metadata.size());
+                        }
+                    }
+                }
+            }
+        }
+        total
+    }
+
+    /// Get the path of the node in the slab. Same as [`Self::path_of`].
+    pub fn node_path(&self, index: SlabIndex) -> Option<PathBuf> {
+        self.path_of(index)
+    }
+
     /// Locate the slab index for a path relative to the watch root.
     pub fn node_index_for_relative_path(&self, relative: &Path) -> Option<SlabIndex> {
         let mut current = self.file_nodes.root();
@@ -279,11 +598,16 @@ impl SearchCache {
         let index = self.file_nodes.insert(node);
         self.name_index
             .add_index(node_name.as_str(), index, &self.file_nodes);
+        self.dirty.mark(index, node_name.as_str());
         index
     }
 
     /// Removes a node by path and its children recursively.
-    fn remove_node_path(&mut self, path: &Path) -> Option<SlabIndex> {
+    fn remove_node_path(
+        &mut self,
+        path: &Path,
+        recorder: &mut ChangeRecorder,
+    ) -> Option<SlabIndex> {
         let mut current = self.file_nodes.root();
         for name in path.components().map(|x| x.as_os_str()) {
             if let Some(&index) = self.file_nodes[current]
@@ -296,15 +620,17 @@ impl SearchCache {
                 return None;
             }
         }
+        recorder.record_removal(self, current);
         self.remove_node(current);
         Some(current)
     }
 
     // Blindly try create node chain, it doesn't check if the path is really exist on disk.
-    fn create_node_chain(&mut self, path: &Path) -> SlabIndex {
+    fn create_node_chain(&mut self, path: &Path, recorder: &mut ChangeRecorder) -> SlabIndex {
         let mut current = self.file_nodes.root();
         let mut current_path = self.file_nodes.path().to_path_buf();
-        for name in path.components().map(|x| x.as_os_str()) {
+        let depth = path.components().count();
+        for (level, name) in path.components().map(|x| x.as_os_str()).enumerate() {
             current_path.push(name);
             current = if let Some(&index) = self.file_nodes[current]
                 .children
@@ -323,11 +649,16 @@ impl SearchCache {
                     name,
                     match metadata {
                         Some(metadata) => SlabNodeMetadataCompact::some(metadata),
+                        // Everything above the last component has to be a folder.
+                        None if level + 1 < depth => SlabNodeMetadataCompact::unaccessible()
+                            .with_file_type_hint(NodeFileType::Dir),
                         None => SlabNodeMetadataCompact::unaccessible(),
                     },
                 );
                 let index = self.push_node(node);
                 self.file_nodes[current].add_children(index);
+                self.dirty.mark_node(current);
+                recorder.added.push(index);
                 index
             };
         }
@@ -337,24 +668,30 @@ impl SearchCache {
     // `Self::scan_path_recursive`function returns index of the constructed node(with metadata provided).
     // - If path is not under the watch root, None is returned.
     // - Procedure contains metadata fetching, if metadata fetching failed, None is returned.
-    fn scan_path_recursive(&mut self, raw_path: &Path) -> Option<SlabIndex> {
+    fn scan_path_recursive(
+        &mut self,
+        raw_path: &Path,
+        recorder: &mut ChangeRecorder,
+    ) -> Option<SlabIndex> {
+        self.generation += 1;
         // Ensure path is under the watch root
         let Ok(path) = raw_path.strip_prefix(self.file_nodes.path()) else {
             return None;
         };
         if raw_path.symlink_metadata().err().map(|e| e.kind()) == Some(ErrorKind::NotFound) {
-            self.remove_node_path(path);
+            self.remove_node_path(path, recorder);
             return None;
         };
         let parent = path.parent().expect(
             "scan_path_recursive doesn't expected to scan root(should be filtered outside)",
         );
         // Ensure node of the path parent is existed
-        let parent = self.create_node_chain(parent);
+        let parent = self.create_node_chain(parent, recorder);
         // Remove node(if exists) and do a full rescan
         if let Some(&old_node) = self.file_nodes[parent].children.iter().find(|&&x| {
             path.file_name() == Some(OsStr::new(self.file_nodes[x].name_and_parent.as_str()))
         }) {
+            recorder.record_removal(self, old_node);
             self.remove_node(old_node);
         }
         // For incremental data, we need metadata
@@ -363,24 +700,95 @@ impl SearchCache {
             let node = self.create_node_slab_update_name_index_and_name_pool(Some(parent), &node);
             // Push the newly created node to the parent's children
             self.file_nodes[parent].add_children(node);
+            self.dirty.mark_node(parent);
+            recorder.record_addition(self, node);
             node
         })
     }
 
+    /// Moves the node at `from` (and its subtree) to `to` in place, keeping its index, for a
+    /// rename reported by FSEvents. Returns `None` without touching anything when the disk
+    /// doesn't agree (`from` still exists or `to` doesn't) or `from` isn't indexed, in which case
+    /// both paths should be rescanned instead.
+    fn move_node(
+        &mut self,
+        from: &Path,
+        to: &Path,
+        recorder: &mut ChangeRecorder,
+    ) -> Option<SlabIndex> {
+        let root = self.file_nodes.path();
+        let from_relative = from.strip_prefix(root).ok()?;
+        let to_relative = to.strip_prefix(root).ok()?;
+        let to_parent = to_relative.parent()?;
+        let to_name = to_relative.file_name()?;
+        if from_relative.as_os_str().is_empty()
+            || to.starts_with(from)
+            || from.symlink_metadata().err().map(|e| e.kind()) != Some(ErrorKind::NotFound)
+            || to.symlink_metadata().is_err()
+        {
+            return None;
+        }
+        let index = self.node_index_for_relative_path(from_relative)?;
+        self.generation += 1;
+
+        let new_parent = self.create_node_chain(to_parent, recorder);
+        // A rename over an existing item replaces it.
+        if let Some(&replaced) = self.file_nodes[new_parent]
+            .children
+            .iter()
+            .find(|&&x| OsStr::new(self.file_nodes[x].name_and_parent.as_str()) == to_name)
+        {
+            recorder.record_removal(self, replaced);
+            self.remove_node(replaced);
+        }
+
+        // The name index orders nodes by path, so the whole subtree is re-added once it moved.
+        let subtree: Vec<SlabIndex> = std::iter::once(index)
+            .chain(
+                self.all_subnodes(index, CancellationToken::noop())
+                    .unwrap_or_default(),
+            )
+            .collect();
+        for &node in &subtree {
+            let name = self.file_nodes[node].name_and_parent.as_str();
+            self.name_index.remove_index(name, node);
+        }
+        if let Some(old_parent) = self.file_nodes[index].name_and_parent.parent() {
+            self.file_nodes[old_parent].children.retain(|&x| x != index);
+            self.dirty.mark_node(old_parent);
+        }
+        let name = NAME_POOL.push(to_name.to_string_lossy().as_ref());
+        self.file_nodes[index].name_and_parent =
+            NameAndParent::new(name, OptionSlabIndex::from_option(Some(new_parent)));
+        self.file_nodes[new_parent].add_children(index);
+        self.dirty.mark_node(new_parent);
+        self.dirty.mark(index, name);
+        for &node in &subtree {
+            let name = self.file_nodes[node].name_and_parent.as_str();
+            self.name_index.add_index(name, node, &self.file_nodes);
+        }
+        recorder.renamed.extend(subtree);
+        Some(index)
+    }
+
     // `Self::scan_path_nonrecursive`function returns index of the constructed node.
     // - If path is not under the watch root, None is returned.
     // - Procedure contains metadata fetching, if metadata fetching failed, None is returned.
     #[allow(dead_code)]
-    fn scan_path_nonrecursive(&mut self, raw_path: &Path) -> Option<SlabIndex> {
+    fn scan_path_nonrecursive(
+        &mut self,
+        raw_path: &Path,
+        recorder: &mut ChangeRecorder,
+    ) -> Option<SlabIndex> {
         // Ensure path is under the watch root
         let Ok(path) = raw_path.strip_prefix(self.file_nodes.path()) else {
             return None;
         };
         if raw_path.symlink_metadata().err().map(|e| e.kind()) == Some(ErrorKind::NotFound) {
-            self.remove_node_path(path);
+            self.remove_node_path(path, recorder);
             return None;
         };
-        Some(self.create_node_chain(path))
+        Some(self.create_node_chain(path, recorder))
     }
 
     pub fn walk_data(&self) -> WalkData<'static> {
@@ -397,7 +805,7 @@ impl SearchCache {
             info!("Rescan cancelled.");
             return None;
         };
-        *self = new_cache;
+        self.replace_with_rescanned(new_cache);
         Some(())
     }
 
@@ -412,7 +820,7 @@ impl SearchCache {
             info!("Rescan cancelled.");
             return;
         };
-        *self = new_cache;
+        self.replace_with_rescanned(new_cache);
     }
 
     /// Removes a node and its children recursively by index.
@@ -423,12 +831,14 @@ impl SearchCache {
                     .name_index
                     .remove_index(node.name_and_parent.as_str(), index);
                 assert!(removed, "inconsistent name index and node");
+                cache.dirty.mark(index, node.name_and_parent.as_str());
             }
         }
 
         // Remove parent reference, make whole subtree unreachable.
         if let Some(parent) = self.file_nodes[index].name_and_parent.parent() {
             self.file_nodes[parent].children.retain(|&x| x != index);
+            self.dirty.mark_node(parent);
         }
         let mut stack = vec![index];
         while let Some(current) = stack.pop() {
@@ -438,12 +848,63 @@ impl SearchCache {
     }
 
     pub fn flush_to_file(self, cache_path: &Path) -> Result<()> {
+        self.flush_to_file_with_compression(cache_path, CacheCompression::default())
+    }
+
+    /// Like [`Self::flush_to_file`], but if the cache was read from `cache_path` only the nodes
+    /// and names that changed since are appended to a journal next to it, which is a lot
+    /// faster for a big index. Once the journal grows too large compared to the snapshot, the
+    /// whole cache is written again and the journal dropped.
+    pub fn flush_incremental(self, cache_path: &Path) -> Result<()> {
+        if let Some(base) = self.dirty.base {
+            let record = JournalRecord {
+                last_event_id: self.last_event_id,
+                slab_root: self.file_nodes.root(),
+                nodes: self
+                    .dirty
+                    .nodes
+                    .iter()
+                    .map(|&index| (index, self.file_nodes.get(index).map(Cow::Borrowed)))
+                    .collect(),
+                names: self
+                    .dirty
+                    .names
+                    .iter()
+                    .map(|&name| {
+                        (
+                            Cow::Borrowed(name),
+                            self.name_index.get(name).map(Cow::Borrowed),
+                        )
+                    })
+                    .collect(),
+            };
+            if append_journal_record(cache_path, base, &record)
+                .context("Append cache journal failed.")?
+            {
+                return Ok(());
+            }
+        }
+        self.flush_to_file(cache_path)
+    }
+
+    pub fn flush_to_file_with_compression(
+        self,
+        cache_path: &Path,
+        compression: CacheCompression,
+    ) -> Result<()> {
         let Self {
             file_nodes: slab,
             last_event_id,
             name_index,
             ignore_paths: _,
             stop: _,
+            date_reference: _,
+            type_table: _,
+            generation: _,
+            content_limits: _,
+            custom_filters: _,
+            page_cache: _,
+            dirty: _,
         } = self;
         let (path, slab_root, slab) = slab.into_parts();
         let name_index = name_index.into_persistent();
@@ -451,12 +912,14 @@ impl SearchCache {
             cache_path,
             PersistentStorage {
                 version: Num,
+                snapshot_id: new_snapshot_id(),
                 path,
                 slab_root,
                 slab,
                 name_index,
                 last_event_id,
             },
+            compression,
         )
         .context("Write cache to file failed.")
     }
@@ -480,21 +943,146 @@ impl SearchCache {
         query: String,
         cancellation_token: CancellationToken,
     ) -> Result<Option<Vec<SearchResultNode>>> {
-        self.query_files_with_options(query, SearchOptions::default(), cancellation_token)
+        self.query_files_with_options(query, &SearchOptions::default(), cancellation_token)
     }
 
+    /// [`Self::search_with_options`] with the matches expanded into [`SearchResultNode`]s.
+    /// Metadata is only fetched when the order needs it.
     pub fn query_files_with_options(
         &mut self,
         query: String,
-        options: SearchOptions,
+        options: &SearchOptions,
         cancellation_token: CancellationToken,
     ) -> Result<Option<Vec<SearchResultNode>>> {
-        self.search_with_options(&query, options, cancellation_token)
-            .map(|outcome| {
-                outcome
-                    .nodes
-                    .map(|nodes| self.expand_file_nodes_inner::<false>(&nodes))
-            })
+        let (matches, _) = self.run_query(&query, options, cancellation_token)?;
+        Ok(matches.map(|matches| match matches.expanded {
+            Some(expanded) => expanded,
+            None => self.expand_file_nodes_inner::<false>(&matches.nodes),
+        }))
+    }
+
+    /// Same results as [`Self::query_files_with_options`], handed to `sink` in chunks of up to
+    /// `chunk_size` nodes instead of one `Vec`.
+    ///
+    /// This isn't streaming from the matcher: the query is fully evaluated, filtered and
+    /// ordered before the first chunk. What is spread over the chunks is expanding the matches
+    /// into paths, so the first rows can be shown before every match is expanded, and
+    /// returning [`ControlFlow::Break`] from `sink` skips expanding the rest. Sorted and ranked
+    /// orders expand every match to order them, so for them only the hand-over is chunked.
+    ///
+    /// Returns how many nodes were handed over, `None` if cancelled.
+    pub fn query_files_in_chunks(
+        &mut self,
+        query: String,
+        options: &SearchOptions,
+        chunk_size: usize,
+        cancellation_token: CancellationToken,
+        mut sink: impl FnMut(Vec<SearchResultNode>) -> ControlFlow<()>,
+    ) -> Result<Option<usize>> {
+        ensure!(chunk_size > 0, "Chunk size must be positive");
+        let (matches, _) = self.run_query(&query, options, cancellation_token)?;
+        let Some(matches) = matches else {
+            return Ok(None);
+        };
+        let mut expanded = matches.expanded.map(Vec::into_iter);
+        let mut delivered = 0;
+        for chunk in matches.nodes.chunks(chunk_size) {
+            if cancellation_token.is_cancelled() {
+                return Ok(None);
+            }
+            let nodes = match expanded.as_mut() {
+                Some(expanded) => expanded.by_ref().take(chunk.len()).collect(),
+                None => self.expand_file_nodes_inner::<false>(chunk),
+            };
+            delivered += nodes.len();
+            if sink(nodes).is_break() {
+                break;
+            }
+        }
+        Ok(Some(delivered))
+    }
+
+    /// One page of [`Self::query_files_with_options`] under a [`ResultOrder::Sorted`] order:
+    /// the first `limit` results ordered after `cursor`, or from the start without one, plus
+    /// the cursor to pass for the next page. There is no next cursor once the last result has
+    /// been handed out.
+    ///
+    /// The first page costs as much as the whole query: every match is expanded, which stats
+    /// them all for keys other than [`SortKey::Name`], and sorted. The sorted results are
+    /// kept, so following pages of the same query and options only look up the cursor, until
+    /// the index changes or another query is paged.
+    pub fn query_page(
+        &mut self,
+        query: String,
+        options: &SearchOptions,
+        cursor: Option<&PageCursor>,
+        limit: usize,
+        cancellation_token: CancellationToken,
+    ) -> Result<Option<(Vec<SearchResultNode>, Option<PageCursor>)>> {
+        ensure!(limit > 0, "Page limit must be positive");
+        let ResultOrder::Sorted {
+            key: sort,
+            ascending,
+        } = options.order
+        else {
+            anyhow::bail!("Pages need a sorted order");
+        };
+        if let Some(cursor) = cursor {
+            ensure!(
+                cursor.sort == sort && cursor.ascending == ascending,
+                "Cursor was made for a different order"
+            );
+        }
+        let cached = self.page_cache.as_ref().is_some_and(|cache| {
+            cache.query == query && cache.options == *options && cache.generation == self.generation
+        });
+        if !cached {
+            let Some(results) =
+                self.query_files_with_options(query.clone(), options, cancellation_token)?
+            else {
+                return Ok(None);
+            };
+            self.page_cache = Some(PageCache {
+                query,
+                options: options.clone(),
+                generation: self.generation,
+                results,
+            });
+        }
+        let results = &self
+            .page_cache
+            .as_ref()
+            .expect("page cache was just filled")
+            .results;
+        let start = match cursor {
+            Some(cursor) => {
+                results.partition_point(|node| !sort.order(&cursor.last, node, ascending).is_lt())
+            }
+            None => 0,
+        };
+        let end = start.saturating_add(limit).min(results.len());
+        let page = results[start..end].to_vec();
+        let next = (end < results.len()).then(|| PageCursor {
+            sort,
+            ascending,
+            last: page[page.len() - 1].clone(),
+        });
+        Ok(Some((page, next)))
+    }
+
+    /// Whether `index` or one of its ancestors is in `roots`.
+    fn is_within(&self, index: SlabIndex, roots: &HashSet<SlabIndex>) -> bool {
+        if roots.is_empty() {
+            return false;
+        }
+        let mut current = Some(index);
+        while let Some(node) = current {
+            if roots.contains(&node) {
+                return true;
+            }
+            current = self.file_nodes[node].name_and_parent.parent();
+        }
+        false
     }
 
     /// Returns a node info vector with the same length as the input nodes.
@@ -509,36 +1097,51 @@ impl SearchCache {
     ) -> Vec<SearchResultNode> {
         nodes
             .iter()
-            .copied()
-            .map(|node_index| {
-                let path = self.node_path(node_index);
-                let metadata = self
-                    .file_nodes
-                    .get_mut(node_index)
-                    .map(|node| {
-                        match (node.metadata.state(), &path) {
-                            (State::None, Some(path)) if FETCH_META => {
-                                // try fetching metadata if it's not cached and cache them
-                                let metadata = match std::fs::symlink_metadata(path) {
-                                    Ok(metadata) => SlabNodeMetadataCompact::some(metadata.into()),
-                                    Err(_) => SlabNodeMetadataCompact::unaccessible(),
-                                };
-                                node.metadata = metadata;
-                                metadata
-                            }
-                            _ => node.metadata,
-                        }
-                    })
-                    .unwrap_or_else(SlabNodeMetadataCompact::unaccessible);
-                SearchResultNode {
-                    path: path.unwrap_or_default(),
-                    metadata,
+            .map(|&node_index| self.expand_file_node::<FETCH_META>(node_index))
+            .collect()
+    }
+
+    fn expand_file_node<const FETCH_META: bool>(
+        &mut self,
+        node_index: SlabIndex,
+    ) -> SearchResultNode {
+        let path = self.node_path(node_index);
+        let dirty = &mut self.dirty;
+        let metadata = self
+            .file_nodes
+            .get_mut(node_index)
+            .map(|node| {
+                match (node.metadata.state(), &path) {
+                    (State::None, Some(path)) if FETCH_META => {
+                        // try fetching metadata if it's not cached and cache them
+                        let metadata = match std::fs::symlink_metadata(path) {
+                            Ok(metadata) => SlabNodeMetadataCompact::some(metadata.into()),
+                            Err(_) => SlabNodeMetadataCompact::unaccessible()
+                                .with_file_type_hint(node.metadata.file_type_hint()),
+                        };
+                        node.metadata = metadata;
+                        dirty.mark_node(node_index);
+                        metadata
+                    }
+                    _ => node.metadata,
                 }
             })
-            .collect()
+            .unwrap_or_else(|| {
+                SlabNodeMetadataCompact::unaccessible().with_file_type_hint(NodeFileType::Unknown)
+            });
+        SearchResultNode {
+            path: path.unwrap_or_default(),
+            metadata,
+            file_type: metadata.file_type_hint(),
+        }
     }
 
-    pub fn handle_fs_events(&mut self, events: Vec<FsEvent>) -> Result<(), HandleFSEError> {
+    /// Applies a batch of filesystem events and reports which nodes were added, removed or
+    /// rescanned in place, so a live result view can patch itself instead of re-querying.
+    pub fn handle_fs_events(
+        &mut self,
+        events: Vec<FsEvent>,
+    ) -> Result<NodeChanges, HandleFSEError> {
         let max_event_id = events.iter().map(|e| e.id).max();
         // If rescan needed, early exit.
         if events.iter().any(|event| {
@@ -554,9 +1157,24 @@ impl SearchCache {
         }) {
             return Err(HandleFSEError::Rescan);
         }
-        for scan_path in scan_paths(events) {
+        let mut recorder = ChangeRecorder::default();
+        let mut events_to_scan = Vec::with_capacity(events.len());
+        for event in pair_renames(events) {
+            match event {
+                PairedEvent::Rename { from, to } => {
+                    if let Some(node) = self.move_node(&from.path, &to.path, &mut recorder) {
+                        info!("Node renamed: {node:?} {:?} -> {:?}", from.path, to.path);
+                    } else {
+                        events_to_scan.push(from);
+                        events_to_scan.push(to);
+                    }
+                }
+                PairedEvent::Single(event) => events_to_scan.push(event),
+            }
+        }
+        for scan_path in scan_paths(events_to_scan) {
             info!("Scanning path: {scan_path:?}");
-            let folder = self.scan_path_recursive(&scan_path);
+            let folder = self.scan_path_recursive(&scan_path, &mut recorder);
             if folder.is_some() {
                 info!("Node changed: {folder:?}");
             }
@@ -564,7 +1182,7 @@ impl SearchCache {
         if let Some(max_event_id) = max_event_id {
             self.update_last_event_id(max_event_id);
         }
-        Ok(())
+        Ok(recorder.finish(self))
     }
 }
 
@@ -660,6 +1278,96 @@ fn has_selected_ancestor(path: &Path, selected: &HashSet<PathBuf>) -> bool {
     false
 }
 
+/// Nodes touched by [`SearchCache::handle_fs_events`], each list sorted.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NodeChanges {
+    /// Nodes that didn't exist before the batch.
+    pub added: Vec<SlabIndex>,
+    /// Nodes gone after the batch, by the index they had. The slab may hand that index to an
+    /// entry of `added`, so apply removals first.
+    pub removed: Vec<SlabIndex>,
+    /// Nodes rescanned in place: same index and path, possibly new metadata.
+    pub modified: Vec<SlabIndex>,
+    /// Nodes that kept their index but live at a new path after a rename, including every
+    /// node under a renamed folder.
+    pub renamed: Vec<SlabIndex>,
+}
+
+impl NodeChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.renamed.is_empty()
+    }
+}
+
+/// Collects node additions and removals while a batch of events is applied.
+#[derive(Default)]
+struct ChangeRecorder {
+    added: Vec<SlabIndex>,
+    /// Paths are captured before removal so rescanned nodes can be matched up afterwards.
+    removed: Vec<(PathBuf, SlabIndex)>,
+    renamed: Vec<SlabIndex>,
+}
+
+impl ChangeRecorder {
+    fn record_addition(&mut self, cache: &SearchCache, index: SlabIndex) {
+        self.added.push(index);
+        self.added.extend(
+            cache
+                .all_subnodes(index, CancellationToken::noop())
+                .unwrap_or_default(),
+        );
+    }
+
+    fn record_removal(&mut self, cache: &SearchCache, index: SlabIndex) {
+        let subnodes = cache
+            .all_subnodes(index, CancellationToken::noop())
+            .unwrap_or_default();
+        for index in std::iter::once(index).chain(subnodes) {
+            if let Some(path) = cache.node_path(index) {
+                self.removed.push((path, index));
+            }
+        }
+    }
+
+    /// A node removed and re-created at the same path and index counts as modified.
+    fn finish(self, cache: &SearchCache) -> NodeChanges {
+        let mut added: HashMap<PathBuf, SlabIndex> = self
+            .added
+            .into_iter()
+            .filter_map(|index| Some((cache.node_path(index)?, index)))
+            .collect();
+        let mut changes = NodeChanges::default();
+        for (path, index) in self.removed {
+            if added.get(&path) == Some(&index) {
+                added.remove(&path);
+                changes.modified.push(index);
+            } else {
+                changes.removed.push(index);
+            }
+        }
+        changes.added = added.into_values().collect();
+        // Renamed nodes rescanned or removed later in the batch are reported as such.
+        changes.renamed = self
+            .renamed
+            .into_iter()
+            .filter(|index| {
+                cache.file_nodes.get(*index).is_some()
+                    && !changes.modified.contains(index)
+                    && !changes.removed.contains(index)
+            })
+            .collect();
+        changes.renamed.sort_unstable();
+        changes.renamed.dedup();
+        changes.added.sort_unstable();
+        changes.removed.sort_unstable();
+        changes.modified.sort_unstable();
+        changes
+    }
+}
+
 /// Error type for `SearchCache::handle_fs_event`.
 #[derive(Debug)]
 pub enum HandleFSEError {
@@ -993,8 +1701,9 @@ mod tests {
 
         let result = cache.search_with_options(
             "bar !foo",
-            SearchOptions {
+            &SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             token,
         );
@@ -1012,9 +1721,10 @@ mod tests {
         let mut cache = SearchCache::walk_fs(dir.to_path_buf());
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let indices =
-            guard_indices(cache.search_with_options("alpha.txt", opts, CancellationToken::noop()));
+            guard_indices(cache.search_with_options("alpha.txt", &opts, CancellationToken::noop()));
         assert_eq!(indices.len(), 1);
         let nodes = cache.expand_file_nodes(&indices);
         assert_eq!(nodes.len(), 1);
@@ -1022,9 +1732,10 @@ mod tests {
 
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let miss =
-            guard_indices(cache.search_with_options("gamma.txt", opts, CancellationToken::noop()));
+            guard_indices(cache.search_with_options("gamma.txt", &opts, CancellationToken::noop()));
         assert!(miss.is_empty());
     }
 
@@ -1041,18 +1752,20 @@ mod tests {
 
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let indices =
-            guard_indices(cache.search_with_options("alpha*.md", opts, CancellationToken::noop()));
+            guard_indices(cache.search_with_options("alpha*.md", &opts, CancellationToken::noop()));
         let nodes = cache.expand_file_nodes(&indices);
         assert_eq!(nodes.len(), 1);
         assert!(nodes[0].path.ends_with("alphaTwo.md"));
 
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let indices =
-            guard_indices(cache.search_with_options("alpha*.md", opts, CancellationToken::noop()));
+            guard_indices(cache.search_with_options("alpha*.md", &opts, CancellationToken::noop()));
         let nodes = cache.expand_file_nodes(&indices);
         assert_eq!(nodes.len(), 2);
         assert!(nodes.iter().any(|node| node.path.ends_with("AlphaOne.md")));
@@ -1070,10 +1783,11 @@ mod tests {
         let mut cache = SearchCache::walk_fs(dir.to_path_buf());
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let indices = guard_indices(cache.search_with_options(
             "content:memchr",
-            opts,
+            &opts,
             CancellationToken::noop(),
         ));
         assert_eq!(indices.len(), 1);
@@ -1082,10 +1796,11 @@ mod tests {
 
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
         let insensitive = guard_indices(cache.search_with_options(
             "content:MEMCHR",
-            opts,
+            &opts,
             CancellationToken::noop(),
         ));
         assert_eq!(insensitive.len(), 1);
@@ -1106,10 +1821,11 @@ mod tests {
         let mut cache = SearchCache::walk_fs(dir.to_path_buf());
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
         let indices = guard_indices(cache.search_with_options(
             "content:XYZ",
-            opts,
+            &opts,
             CancellationToken::noop(),
         ));
         assert_eq!(indices.len(), 1);
@@ -1129,8 +1845,9 @@ mod tests {
 
         let insensitive = guard_indices(cache.search_with_options(
             "content:a",
-            SearchOptions {
+            &SearchOptions {
                 case_insensitive: true,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -1138,8 +1855,9 @@ mod tests {
 
         let sensitive = guard_indices(cache.search_with_options(
             "content:a",
-            SearchOptions {
+            &SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -1148,8 +1866,9 @@ mod tests {
         // But searching for uppercase 'A' case-sensitively should also work
         let sensitive_upper = guard_indices(cache.search_with_options(
             "content:A",
-            SearchOptions {
+            &SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -1158,8 +1877,9 @@ mod tests {
         // Searching for 'z' should return nothing
         let no_match = guard_indices(cache.search_with_options(
             "content:z",
-            SearchOptions {
+            &SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -1182,8 +1902,9 @@ mod tests {
         let mut cache = SearchCache::walk_fs(dir.to_path_buf());
         let indices = guard_indices(cache.search_with_options(
             "content:XYZ",
-            SearchOptions {
+            &SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -1212,8 +1933,9 @@ mod tests {
         let query = format!("content:{needle}");
         let indices = guard_indices(cache.search_with_options(
             &query,
-            SearchOptions {
+            &SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -1249,8 +1971,9 @@ mod tests {
 
         let result = cache.search_with_options(
             "file_a",
-            SearchOptions {
+            &SearchOptions {
                 case_insensitive: false,
+                ..Default::default()
             },
             token,
         );
@@ -1801,6 +2524,101 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_query_files_in_chunks_matches_query_files() {
+        let temp_dir = TempDir::new("test_query_files_in_chunks").unwrap();
+        let root_path = temp_dir.path();
+        fs::create_dir_all(root_path.join("src")).unwrap();
+        for i in 0..10 {
+            fs::File::create(root_path.join(format!("src/stream_{i:02}.rs"))).unwrap();
+        }
+        fs::File::create(root_path.join("other.txt")).unwrap();
+
+        let mut cache = SearchCache::walk_fs(root_path.to_path_buf());
+        let sorted = SearchOptions {
+            order: ResultOrder::Sorted {
+                key: SortKey::Name,
+                ascending: false,
+            },
+            ..Default::default()
+        };
+        for options in [SearchOptions::default(), sorted] {
+            for q in ["", "stream_", "ext:rs", "stream_ | other", "missing"] {
+                let expected: Vec<PathBuf> = cache
+                    .query_files_with_options(q.to_string(), &options, CancellationToken::noop())
+                    .unwrap()
+                    .unwrap()
+                    .into_iter()
+                    .map(|node| node.path)
+                    .collect();
+                for chunk_size in [1, 3, 100] {
+                    let mut chunked = Vec::new();
+                    let count = cache
+                        .query_files_in_chunks(
+                            q.to_string(),
+                            &options,
+                            chunk_size,
+                            CancellationToken::noop(),
+                            |nodes| {
+                                assert!(!nodes.is_empty() && nodes.len() <= chunk_size);
+                                chunked.extend(nodes.into_iter().map(|node| node.path));
+                                ControlFlow::Continue(())
+                            },
+                        )
+                        .expect("query should succeed")
+                        .expect("noop cancellation token should not cancel");
+                    assert_eq!(chunked, expected, "chunk mismatch for {q:?} {options:?}");
+                    assert_eq!(count, expected.len());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_query_files_in_chunks_stops_on_break() {
+        let temp_dir = TempDir::new("test_query_files_in_chunks_break").unwrap();
+        let root_path = temp_dir.path();
+        for i in 0..10 {
+            fs::File::create(root_path.join(format!("stream_{i:02}.txt"))).unwrap();
+        }
+
+        let mut cache = SearchCache::walk_fs(root_path.to_path_buf());
+        let expected: Vec<PathBuf> = query(&mut cache, "stream_")
+            .into_iter()
+            .map(|node| node.path)
+            .collect();
+        let mut chunks = 0;
+        let mut chunked = Vec::new();
+        let count = cache
+            .query_files_in_chunks(
+                "stream_".to_string(),
+                &SearchOptions::default(),
+                3,
+                CancellationToken::noop(),
+                |nodes| {
+                    chunks += 1;
+                    chunked.extend(nodes.into_iter().map(|node| node.path));
+                    ControlFlow::Break(())
+                },
+            )
+            .expect("query should succeed")
+            .expect("noop cancellation token should not cancel");
+        assert_eq!(chunks, 1);
+        assert_eq!(count, 3);
+        assert_eq!(chunked, expected[..3]);
+        assert!(
+            cache
+                .query_files_in_chunks(
+                    "stream_".to_string(),
+                    &SearchOptions::default(),
+                    0,
+                    CancellationToken::noop(),
+                    |_| ControlFlow::Continue(()),
+                )
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_query_files_root_directory() {
         let temp_dir = TempDir::new("test_query_files_root").unwrap();
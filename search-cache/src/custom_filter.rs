@@ -0,0 +1,73 @@
+use crate::SlabNode;
+use cardinal_syntax::FilterArgument;
+use hashbrown::HashMap;
+use std::{fmt, sync::Arc};
+
+/// Evaluates a filter the query language doesn't know, such as `proj:` or
+/// `musiclastyear:`, see [`CustomFilters::register`].
+pub trait CustomFilter: Send + Sync {
+    /// Whether `node` passes the filter. `arg` is the text after the colon, if any.
+    fn matches(&self, node: &SlabNode, arg: Option<&FilterArgument>) -> bool;
+}
+
+impl<F> CustomFilter for F
+where
+    F: Fn(&SlabNode, Option<&FilterArgument>) -> bool + Send + Sync,
+{
+    fn matches(&self, node: &SlabNode, arg: Option<&FilterArgument>) -> bool {
+        self(node, arg)
+    }
+}
+
+/// What a custom filter without a registered handler does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownCustomFilter {
+    /// Fails the search, as with any other unsupported filter.
+    #[default]
+    Error,
+    /// Ignores the filter, every node passes.
+    MatchAll,
+}
+
+/// Handlers for custom filter names, set with [`crate::SearchCache::set_custom_filters`].
+#[derive(Clone, Default)]
+pub struct CustomFilters {
+    handlers: HashMap<String, Arc<dyn CustomFilter>>,
+    unknown: UnknownCustomFilter,
+}
+
+impl fmt::Debug for CustomFilters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomFilters")
+            .field("names", &self.handlers.keys().collect::<Vec<_>>())
+            .field("unknown", &self.unknown)
+            .finish()
+    }
+}
+
+impl CustomFilters {
+    /// Evaluates `name:` with `filter`, replacing any handler already registered for it.
+    /// Names are matched case-insensitively, like the built-in filters.
+    pub fn register(&mut self, name: &str, filter: impl CustomFilter + 'static) {
+        self.handlers
+            .insert(name.to_ascii_lowercase(), Arc::new(filter));
+    }
+
+    /// Removes the handler of `name`, returning whether there was one.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.handlers.remove(&name.to_ascii_lowercase()).is_some()
+    }
+
+    /// Sets what custom filters without a handler do.
+    pub fn set_unknown(&mut self, unknown: UnknownCustomFilter) {
+        self.unknown = unknown;
+    }
+
+    pub fn unknown(&self) -> UnknownCustomFilter {
+        self.unknown
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<Arc<dyn CustomFilter>> {
+        self.handlers.get(&name.to_ascii_lowercase()).cloned()
+    }
+}
@@ -21,6 +21,7 @@ impl FileNodes {
         self.root
     }
 
+    /// Absolute path of `index`, `None` if the node or one of its ancestors is gone.
     pub fn node_path(&self, index: SlabIndex) -> Option<PathBuf> {
         let mut current = index;
         let mut segments = vec![];
@@ -28,6 +29,10 @@ impl FileNodes {
             segments.push(self.slab.get(current)?.name_and_parent.as_str());
             current = parent;
         }
+        // A chain that stops short of the root belongs to a detached subtree.
+        if current != self.root {
+            return None;
+        }
         Some(
             self.path
                 .iter()
@@ -0,0 +1,162 @@
+use crate::{SearchCache, SearchOptions, SlabIndex, cache::NAME_POOL};
+use cardinal_syntax::{Expr, Term};
+use query_segmentation::{Segment, query_segmentation};
+use search_cancel::{CANCEL_CHECK_INTERVAL, CancellationToken};
+
+impl SearchCache {
+    /// Nodes whose name contains `needle` with at most `max_distance` edits.
+    pub(crate) fn evaluate_fuzzy_word(
+        &self,
+        needle: &str,
+        max_distance: u8,
+        options: &SearchOptions,
+        token: CancellationToken,
+    ) -> Option<Vec<SlabIndex>> {
+        let needle = fold_chars(needle, options.case_insensitive);
+        let mut haystack = Vec::new();
+        let mut row = Vec::new();
+        let names = NAME_POOL.search_by(
+            |name| {
+                haystack.clear();
+                haystack.extend(
+                    name.chars()
+                        .map(|ch| fold_char(ch, options.case_insensitive)),
+                );
+                substring_edit_distance(&needle, &haystack, &mut row) <= usize::from(max_distance)
+            },
+            token,
+        )?;
+        let mut nodes = Vec::with_capacity(names.len());
+        for (i, name) in names.iter().enumerate() {
+            if i % CANCEL_CHECK_INTERVAL == 0 && token.is_cancelled() {
+                return None;
+            }
+            if let Some(indices) = self.name_index.get(name) {
+                nodes.extend(indices.iter().copied());
+            }
+        }
+        Some(nodes)
+    }
+
+    /// Stable-sorts `nodes` by how close their name is to the closest of `words`, nodes
+    /// matched by something other than a fuzzy word go last.
+    pub(crate) fn rank_fuzzy_matches(
+        &self,
+        nodes: &mut [SlabIndex],
+        words: &[&str],
+        options: &SearchOptions,
+    ) {
+        let words: Vec<Vec<char>> = words
+            .iter()
+            .map(|word| fold_chars(word, options.case_insensitive))
+            .collect();
+        let mut row = Vec::new();
+        nodes.sort_by_cached_key(|&index| {
+            let name = fold_chars(
+                self.file_nodes[index].name_and_parent.as_str(),
+                options.case_insensitive,
+            );
+            words
+                .iter()
+                .map(|word| substring_edit_distance(word, &name, &mut row))
+                .min()
+                .unwrap_or(usize::MAX)
+        });
+    }
+}
+
+/// Word terms eligible for fuzzy matching: a plain name substring, no path segments or
+/// wildcards.
+pub(crate) fn fuzzy_needle(text: &str) -> Option<&str> {
+    match query_segmentation(text).as_slice() {
+        [Segment::Substr(needle)] if !needle.contains(['*', '?']) => Some(needle),
+        _ => None,
+    }
+}
+
+/// Fuzzy-eligible word terms of `expr`, in query order.
+pub(crate) fn fuzzy_words(expr: &Expr) -> Vec<&str> {
+    let mut words = Vec::new();
+    collect_fuzzy_words(expr, &mut words);
+    words
+}
+
+fn collect_fuzzy_words<'e>(expr: &'e Expr, words: &mut Vec<&'e str>) {
+    match expr {
+        Expr::Term(Term::Word(text)) => words.extend(fuzzy_needle(text)),
+        Expr::And(parts) | Expr::Or(parts) => {
+            for part in parts {
+                collect_fuzzy_words(part, words);
+            }
+        }
+        Expr::Near { left, right, .. } => {
+            collect_fuzzy_words(left, words);
+            collect_fuzzy_words(right, words);
+        }
+        // Negated words don't contribute to why a node matched.
+        Expr::Not(_) | Expr::Term(_) | Expr::Empty => {}
+    }
+}
+
+fn fold_char(ch: char, case_insensitive: bool) -> char {
+    if case_insensitive {
+        ch.to_lowercase().next().unwrap_or(ch)
+    } else {
+        ch
+    }
+}
+
+fn fold_chars(text: &str, case_insensitive: bool) -> Vec<char> {
+    text.chars()
+        .map(|ch| fold_char(ch, case_insensitive))
+        .collect()
+}
+
+/// Levenshtein distance between `needle` and its best matching substring of `haystack`
+/// (Sellers' algorithm). `row` is scratch space reused across calls.
+fn substring_edit_distance(needle: &[char], haystack: &[char], row: &mut Vec<usize>) -> usize {
+    // row[j]: distance of the needle prefix processed so far to a substring ending at j.
+    row.clear();
+    row.resize(haystack.len() + 1, 0);
+    for (i, &expected) in needle.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &actual) in haystack.iter().enumerate() {
+            let substitution = diagonal + usize::from(expected != actual);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j + 1] + 1).min(row[j] + 1);
+        }
+    }
+    row.iter().copied().min().unwrap_or(needle.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(needle: &str, haystack: &str) -> usize {
+        substring_edit_distance(
+            &fold_chars(needle, false),
+            &fold_chars(haystack, false),
+            &mut Vec::new(),
+        )
+    }
+
+    #[test]
+    fn substring_distance_ignores_surrounding_text() {
+        assert_eq!(distance("report", "my_report.txt"), 0);
+        assert_eq!(distance("reprot", "report.txt"), 2);
+        assert_eq!(distance("raport", "report.txt"), 1);
+        assert_eq!(distance("reportt", "report"), 1);
+        assert_eq!(distance("abc", ""), 3);
+        assert_eq!(distance("", "anything"), 0);
+    }
+
+    #[test]
+    fn only_plain_words_are_fuzzy() {
+        assert_eq!(fuzzy_needle("reprot"), Some("reprot"));
+        assert_eq!(fuzzy_needle("rep*"), None);
+        assert_eq!(fuzzy_needle("docs/reprot"), None);
+        assert_eq!(fuzzy_needle("/reprot"), None);
+    }
+}
@@ -1,16 +1,112 @@
 use cardinal_syntax::{ArgumentKind, Expr, FilterArgument, Term};
-use query_segmentation::{Segment, query_segmentation};
-use std::collections::BTreeSet;
+use query_segmentation::query_segmentation;
+use std::{collections::BTreeMap, ops::Range};
+
+/// Tweaks for [`derive_highlight_terms_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HighlightOptions {
+    /// Also emit the literal values of comparison and range arguments, e.g. `1gb` for
+    /// `size:>1GB` or `1mb` and `10mb` for `size:1mb..10mb`.
+    pub include_numeric: bool,
+    /// How quoted phrases turn into terms.
+    pub phrases: PhraseHighlight,
+}
+
+/// Terms emitted for a phrase such as `"hello  world"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PhraseHighlight {
+    /// The phrase as a single term: `hello  world`.
+    #[default]
+    Whole,
+    /// The phrase plus each of its whitespace separated words: `hello  world`, `hello`, `world`.
+    WholeAndWords,
+    /// Only the words: `hello`, `world`.
+    Words,
+}
 
 pub fn derive_highlight_terms(expr: &Expr) -> Vec<String> {
-    let mut collector = HighlightCollector::default();
+    derive_highlight_terms_with(expr, HighlightOptions::default())
+}
+
+pub fn derive_highlight_terms_with(expr: &Expr, options: HighlightOptions) -> Vec<String> {
+    collect_highlights(expr, options).into_keys().collect()
+}
+
+/// A highlight term with the casing the user typed, for echoing back in a UI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighlightTerm {
+    /// Lowercased form used for matching, as returned by [`derive_highlight_terms`].
+    pub key: String,
+    /// First spelling of `key` seen in the query.
+    pub display: String,
+}
+
+/// Same terms as [`derive_highlight_terms_with`], each paired with its original casing.
+pub fn derive_highlight_display_terms(
+    expr: &Expr,
+    options: HighlightOptions,
+) -> Vec<HighlightTerm> {
+    collect_highlights(expr, options)
+        .into_iter()
+        .map(|(key, display)| HighlightTerm { key, display })
+        .collect()
+}
+
+/// Byte ranges of `name` to bold for `terms`, matched case-insensitively. Ranges are sorted,
+/// and overlapping or touching matches are merged, so none of them overlap.
+pub fn highlight_ranges(name: &str, terms: &[String]) -> Vec<Range<usize>> {
+    // Lowercasing can change a char's byte length, so remember which char of `name` every
+    // byte of the folded name came from.
+    let mut folded = String::with_capacity(name.len());
+    let mut origin = Vec::with_capacity(name.len());
+    for (start, ch) in name.char_indices() {
+        for lower in ch.to_lowercase() {
+            folded.push(lower);
+            origin.extend(std::iter::repeat_n(
+                (start, start + ch.len_utf8()),
+                lower.len_utf8(),
+            ));
+        }
+    }
+
+    let mut ranges = Vec::new();
+    for term in terms {
+        let term: String = term.chars().flat_map(char::to_lowercase).collect();
+        if term.is_empty() {
+            continue;
+        }
+        let mut from = 0;
+        while let Some(pos) = folded[from..].find(&term) {
+            let at = from + pos;
+            ranges.push(origin[at].0..origin[at + term.len() - 1].1);
+            from = at + folded[at..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    ranges.sort_unstable_by_key(|range| (range.start, range.end));
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+fn collect_highlights(expr: &Expr, options: HighlightOptions) -> BTreeMap<String, String> {
+    let mut collector = HighlightCollector {
+        options,
+        terms: BTreeMap::new(),
+    };
     collector.collect_expr(expr);
-    collector.into_terms()
+    collector.terms
 }
 
-#[derive(Default)]
 struct HighlightCollector {
-    terms: BTreeSet<String>,
+    options: HighlightOptions,
+    /// Lowercased key to the first-seen spelling.
+    terms: BTreeMap<String, String>,
 }
 
 impl HighlightCollector {
@@ -19,6 +115,10 @@ impl HighlightCollector {
             Expr::Empty => {}
             Expr::Term(term) => self.collect_term(term),
             Expr::Not(inner) => self.collect_expr(inner),
+            Expr::Near { left, right, .. } => {
+                self.collect_expr(left);
+                self.collect_expr(right);
+            }
             Expr::And(parts) | Expr::Or(parts) => {
                 for part in parts {
                     self.collect_expr(part);
@@ -30,7 +130,7 @@ impl HighlightCollector {
     fn collect_term(&mut self, term: &Term) {
         match term {
             Term::Word(word) => self.collect_text(word),
-            Term::Phrase(word) => self.push(word.clone()),
+            Term::Phrase(phrase) => self.collect_phrase(phrase),
             Term::Filter(filter) => {
                 if let Some(argument) = &filter.argument {
                     self.collect_argument(argument);
@@ -48,7 +148,22 @@ impl HighlightCollector {
                     self.collect_text(value);
                 }
             }
-            ArgumentKind::Range(_) | ArgumentKind::Comparison(_) => {}
+            ArgumentKind::Range(range) => {
+                if self.options.include_numeric {
+                    for value in [&range.start, &range.end].into_iter().flatten() {
+                        self.push_literal(value);
+                    }
+                }
+            }
+            ArgumentKind::Comparison(comparison) => {
+                if self.options.include_numeric {
+                    self.push_literal(&comparison.value);
+                }
+            }
+            ArgumentKind::Dimensions { .. }
+            | ArgumentKind::Attributes(_)
+            | ArgumentKind::Date(_)
+            | ArgumentKind::Orientation(_) => {}
         }
     }
 
@@ -59,7 +174,7 @@ impl HighlightCollector {
 
         let segments = query_segmentation(value);
         if let Some(segment) = segments.last() {
-            let candidates = literal_chunks(segment_value(segment));
+            let candidates = literal_chunks(segment.value());
             if !candidates.is_empty() {
                 for candidate in candidates {
                     self.push(candidate);
@@ -73,12 +188,28 @@ impl HighlightCollector {
         }
     }
 
-    fn push(&mut self, candidate: String) {
-        self.terms.insert(candidate.to_lowercase());
+    fn collect_phrase(&mut self, phrase: &str) {
+        if self.options.phrases != PhraseHighlight::Words {
+            self.push(phrase.to_string());
+        }
+        if self.options.phrases != PhraseHighlight::Whole {
+            for word in phrase.split_whitespace() {
+                self.push(word.to_string());
+            }
+        }
     }
 
-    fn into_terms(self) -> Vec<String> {
-        self.terms.into_iter().collect()
+    fn push_literal(&mut self, value: &str) {
+        let value = value.trim();
+        if !value.is_empty() {
+            self.push(value.to_string());
+        }
+    }
+
+    fn push(&mut self, candidate: String) {
+        self.terms
+            .entry(candidate.to_lowercase())
+            .or_insert(candidate);
     }
 }
 
@@ -102,15 +233,6 @@ fn literal_chunks(value: &str) -> Vec<String> {
     }
 }
 
-fn segment_value<'a>(segment: &'a Segment<'a>) -> &'a str {
-    match segment {
-        Segment::Substr(value)
-        | Segment::Prefix(value)
-        | Segment::Suffix(value)
-        | Segment::Exact(value) => value,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2787,4 +2909,165 @@ mod tests {
         assert_eq!(terms[2], "mmm");
         assert_eq!(terms[3], "zzz");
     }
+
+    #[test]
+    fn test_numeric_literals_are_opt_in() {
+        let numeric = HighlightOptions {
+            include_numeric: true,
+            ..HighlightOptions::default()
+        };
+        let result = parse_query("size:>1GB report").unwrap();
+        assert_eq!(derive_highlight_terms(&result.expr), vec!["report"]);
+        assert_eq!(
+            derive_highlight_terms_with(&result.expr, HighlightOptions::default()),
+            vec!["report"]
+        );
+        assert_eq!(
+            derive_highlight_terms_with(&result.expr, numeric),
+            vec!["1gb", "report"]
+        );
+    }
+
+    #[test]
+    fn test_numeric_range_literals() {
+        let numeric = HighlightOptions {
+            include_numeric: true,
+            ..HighlightOptions::default()
+        };
+        let result = parse_query("size:1mb..10mb").unwrap();
+        assert!(derive_highlight_terms(&result.expr).is_empty());
+        assert_eq!(
+            derive_highlight_terms_with(&result.expr, numeric),
+            vec!["10mb", "1mb"]
+        );
+
+        let open = parse_query("size:..10mb").unwrap();
+        assert!(derive_highlight_terms(&open.expr).is_empty());
+        assert_eq!(
+            derive_highlight_terms_with(&open.expr, numeric),
+            vec!["10mb"]
+        );
+    }
+
+    #[test]
+    fn test_display_terms_keep_first_casing() {
+        let result = raw_parse_query("Report REPORT report Draft").unwrap();
+        let terms = derive_highlight_display_terms(&result.expr, HighlightOptions::default());
+        assert_eq!(
+            terms,
+            vec![
+                HighlightTerm {
+                    key: "draft".to_string(),
+                    display: "Draft".to_string(),
+                },
+                HighlightTerm {
+                    key: "report".to_string(),
+                    display: "Report".to_string(),
+                },
+            ]
+        );
+        let keys: Vec<String> = terms.into_iter().map(|term| term.key).collect();
+        assert_eq!(keys, derive_highlight_terms(&result.expr));
+    }
+
+    #[test]
+    fn test_display_terms_cover_phrases_and_filters() {
+        let result = raw_parse_query("\"Hello World\" ext:PDF hello*").unwrap();
+        let terms = derive_highlight_display_terms(&result.expr, HighlightOptions::default());
+        let pairs: Vec<(&str, &str)> = terms
+            .iter()
+            .map(|term| (term.key.as_str(), term.display.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("hello", "hello"),
+                ("hello world", "Hello World"),
+                ("pdf", "PDF")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_phrase_words_combined_and_split() {
+        let result = raw_parse_query("\"Hello   world\" hello").unwrap();
+        let with = |phrases| {
+            derive_highlight_terms_with(
+                &result.expr,
+                HighlightOptions {
+                    phrases,
+                    ..HighlightOptions::default()
+                },
+            )
+        };
+        assert_eq!(with(PhraseHighlight::Whole), vec!["hello", "hello   world"]);
+        assert_eq!(
+            with(PhraseHighlight::WholeAndWords),
+            vec!["hello", "hello   world", "world"]
+        );
+        assert_eq!(with(PhraseHighlight::Words), vec!["hello", "world"]);
+    }
+
+    #[test]
+    fn test_phrase_words_drop_empty_chunks() {
+        let result = raw_parse_query("\"  alpha \t beta  \"").unwrap();
+        let terms = derive_highlight_terms_with(
+            &result.expr,
+            HighlightOptions {
+                phrases: PhraseHighlight::Words,
+                ..HighlightOptions::default()
+            },
+        );
+        assert_eq!(terms, vec!["alpha", "beta"]);
+    }
+
+    fn terms(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_highlight_ranges_multiple_terms() {
+        let name = "Quarterly-Report-2024.PDF";
+        assert_eq!(
+            highlight_ranges(name, &terms(&["report", "pdf"])),
+            vec![10..16, 22..25]
+        );
+        assert_eq!(&name[10..16], "Report");
+        assert!(highlight_ranges(name, &terms(&["missing"])).is_empty());
+        assert!(highlight_ranges(name, &terms(&[""])).is_empty());
+    }
+
+    #[test]
+    fn test_highlight_ranges_every_occurrence() {
+        assert_eq!(highlight_ranges("abcabc", &terms(&["ABC"])), vec![0..6]);
+        assert_eq!(highlight_ranges("ab-ab", &terms(&["ab"])), vec![0..2, 3..5]);
+    }
+
+    #[test]
+    fn test_highlight_ranges_merge_overlaps() {
+        assert_eq!(
+            highlight_ranges("notebook.txt", &terms(&["note", "ebook", "book"])),
+            vec![0..8]
+        );
+        // Overlapping occurrences of the same term.
+        assert_eq!(highlight_ranges("aaaa", &terms(&["aaa"])), vec![0..4]);
+        // A term inside another.
+        assert_eq!(
+            highlight_ranges("database", &terms(&["base", "database"])),
+            vec![0..8]
+        );
+    }
+
+    #[test]
+    fn test_highlight_ranges_unicode_name() {
+        let name = "Ünïcode 文档 ÉTÉ.txt";
+        let ranges = highlight_ranges(name, &terms(&["ünï", "文档", "été"]));
+        let bolded: Vec<&str> = ranges.iter().map(|range| &name[range.clone()]).collect();
+        assert_eq!(bolded, vec!["Ünï", "文档", "ÉTÉ"]);
+
+        // `İ` lowercases to two chars; a match inside it bolds the whole char.
+        let name = "İstanbul.jpg";
+        assert_eq!(highlight_ranges(name, &terms(&["i"])), vec![0..2]);
+        assert_eq!(highlight_ranges(name, &terms(&["stan"])), vec![2..6]);
+    }
 }
@@ -1,27 +1,37 @@
 #![feature(str_from_raw_parts)]
 mod cache;
+mod custom_filter;
 mod file_nodes;
+mod fuzzy;
 mod highlight;
 mod metadata_cache;
 mod name_index;
 mod persistent;
 mod query;
 mod query_preprocessor;
+mod query_session;
+mod ranking;
 mod segment;
 mod slab;
 mod slab_node;
 mod type_and_size;
+mod type_table;
 
 pub use cache::*;
+pub use custom_filter::*;
 pub use file_nodes::*;
 pub use fswalk::WalkData;
+pub use highlight::*;
 pub use metadata_cache::*;
 pub use name_index::*;
 pub use persistent::*;
+pub use query_session::*;
+pub use ranking::*;
 pub use segment::*;
 pub use slab::*;
 pub use slab_node::*;
 pub use type_and_size::*;
+pub use type_table::*;
 
 #[cfg(test)]
 mod tests;
@@ -1,22 +1,130 @@
-use crate::{SlabIndex, SlabNode, ThinSlab, name_index::SortedSlabIndices};
-use anyhow::{Context, Result};
+use crate::{SlabIndex, SlabNode, ThinSlab, name_index::SortedSlabIndices, slab_node::SlabNodeV6};
+use anyhow::{Context, Result, bail};
+use hashbrown::HashSet;
 use serde::{Deserialize, Serialize};
 use std::{
+    borrow::Cow,
     collections::BTreeMap,
-    fs::{self, File},
-    io::{BufReader, BufWriter},
+    fs::{self, File, OpenOptions},
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
     path::{Path, PathBuf},
     thread::available_parallelism,
-    time::Instant,
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
-use tracing::info;
+use tracing::{info, warn};
 use typed_num::Num;
 
-const LSF_VERSION: i64 = 2;
+const LSF_VERSION: i64 = 7;
+
+/// Every cache file starts with these bytes followed by the little endian `u32` schema version,
+/// a [`CacheCompression`] tag byte and the little endian `u64` node count. The version is
+/// checked before decoding, so a layout change rejects old files instead of decoding them into
+/// garbage.
+const CACHE_MAGIC: &[u8; 4] = b"CRDL";
+pub(crate) const CACHE_SCHEMA_VERSION: u32 = LSF_VERSION as u32;
+/// The previous schema version, still read and migrated so that an update doesn't have to
+/// rewalk the disk.
+pub(crate) const LEGACY_SCHEMA_VERSION: u32 = 6;
+
+/// The cache file was written with a different schema version, the caller should rewalk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheVersionMismatch {
+    pub found: u32,
+    pub expected: u32,
+}
+
+impl std::fmt::Display for CacheVersionMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cache schema version mismatch: found {}, expected {}",
+            self.found, self.expected
+        )
+    }
+}
+
+impl std::error::Error for CacheVersionMismatch {}
+
+/// How the encoded cache following the header is compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheCompression {
+    None,
+    /// zstd at the given level, 1 (fastest) to 22 (smallest).
+    Zstd {
+        level: i32,
+    },
+}
+
+impl Default for CacheCompression {
+    fn default() -> Self {
+        Self::Zstd { level: 6 }
+    }
+}
+
+impl CacheCompression {
+    fn tag(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Zstd { .. } => 1,
+        }
+    }
+}
+
+struct CacheHeader {
+    version: u32,
+    zstd: bool,
+    /// Nodes in the cache when it was written.
+    node_count: u64,
+}
+
+fn read_header(input: &mut impl Read) -> Result<CacheHeader> {
+    let mut header = [0u8; 17];
+    input
+        .read_exact(&mut header)
+        .context("Failed to read cache header")?;
+    let (magic, rest) = header.split_at(4);
+    let (version, rest) = rest.split_at(4);
+    let (compression, node_count) = rest.split_at(1);
+    if magic != CACHE_MAGIC {
+        bail!("Unrecognized cache file header");
+    }
+    let found = u32::from_le_bytes(version.try_into().unwrap());
+    if found != CACHE_SCHEMA_VERSION && found != LEGACY_SCHEMA_VERSION {
+        return Err(CacheVersionMismatch {
+            found,
+            expected: CACHE_SCHEMA_VERSION,
+        }
+        .into());
+    }
+    let zstd = match compression[0] {
+        0 => false,
+        1 => true,
+        tag => bail!("Unknown cache compression tag {tag}"),
+    };
+    Ok(CacheHeader {
+        version: found,
+        zstd,
+        node_count: u64::from_le_bytes(node_count.try_into().unwrap()),
+    })
+}
+
+fn write_header(
+    output: &mut impl Write,
+    compression: CacheCompression,
+    node_count: u64,
+) -> Result<()> {
+    output.write_all(CACHE_MAGIC)?;
+    output.write_all(&CACHE_SCHEMA_VERSION.to_le_bytes())?;
+    output.write_all(&[compression.tag()])?;
+    output.write_all(&node_count.to_le_bytes())?;
+    Ok(())
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct PersistentStorage {
     pub version: Num<LSF_VERSION>,
+    /// Identifies this snapshot, the journal next to it only applies on top of the same id.
+    pub snapshot_id: u64,
     /// The last event id of the cache.
     pub last_event_id: u64,
     /// Root file path of the cache
@@ -27,34 +135,118 @@ pub struct PersistentStorage {
     pub name_index: BTreeMap<Box<str>, SortedSlabIndices>,
 }
 
+/// [`PersistentStorage`] as schema version 6 stored it, see [`SlabNodeV6`].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PersistentStorageV6 {
+    pub(crate) version: Num<6>,
+    pub(crate) snapshot_id: u64,
+    pub(crate) last_event_id: u64,
+    pub(crate) path: PathBuf,
+    pub(crate) slab_root: SlabIndex,
+    pub(crate) slab: ThinSlab<SlabNodeV6>,
+    pub(crate) name_index: BTreeMap<Box<str>, SortedSlabIndices>,
+}
+
+impl PersistentStorageV6 {
+    /// Keeps the snapshot id, so a journal appended later still applies to the file this was
+    /// read from. A journal written before the update has the old version and is ignored,
+    /// its changes come back with the file system events since `last_event_id`.
+    fn migrate(self) -> Result<PersistentStorage> {
+        let Self {
+            version: _,
+            snapshot_id,
+            last_event_id,
+            path,
+            slab_root,
+            slab: legacy_slab,
+            name_index,
+        } = self;
+        let mut slab = ThinSlab::try_new().context("Failed to allocate slab")?;
+        // Vacant slots stay vacant, so every index keeps pointing at the same node.
+        slab.patch(
+            legacy_slab
+                .iter()
+                .map(|(index, node)| (index, Some(node.into()))),
+        )
+        .context("Failed to migrate slab")?;
+        Ok(PersistentStorage {
+            version: Num,
+            snapshot_id,
+            last_event_id,
+            path,
+            slab_root,
+            slab,
+            name_index,
+        })
+    }
+}
+
 pub fn read_cache_from_file(path: &Path) -> Result<PersistentStorage> {
     let cache_decode_time = Instant::now();
     let mut bytes = vec![0u8; 4 * 1024];
-    let input = File::open(path).context("Failed to open cache file")?;
-    let input = zstd::Decoder::new(input).context("Failed to create zstd decoder")?;
+    let mut input = File::open(path).context("Failed to open cache file")?;
+    let header = read_header(&mut input)?;
+    let input: Box<dyn Read> = if header.zstd {
+        Box::new(zstd::Decoder::new(input).context("Failed to create zstd decoder")?)
+    } else {
+        Box::new(input)
+    };
     let mut input = BufReader::new(input);
-    let storage: PersistentStorage = postcard::from_io((&mut input, &mut bytes))
-        .context("Failed to decode cache, maybe the cache is corrupted")?
-        .0;
+    let storage = if header.version == LEGACY_SCHEMA_VERSION {
+        let storage: PersistentStorageV6 = postcard::from_io((&mut input, &mut bytes))
+            .context("Failed to decode cache, maybe the cache is corrupted")?
+            .0;
+        storage.migrate()?
+    } else {
+        postcard::from_io((&mut input, &mut bytes))
+            .context("Failed to decode cache, maybe the cache is corrupted")?
+            .0
+    };
     info!("Cache decode time: {:?}", cache_decode_time.elapsed());
     Ok(storage)
 }
 
-pub fn write_cache_to_file(path: &Path, storage: PersistentStorage) -> Result<()> {
+/// Node count recorded in the header of the cache at `path`, without decoding the rest.
+pub fn read_cache_node_count(path: &Path) -> Result<u64> {
+    let mut input = File::open(path).context("Failed to open cache file")?;
+    Ok(read_header(&mut input)?.node_count)
+}
+
+pub fn write_cache_to_file(
+    path: &Path,
+    storage: PersistentStorage,
+    compression: CacheCompression,
+) -> Result<()> {
     let cache_encode_time = Instant::now();
     let _ = fs::create_dir_all(path.parent().unwrap());
     let tmp_path = &path.with_extension(".sctmp");
     {
-        let output = File::create(tmp_path).context("Failed to create cache file")?;
-        let mut output = zstd::Encoder::new(output, 6).context("Failed to create zstd encoder")?;
-        output
-            .multithread(available_parallelism().map(|x| x.get() as u32).unwrap_or(4))
-            .context("Failed to create parallel zstd encoder")?;
-        let output = output.auto_finish();
+        let mut output = File::create(tmp_path).context("Failed to create cache file")?;
+        write_header(&mut output, compression, storage.slab.len() as u64)
+            .context("Failed to write cache header")?;
+        let output: Box<dyn Write> = match compression {
+            CacheCompression::None => Box::new(output),
+            CacheCompression::Zstd { level } => {
+                let mut output =
+                    zstd::Encoder::new(output, level).context("Failed to create zstd encoder")?;
+                output
+                    .multithread(available_parallelism().map(|x| x.get() as u32).unwrap_or(4))
+                    .context("Failed to create parallel zstd encoder")?;
+                Box::new(output.auto_finish())
+            }
+        };
         let mut output = BufWriter::new(output);
         postcard::to_io(&storage, &mut output).context("Failed to encode cache")?;
+        output.flush().context("Failed to flush cache file")?;
     }
     fs::rename(tmp_path, path).context("Failed to rename cache file")?;
+    // The journal belonged to the snapshot we just replaced.
+    match fs::remove_file(journal_path(path)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => {
+            warn!("Failed to remove stale cache journal: {e:?}")
+        }
+        _ => {}
+    }
     info!("Cache encode time: {:?}", cache_encode_time.elapsed());
     info!(
         "Cache size: {} MB",
@@ -66,3 +258,206 @@ pub fn write_cache_to_file(path: &Path, storage: PersistentStorage) -> Result<()
     );
     Ok(())
 }
+
+/// A fresh id for a snapshot about to be written.
+pub(crate) fn new_snapshot_id() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+/// Every journal starts with these bytes followed by the little endian `u32` schema version and
+/// the little endian `u64` id of the snapshot it applies to. After that come the
+/// [`JournalRecord`]s, each prefixed with its little endian `u32` byte length.
+const JOURNAL_MAGIC: &[u8; 4] = b"CRDJ";
+
+/// Once the journal would grow past `1 / JOURNAL_COMPACT_DIVISOR` of the snapshot it's folded
+/// into a fresh snapshot instead.
+const JOURNAL_COMPACT_DIVISOR: u64 = 2;
+
+/// Where the journal of the snapshot at `cache_path` lives.
+pub(crate) fn journal_path(cache_path: &Path) -> PathBuf {
+    cache_path.with_extension("journal")
+}
+
+/// Slab slots and names touched since the cache was read from its snapshot.
+#[derive(Debug, Default)]
+pub(crate) struct DirtySet {
+    /// Snapshot the changes are relative to, `None` when there is none to append to and the next
+    /// flush has to write the whole cache.
+    pub(crate) base: Option<u64>,
+    pub(crate) nodes: HashSet<SlabIndex>,
+    pub(crate) names: HashSet<&'static str>,
+}
+
+impl DirtySet {
+    pub(crate) fn since(base: Option<u64>) -> Self {
+        Self {
+            base,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn mark(&mut self, index: SlabIndex, name: &'static str) {
+        self.nodes.insert(index);
+        self.names.insert(name);
+    }
+
+    pub(crate) fn mark_node(&mut self, index: SlabIndex) {
+        self.nodes.insert(index);
+    }
+}
+
+/// The current state of every slot and name touched during one session, `None` for the ones
+/// that are gone.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct JournalRecord<'a> {
+    pub(crate) last_event_id: u64,
+    pub(crate) slab_root: SlabIndex,
+    pub(crate) nodes: Vec<(SlabIndex, Option<Cow<'a, SlabNode>>)>,
+    pub(crate) names: Vec<(Cow<'a, str>, Option<Cow<'a, SortedSlabIndices>>)>,
+}
+
+impl PersistentStorage {
+    fn apply(&mut self, record: JournalRecord<'_>) -> Result<()> {
+        self.last_event_id = record.last_event_id;
+        self.slab_root = record.slab_root;
+        self.slab
+            .patch(
+                record
+                    .nodes
+                    .into_iter()
+                    .map(|(index, node)| (index, node.map(Cow::into_owned))),
+            )
+            .context("Failed to patch slab")?;
+        for (name, indices) in record.names {
+            match indices {
+                Some(indices) => {
+                    self.name_index
+                        .insert(name.into_owned().into_boxed_str(), indices.into_owned());
+                }
+                None => {
+                    self.name_index.remove(&*name);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Applies the journal next to `cache_path` to `storage`. Returns whether later records can be
+/// appended to it: a journal of another snapshot or one that ends in a record torn by an
+/// interrupted flush can't, the next flush rewrites the whole cache instead.
+pub(crate) fn replay_journal(cache_path: &Path, storage: &mut PersistentStorage) -> Result<bool> {
+    let replay_time = Instant::now();
+    let input = match File::open(journal_path(cache_path)) {
+        Ok(input) => input,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(true),
+        Err(e) => return Err(e).context("Failed to open cache journal"),
+    };
+    let mut input = BufReader::new(input);
+    let mut header = [0u8; 16];
+    if input.read_exact(&mut header).is_err() {
+        warn!("Ignoring cache journal with a truncated header");
+        return Ok(false);
+    }
+    let (magic, rest) = header.split_at(4);
+    let (version, base) = rest.split_at(4);
+    if magic != JOURNAL_MAGIC
+        || u32::from_le_bytes(version.try_into().unwrap()) != CACHE_SCHEMA_VERSION
+        || u64::from_le_bytes(base.try_into().unwrap()) != storage.snapshot_id
+    {
+        warn!("Ignoring cache journal of another snapshot");
+        return Ok(false);
+    }
+
+    let mut records = 0;
+    let mut bytes = vec![];
+    loop {
+        let mut len = [0u8; 4];
+        match read_full(&mut input, &mut len)? {
+            0 => break,
+            4 => {}
+            _ => return Ok(torn_journal(records)),
+        }
+        bytes.resize(u32::from_le_bytes(len) as usize, 0);
+        if read_full(&mut input, &mut bytes)? != bytes.len() {
+            return Ok(torn_journal(records));
+        }
+        let record: JournalRecord =
+            postcard::from_bytes(&bytes).context("Failed to decode cache journal record")?;
+        storage.apply(record)?;
+        records += 1;
+    }
+    info!(
+        "Cache journal replay time: {:?}, records: {records}",
+        replay_time.elapsed()
+    );
+    Ok(true)
+}
+
+fn torn_journal(records: usize) -> bool {
+    warn!("Cache journal ends in a torn record, kept the first {records}");
+    false
+}
+
+/// Like [`Read::read_exact`], but reports how much was read when the input ends early.
+fn read_full(input: &mut impl Read, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e).context("Failed to read cache journal"),
+        }
+    }
+    Ok(filled)
+}
+
+/// Appends `record` to the journal of snapshot `base` at `cache_path`, starting the journal if
+/// there is none yet. Returns `false` without writing anything when the journal is due for
+/// compaction.
+pub(crate) fn append_journal_record(
+    cache_path: &Path,
+    base: u64,
+    record: &JournalRecord,
+) -> Result<bool> {
+    let encode_time = Instant::now();
+    let bytes = postcard::to_stdvec(record).context("Failed to encode cache journal record")?;
+    let snapshot_len = fs::metadata(cache_path).map_or(0, |metadata| metadata.len());
+    let journal_path = journal_path(cache_path);
+    let journal_len = fs::metadata(&journal_path).map_or(0, |metadata| metadata.len());
+    if journal_len + bytes.len() as u64 > snapshot_len / JOURNAL_COMPACT_DIVISOR {
+        info!(
+            "Cache journal would reach {} bytes, snapshot is {snapshot_len} bytes",
+            journal_len + bytes.len() as u64
+        );
+        return Ok(false);
+    }
+    let len = u32::try_from(bytes.len()).context("Cache journal record too large")?;
+
+    let mut output = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&journal_path)
+        .context("Failed to open cache journal")?;
+    let mut buf = Vec::with_capacity(16 + 4 + bytes.len());
+    if journal_len == 0 {
+        buf.extend_from_slice(JOURNAL_MAGIC);
+        buf.extend_from_slice(&CACHE_SCHEMA_VERSION.to_le_bytes());
+        buf.extend_from_slice(&base.to_le_bytes());
+    }
+    buf.extend_from_slice(&len.to_le_bytes());
+    buf.extend_from_slice(&bytes);
+    output
+        .write_all(&buf)
+        .context("Failed to append cache journal record")?;
+    output.sync_data().context("Failed to sync cache journal")?;
+    info!(
+        "Cache journal append time: {:?}, record size: {} bytes",
+        encode_time.elapsed(),
+        bytes.len()
+    );
+    Ok(true)
+}
@@ -1,63 +1,149 @@
 use crate::{
-    SearchCache, SearchOptions, SegmentKind, SegmentMatcher, SlabIndex, SlabNodeMetadataCompact,
-    build_segment_matchers, cache::NAME_POOL,
+    BINARY_SNIFF_BYTES, ContentSearchLimits, SearchCache, SearchOptions, SegmentKind,
+    SegmentMatcher, SlabIndex, SlabNodeMetadataCompact, UnknownCustomFilter, build_name_matchers,
+    build_segment_matchers,
+    cache::NAME_POOL,
+    fuzzy::fuzzy_needle,
+    query_preprocessor::is_toggle,
+    segment::NameMatch,
+    type_table::{BUNDLE_EXTENSIONS, TypeFilterTarget},
 };
 use anyhow::{Result, anyhow, bail};
 use cardinal_syntax::{
-    ArgumentKind, ComparisonOp, Expr, Filter, FilterArgument, FilterKind, RangeSeparator, Term,
+    ArgumentKind, ComparisonOp, DateKeyword, DateSpec, DateUnit, Expr, Filter, FilterArgument,
+    FilterKind, RangeSeparator, Term, strip_digit_separators,
 };
 use fswalk::NodeFileType;
-use hashbrown::HashSet;
-use jiff::{Timestamp, civil::Date, tz::TimeZone};
+use hashbrown::{HashMap, HashSet};
+use jiff::{Timestamp, Zoned, civil::Date, tz::TimeZone};
 use memchr::arch::all::rabinkarp;
-use query_segmentation::query_segmentation;
+use query_segmentation::{Segment, query_segmentation};
 use rayon::iter::{ParallelBridge, ParallelIterator};
 use regex::RegexBuilder;
 use search_cancel::{CANCEL_CHECK_INTERVAL, CancellationToken};
-use std::{collections::BTreeSet, fs::File, io::Read, path::Path};
+use std::{
+    borrow::Cow,
+    collections::BTreeSet,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    ops::{Bound, RangeBounds},
+    os::unix::fs::PermissionsExt,
+    path::Path,
+};
 
 pub(crate) const CONTENT_BUFFER_BYTES: usize = 64 * 1024;
 
+/// Per-query settings the evaluators need on top of [`SearchOptions`].
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct QueryContext {
+    /// Edit distance word terms tolerate, from [`SearchOptions::fuzzy_distance`].
+    pub(crate) fuzzy_distance: Option<u8>,
+    /// How much of a name words must cover, set from `wholeword:`/`wholefilename:`.
+    pub(crate) name_match: NameMatch,
+}
+
 impl SearchCache {
     pub(crate) fn evaluate_expr(
         &mut self,
         expr: &Expr,
-        options: SearchOptions,
+        options: &SearchOptions,
+        context: QueryContext,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
         match expr {
             Expr::Empty => Ok(self.search_empty(token)),
-            Expr::Term(term) => self.evaluate_term(term, options, token),
-            Expr::Not(inner) => self.evaluate_not(inner, None, options, token),
-            Expr::And(parts) => self.evaluate_and(parts, options, token),
-            Expr::Or(parts) => self.evaluate_or(parts, options, token),
+            Expr::Term(term) => self.evaluate_term(term, options, context, token),
+            Expr::Not(inner) => self.evaluate_not(inner, None, options, context, token),
+            Expr::Near {
+                left,
+                right,
+                distance,
+            } => self.evaluate_near(left, right, *distance, options, context, token),
+            Expr::And(parts) => self.evaluate_and(parts, options, context, token),
+            Expr::Or(parts) => self.evaluate_or(parts, options, context, token),
+        }
+    }
+
+    /// Nodes of `base` matching `expr`, in `base` order. Filters and negations only look at
+    /// `base`; other terms are intersected with it.
+    pub(crate) fn evaluate_within(
+        &mut self,
+        expr: &Expr,
+        base: Vec<SlabIndex>,
+        options: &SearchOptions,
+        context: QueryContext,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        match expr {
+            Expr::Empty => Ok(Some(base)),
+            Expr::And(parts) => self.evaluate_and_from(parts, Some(base), options, context, token),
+            _ => self.evaluate_and_from(
+                std::slice::from_ref(expr),
+                Some(base),
+                options,
+                context,
+                token,
+            ),
         }
     }
 
     fn evaluate_and(
         &mut self,
         parts: &[Expr],
-        options: SearchOptions,
+        options: &SearchOptions,
+        context: QueryContext,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
-        let mut current: Option<Vec<SlabIndex>> = None;
+        self.evaluate_and_from(parts, None, options, context, token)
+    }
+
+    /// Intersects `parts` in order. A bare modifier toggle (`case:`) isn't a term: it switches
+    /// matching for the parts after it.
+    fn evaluate_and_from(
+        &mut self,
+        parts: &[Expr],
+        mut current: Option<Vec<SlabIndex>>,
+        options: &SearchOptions,
+        mut context: QueryContext,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let mut options = Cow::Borrowed(options);
         for part in parts {
             match part {
+                Expr::Term(Term::Filter(filter)) if is_toggle(filter) => match filter.kind {
+                    FilterKind::CaseSensitive => options.to_mut().case_insensitive = false,
+                    FilterKind::NoCase => options.to_mut().case_insensitive = true,
+                    FilterKind::WholeWord => context.name_match = NameMatch::WholeWord,
+                    FilterKind::WholeFilename => context.name_match = NameMatch::WholeFilename,
+                    FilterKind::NoWholeWord if context.name_match == NameMatch::WholeWord => {
+                        context.name_match = NameMatch::Substring
+                    }
+                    FilterKind::NoWholeFilename
+                        if context.name_match == NameMatch::WholeFilename =>
+                    {
+                        context.name_match = NameMatch::Substring
+                    }
+                    FilterKind::NoWholeWord | FilterKind::NoWholeFilename => {}
+                    _ => bail!("Filter {:?} is not supported yet", filter.kind),
+                },
                 Expr::Not(inner) => {
-                    let Some(x) = self.evaluate_not(inner, current, options, token)? else {
+                    let Some(x) = self.evaluate_not(inner, current, &options, context, token)?
+                    else {
                         return Ok(None);
                     };
                     current = Some(x);
                 }
                 Expr::Term(Term::Filter(filter)) => {
                     let base = current.take();
-                    let Some(nodes) = self.evaluate_filter(filter, base, options, token)? else {
+                    let Some(nodes) =
+                        self.evaluate_filter(filter, base, &options, context, token)?
+                    else {
                         return Ok(None);
                     };
                     current = Some(nodes);
                 }
                 _ => {
-                    let Some(nodes) = self.evaluate_expr(part, options, token)? else {
+                    let Some(nodes) = self.evaluate_expr(part, &options, context, token)? else {
                         return Ok(None);
                     };
                     current = Some(match current {
@@ -78,12 +164,18 @@ impl SearchCache {
     fn evaluate_or(
         &mut self,
         parts: &[Expr],
-        options: SearchOptions,
+        options: &SearchOptions,
+        context: QueryContext,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
+        if let Some(needles) = plain_needles(parts) {
+            if context.fuzzy_distance.is_none() && context.name_match == NameMatch::Substring {
+                return self.evaluate_any_substr(&needles, options, token);
+            }
+        }
         let mut result: Vec<SlabIndex> = Vec::new();
         for part in parts {
-            let candidate = self.evaluate_expr(part, options, token)?;
+            let candidate = self.evaluate_expr(part, options, context, token)?;
             let Some(nodes) = candidate else {
                 return Ok(None);
             };
@@ -94,11 +186,32 @@ impl SearchCache {
         Ok(Some(result))
     }
 
+    /// Nodes whose name contains any of `needles`, found in one pass over the name pool
+    /// instead of one per needle. Nodes come in name order like a single word's.
+    fn evaluate_any_substr(
+        &self,
+        needles: &[&str],
+        options: &SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let pattern = needles
+            .iter()
+            .map(|needle| regex::escape(needle))
+            .collect::<Vec<_>>()
+            .join("|");
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(options.case_insensitive)
+            .build()
+            .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
+        self.execute_matchers(&[SegmentMatcher::Regex { regex }], token)
+    }
+
     fn evaluate_not(
         &mut self,
         inner: &Expr,
         base: Option<Vec<SlabIndex>>,
-        options: SearchOptions,
+        options: &SearchOptions,
+        context: QueryContext,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
         let mut universe = if let Some(current) = base {
@@ -109,7 +222,7 @@ impl SearchCache {
                 None => return Ok(None),
             }
         };
-        if let Some(negated) = self.evaluate_expr(inner, options, token)? {
+        if let Some(negated) = self.evaluate_expr(inner, options, context, token)? {
             if difference_in_place(&mut universe, &negated, token).is_none() {
                 return Ok(None);
             }
@@ -119,40 +232,96 @@ impl SearchCache {
         Ok(Some(universe))
     }
 
+    /// Nodes matching both sides. When both are plain name substrings, their occurrences must
+    /// also be at most `distance` words apart in the name; other operands behave like AND.
+    fn evaluate_near(
+        &mut self,
+        left: &Expr,
+        right: &Expr,
+        distance: u32,
+        options: &SearchOptions,
+        context: QueryContext,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(mut nodes) = self.evaluate_expr(left, options, context, token)? else {
+            return Ok(None);
+        };
+        let Some(right_nodes) = self.evaluate_expr(right, options, context, token)? else {
+            return Ok(None);
+        };
+        if intersect_in_place(&mut nodes, &right_nodes, token).is_none() {
+            return Ok(None);
+        }
+        let (Some(left), Some(right)) = (plain_needle(left), plain_needle(right)) else {
+            return Ok(Some(nodes));
+        };
+        if context.fuzzy_distance.is_some() {
+            // Typo-tolerant operands don't occur verbatim in the name.
+            return Ok(Some(nodes));
+        }
+        let fold = |text: &str| {
+            if options.case_insensitive {
+                text.to_lowercase()
+            } else {
+                text.to_string()
+            }
+        };
+        let (left, right) = (fold(left), fold(right));
+        let mut kept = Vec::with_capacity(nodes.len());
+        for (i, index) in nodes.into_iter().enumerate() {
+            if i % CANCEL_CHECK_INTERVAL == 0 && token.is_cancelled() {
+                return Ok(None);
+            }
+            let name = fold(self.file_nodes[index].name_and_parent.as_str());
+            if words_within(&name, &left, &right, distance) {
+                kept.push(index);
+            }
+        }
+        Ok(Some(kept))
+    }
+
     fn evaluate_term(
         &mut self,
         term: &Term,
-        options: SearchOptions,
+        options: &SearchOptions,
+        context: QueryContext,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
         match term {
-            Term::Word(text) => self.evaluate_word(text, options, token),
-            Term::Phrase(text) => self.evaluate_phrase(text, options, token),
+            Term::Word(text) => self.evaluate_word(text, options, context, token),
+            Term::Phrase(text) => self.evaluate_phrase(text, options, context, token),
             Term::Regex(pattern) => self.evaluate_regex(pattern, options, token),
-            Term::Filter(filter) => self.evaluate_filter(filter, None, options, token),
+            Term::Filter(filter) => self.evaluate_filter(filter, None, options, context, token),
         }
     }
 
     fn evaluate_word(
         &self,
         text: &str,
-        options: SearchOptions,
+        options: &SearchOptions,
+        context: QueryContext,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
-        self.evaluate_phrase(text, options, token)
+        if let Some(max_distance) = context.fuzzy_distance {
+            if let Some(needle) = fuzzy_needle(text) {
+                return Ok(self.evaluate_fuzzy_word(needle, max_distance, options, token));
+            }
+        }
+        self.evaluate_phrase(text, options, context, token)
     }
 
     fn evaluate_phrase(
         &self,
         text: &str,
-        options: SearchOptions,
+        options: &SearchOptions,
+        context: QueryContext,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
         let segments = query_segmentation(text);
         if segments.is_empty() {
             bail!("Unprocessable term: {text:?}");
         }
-        let matchers = build_segment_matchers(&segments, options)
+        let matchers = build_name_matchers(&segments, context.name_match, options)
             .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
         self.execute_matchers(&matchers, token)
     }
@@ -198,6 +367,13 @@ impl SearchCache {
                         SegmentKind::Exact => NAME_POOL.search_exact(needle, token),
                     },
                     SegmentMatcher::Regex { regex } => NAME_POOL.search_regex(regex, token),
+                    SegmentMatcher::Glob { regex, literal } if !literal.is_empty() => {
+                        NAME_POOL.search_substr(literal, token).map(|mut names| {
+                            names.retain(|name| regex.is_match(name));
+                            names
+                        })
+                    }
+                    SegmentMatcher::Glob { regex, .. } => NAME_POOL.search_regex(regex, token),
                 };
                 let Some(names) = names else {
                     return Ok(None);
@@ -220,7 +396,7 @@ impl SearchCache {
     fn evaluate_regex(
         &self,
         pattern: &str,
-        options: SearchOptions,
+        options: &SearchOptions,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
         let mut builder = RegexBuilder::new(pattern);
@@ -228,23 +404,82 @@ impl SearchCache {
         let regex = builder
             .build()
             .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
+        if pattern.contains('/') {
+            return Ok(self.nodes_matching_path(token, |path| regex.is_match(path)));
+        }
         let matcher = SegmentMatcher::Regex { regex };
         self.execute_matchers(std::slice::from_ref(&matcher), token)
     }
 
+    /// Nodes whose absolute path satisfies `matches`. Paths are built while walking down from
+    /// the root, so each costs one append instead of a walk up its parents.
+    fn nodes_matching_path(
+        &self,
+        token: CancellationToken,
+        matches: impl Fn(&str) -> bool,
+    ) -> Option<Vec<SlabIndex>> {
+        let root = self.file_nodes.root();
+        let mut path = self.file_nodes.path().to_string_lossy().into_owned();
+        let mut nodes = Vec::new();
+        if matches(&path) {
+            nodes.push(root);
+        }
+        let mut visited = 0;
+        self.collect_path_matches(root, &mut path, &mut nodes, &mut visited, token, &matches)?;
+        Some(nodes)
+    }
+
+    fn collect_path_matches(
+        &self,
+        index: SlabIndex,
+        path: &mut String,
+        out: &mut Vec<SlabIndex>,
+        visited: &mut usize,
+        token: CancellationToken,
+        matches: &impl Fn(&str) -> bool,
+    ) -> Option<()> {
+        for &child in &self.file_nodes[index].children {
+            if *visited % CANCEL_CHECK_INTERVAL == 0 && token.is_cancelled() {
+                return None;
+            }
+            *visited += 1;
+            let len = path.len();
+            if !path.ends_with('/') {
+                path.push('/');
+            }
+            path.push_str(self.file_nodes[child].name_and_parent.as_str());
+            if matches(path) {
+                out.push(child);
+            }
+            self.collect_path_matches(child, path, out, visited, token, matches)?;
+            path.truncate(len);
+        }
+        Some(())
+    }
+
     fn evaluate_filter(
         &mut self,
         filter: &Filter,
         base: Option<Vec<SlabIndex>>,
-        options: SearchOptions,
+        options: &SearchOptions,
+        context: QueryContext,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
+        if filter.negated {
+            // See `fold_negated_filters`, same as the `Expr::Not` it was folded from.
+            let positive = Expr::Term(Term::Filter(Filter {
+                negated: false,
+                ..filter.clone()
+            }));
+            return self.evaluate_not(&positive, base, options, context, token);
+        }
         match filter.kind {
             FilterKind::File => self.evaluate_type_filter(
                 NodeFileType::File,
                 base,
                 filter.argument.as_ref(),
                 options,
+                context,
                 token,
             ),
             FilterKind::Folder => self.evaluate_type_filter(
@@ -252,6 +487,7 @@ impl SearchCache {
                 base,
                 filter.argument.as_ref(),
                 options,
+                context,
                 token,
             ),
             FilterKind::Ext => {
@@ -275,6 +511,28 @@ impl SearchCache {
                     .ok_or_else(|| anyhow!("infolder: requires a folder path"))?;
                 self.evaluate_infolder_filter(argument, base, token)
             }
+            FilterKind::Child => {
+                let argument = filter
+                    .argument
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("child: requires a file name pattern"))?;
+                self.evaluate_child_filter(argument, base, options, token)
+            }
+            FilterKind::Duplicate => {
+                self.evaluate_duplicate_filter(DuplicateKey::Name, base, token)
+            }
+            FilterKind::NamePartDuplicate => {
+                self.evaluate_duplicate_filter(DuplicateKey::NamePart, base, token)
+            }
+            FilterKind::SizeDuplicate => {
+                self.evaluate_duplicate_filter(DuplicateKey::Size, base, token)
+            }
+            FilterKind::DateModifiedDuplicate => {
+                self.evaluate_duplicate_filter(DuplicateKey::DateModified, base, token)
+            }
+            FilterKind::AttributeDuplicate => {
+                self.evaluate_duplicate_filter(DuplicateKey::Attributes, base, token)
+            }
             FilterKind::NoSubfolders => {
                 let argument = filter
                     .argument
@@ -282,25 +540,52 @@ impl SearchCache {
                     .ok_or_else(|| anyhow!("nosubfolders: requires a folder path"))?;
                 self.evaluate_nosubfolders_filter(argument, base, token)
             }
+            FilterKind::Path => {
+                let argument = filter
+                    .argument
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("path: requires a path fragment"))?;
+                self.evaluate_path_filter(argument, base, options, token)
+            }
             FilterKind::Type => {
                 let argument = filter
                     .argument
                     .as_ref()
                     .ok_or_else(|| anyhow!("type: requires a category"))?;
-                self.evaluate_named_type_filter(&argument.raw, base, options, token)
-            }
-            FilterKind::Audio => {
-                self.evaluate_type_macro("audio", base, filter.argument.as_ref(), options, token)
-            }
-            FilterKind::Video => {
-                self.evaluate_type_macro("video", base, filter.argument.as_ref(), options, token)
-            }
-            FilterKind::Doc => {
-                self.evaluate_type_macro("doc", base, filter.argument.as_ref(), options, token)
-            }
-            FilterKind::Exe => {
-                self.evaluate_type_macro("exe", base, filter.argument.as_ref(), options, token)
+                self.evaluate_named_type_filter(argument, base, options, context, token)
             }
+            FilterKind::Audio => self.evaluate_type_macro(
+                "audio",
+                base,
+                filter.argument.as_ref(),
+                options,
+                context,
+                token,
+            ),
+            FilterKind::Video => self.evaluate_type_macro(
+                "video",
+                base,
+                filter.argument.as_ref(),
+                options,
+                context,
+                token,
+            ),
+            FilterKind::Doc => self.evaluate_type_macro(
+                "doc",
+                base,
+                filter.argument.as_ref(),
+                options,
+                context,
+                token,
+            ),
+            FilterKind::Exe => self.evaluate_type_macro(
+                "exe",
+                base,
+                filter.argument.as_ref(),
+                options,
+                context,
+                token,
+            ),
             FilterKind::Size => {
                 let argument = filter
                     .argument
@@ -329,21 +614,79 @@ impl SearchCache {
                     .ok_or_else(|| anyhow!("content: requires a value"))?;
                 self.evaluate_content_filter(argument, base, options, token)
             }
+            FilterKind::CaseSensitive => {
+                self.evaluate_case_filter(base, filter.argument.as_ref(), options, context, token)
+            }
+            FilterKind::WholeWord => self.evaluate_name_match_filter(
+                NameMatch::WholeWord,
+                base,
+                filter.argument.as_ref(),
+                options,
+                context,
+                token,
+            ),
+            FilterKind::WholeFilename => self.evaluate_name_match_filter(
+                NameMatch::WholeFilename,
+                base,
+                filter.argument.as_ref(),
+                options,
+                context,
+                token,
+            ),
+            FilterKind::NoWholeWord | FilterKind::NoWholeFilename => self
+                .evaluate_name_match_filter(
+                    NameMatch::Substring,
+                    base,
+                    filter.argument.as_ref(),
+                    options,
+                    context,
+                    token,
+                ),
+            FilterKind::Custom(ref name) => {
+                self.evaluate_custom_filter(name, filter.argument.as_ref(), base, token)
+            }
             _ => bail!("Filter {:?} is not supported yet", filter.kind),
         }
     }
 
+    fn evaluate_custom_filter(
+        &self,
+        name: &str,
+        argument: Option<&FilterArgument>,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let handler = self.custom_filters.get(name);
+        if handler.is_none() && self.custom_filters.unknown() == UnknownCustomFilter::Error {
+            bail!("Filter {name}: has no registered handler");
+        }
+        let nodes = match base {
+            Some(nodes) => nodes,
+            None => match self.search_empty(token) {
+                Some(nodes) => nodes,
+                None => return Ok(None),
+            },
+        };
+        let Some(handler) = handler else {
+            return Ok(Some(nodes));
+        };
+        Ok(filter_nodes(nodes, token, |index| {
+            handler.matches(&self.file_nodes[index], argument)
+        }))
+    }
+
     fn evaluate_type_filter(
         &self,
         file_type: NodeFileType,
         base: Option<Vec<SlabIndex>>,
         argument: Option<&FilterArgument>,
-        options: SearchOptions,
+        options: &SearchOptions,
+        context: QueryContext,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
         let (mut nodes, argument_applied) = match (base, argument) {
             (Some(nodes), _) => (nodes, false),
-            (None, Some(arg)) => match self.evaluate_phrase(&arg.raw, options, token)? {
+            (None, Some(arg)) => match self.evaluate_phrase(&arg.raw, options, context, token)? {
                 Some(nodes) => (nodes, true),
                 None => return Ok(None),
             },
@@ -355,7 +698,7 @@ impl SearchCache {
 
         if !argument_applied {
             if let Some(arg) = argument {
-                let Some(matches) = self.evaluate_phrase(&arg.raw, options, token)? else {
+                let Some(matches) = self.evaluate_phrase(&arg.raw, options, context, token)? else {
                     return Ok(None);
                 };
                 if intersect_in_place(&mut nodes, &matches, token).is_none() {
@@ -369,6 +712,63 @@ impl SearchCache {
         }))
     }
 
+    /// `case:ABC` matches its argument byte-exactly. A bare `case:` is a toggle handled by
+    /// [`Self::evaluate_and_from`], so without an argument only `base` is kept.
+    fn evaluate_case_filter(
+        &self,
+        base: Option<Vec<SlabIndex>>,
+        argument: Option<&FilterArgument>,
+        options: &SearchOptions,
+        context: QueryContext,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(argument) = argument else {
+            return Ok(self.nodes_from_base(base, token));
+        };
+        let mut options = Cow::Borrowed(options);
+        options.to_mut().case_insensitive = false;
+        let Some(matches) = self.evaluate_phrase(&argument.raw, &options, context, token)? else {
+            return Ok(None);
+        };
+        let Some(mut nodes) = base else {
+            return Ok(Some(matches));
+        };
+        if intersect_in_place(&mut nodes, &matches, token).is_none() {
+            return Ok(None);
+        }
+        Ok(Some(nodes))
+    }
+
+    /// `wholeword:` and friends. A bare toggle is handled by [`Self::evaluate_and_from`], so
+    /// only an argument, matched under `name_match`, narrows `base`.
+    fn evaluate_name_match_filter(
+        &self,
+        name_match: NameMatch,
+        base: Option<Vec<SlabIndex>>,
+        argument: Option<&FilterArgument>,
+        options: &SearchOptions,
+        context: QueryContext,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(argument) = argument else {
+            return Ok(self.nodes_from_base(base, token));
+        };
+        let context = QueryContext {
+            name_match,
+            ..context
+        };
+        let Some(matches) = self.evaluate_phrase(&argument.raw, options, context, token)? else {
+            return Ok(None);
+        };
+        let Some(mut nodes) = base else {
+            return Ok(Some(matches));
+        };
+        if intersect_in_place(&mut nodes, &matches, token).is_none() {
+            return Ok(None);
+        }
+        Ok(Some(nodes))
+    }
+
     fn evaluate_extension_filter(
         &self,
         argument: &FilterArgument,
@@ -457,12 +857,18 @@ impl SearchCache {
             bail!("nosubfolders path {:?} is not a folder", argument.raw);
         }
 
-        let nodes = if let Some(nodes) = base
-            && nodes.len() <= self.file_nodes[target].children.len()
-        {
-            nodes
-        } else {
-            self.file_nodes[target].children.to_vec()
+        // Filter whichever candidate list is shorter, but never drop the constraints in `base`.
+        let children = &self.file_nodes[target].children;
+        let nodes = match base {
+            Some(nodes) if nodes.len() <= children.len() => nodes,
+            Some(base) => {
+                let mut nodes = children.to_vec();
+                if intersect_in_place(&mut nodes, &base, token).is_none() {
+                    return Ok(None);
+                }
+                nodes
+            }
+            None => children.to_vec(),
         };
 
         Ok(filter_nodes(nodes, token, |index| {
@@ -470,6 +876,146 @@ impl SearchCache {
         }))
     }
 
+    /// `path:fragment` keeps nodes whose absolute path contains `fragment`, following the
+    /// case sensitivity of the query. Unlike `infolder:` the fragment doesn't have to exist.
+    fn evaluate_path_filter(
+        &self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        options: &SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let matchers = build_segment_matchers(&[Segment::Substr(&argument.raw)], options)
+            .map_err(|err| anyhow!("Invalid path: fragment: {err}"))?;
+        let matcher = &matchers[0];
+        match base {
+            Some(nodes) => Ok(filter_nodes(nodes, token, |index| {
+                self.node_path(index)
+                    .is_some_and(|path| matcher.matches(&path.to_string_lossy()))
+            })),
+            None => Ok(self.nodes_matching_path(token, |path| matcher.matches(path))),
+        }
+    }
+
+    /// Folders with at least one direct child whose name matches the argument (wildcards
+    /// allowed, `;` separates alternatives).
+    fn evaluate_child_filter(
+        &self,
+        argument: &FilterArgument,
+        base: Option<Vec<SlabIndex>>,
+        options: &SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let patterns: Vec<&str> = match &argument.kind {
+            ArgumentKind::List(values) => values.iter().map(String::as_str).collect(),
+            _ => vec![argument.raw.as_str()],
+        };
+        let mut seen = HashSet::new();
+        let mut parents = Vec::new();
+        for pattern in patterns {
+            let matchers = build_segment_matchers(&[Segment::Substr(pattern)], options)
+                .map_err(|err| anyhow!("Invalid child: pattern: {err}"))?;
+            let Some(children) = self.execute_matchers(&matchers, token)? else {
+                return Ok(None);
+            };
+            for (i, child) in children.into_iter().enumerate() {
+                if i % CANCEL_CHECK_INTERVAL == 0 && token.is_cancelled() {
+                    return Ok(None);
+                }
+                if let Some(parent) = self.file_nodes[child].name_and_parent.parent() {
+                    if seen.insert(parent) {
+                        parents.push(parent);
+                    }
+                }
+            }
+        }
+        if let Some(mut nodes) = base {
+            if intersect_in_place(&mut nodes, &parents, token).is_none() {
+                return Ok(None);
+            }
+            Ok(Some(nodes))
+        } else {
+            Ok(Some(parents))
+        }
+    }
+
+    /// Nodes sharing `key` with at least one other node of `base` (every node if unset). Size
+    /// and date duplicates only consider files.
+    fn evaluate_duplicate_filter(
+        &mut self,
+        key: DuplicateKey,
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        let mut keyed = Vec::with_capacity(nodes.len());
+        for (i, index) in nodes.into_iter().enumerate() {
+            if i % (CANCEL_CHECK_INTERVAL / 4) == 0 && token.is_cancelled() {
+                return Ok(None);
+            }
+            if let Some(value) = self.duplicate_key_value(key, index) {
+                keyed.push((value, index));
+            }
+        }
+        let mut counts: HashMap<&DuplicateValue, usize> = HashMap::new();
+        for (value, _) in &keyed {
+            *counts.entry(value).or_default() += 1;
+        }
+        Ok(Some(
+            keyed
+                .iter()
+                .filter(|(value, _)| counts[value] > 1)
+                .map(|&(_, index)| index)
+                .collect(),
+        ))
+    }
+
+    fn duplicate_key_value(
+        &mut self,
+        key: DuplicateKey,
+        index: SlabIndex,
+    ) -> Option<DuplicateValue> {
+        let name = || self.file_nodes[index].name_and_parent.as_str();
+        match key {
+            DuplicateKey::Name => Some(DuplicateValue::Text(name().to_lowercase())),
+            DuplicateKey::NamePart => {
+                let name = name();
+                let stem = match name.rsplit_once('.') {
+                    Some((stem, _)) if !stem.is_empty() => stem,
+                    _ => name,
+                };
+                Some(DuplicateValue::Text(stem.to_lowercase()))
+            }
+            DuplicateKey::Size | DuplicateKey::DateModified => {
+                let metadata = self.ensure_metadata(index);
+                let metadata = metadata.as_ref()?;
+                if metadata.r#type() != NodeFileType::File {
+                    return None;
+                }
+                match key {
+                    DuplicateKey::Size => Some(DuplicateValue::Number(metadata.size())),
+                    _ => metadata
+                        .mtime()
+                        .map(|mtime| DuplicateValue::Number(u64::from(mtime.get()))),
+                }
+            }
+            DuplicateKey::Attributes => {
+                // File attributes aren't cached in the slab: permission bits plus the hidden
+                // (dot file) flag stand in for them, read fresh from disk.
+                let hidden = name().starts_with('.');
+                let path = self.node_path(index)?;
+                let metadata = std::fs::symlink_metadata(&path).ok()?;
+                Some(DuplicateValue::Attributes {
+                    is_dir: metadata.is_dir(),
+                    mode: metadata.permissions().mode() & 0o7777,
+                    hidden,
+                })
+            }
+        }
+    }
+
     fn keep_node_for_nosubfolders(&self, index: SlabIndex, root: SlabIndex) -> bool {
         index == root || {
             let node = &self.file_nodes[index];
@@ -478,22 +1024,46 @@ impl SearchCache {
         }
     }
 
+    /// `type:` with one category, or the union of a list of them (`type:picture,video`).
     fn evaluate_named_type_filter(
         &self,
-        raw: &str,
+        argument: &FilterArgument,
         base: Option<Vec<SlabIndex>>,
-        options: SearchOptions,
+        options: &SearchOptions,
+        context: QueryContext,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
-        let name = raw.trim();
-        if name.is_empty() {
-            bail!("type: requires a category");
-        }
-        let normalized = name.to_ascii_lowercase();
-        let Some(target) = lookup_type_group(&normalized) else {
-            bail!("Unknown type category: {name}");
+        let names: Vec<&str> = match &argument.kind {
+            ArgumentKind::List(values) => values.iter().map(|value| value.trim()).collect(),
+            _ => vec![argument.raw.trim()],
         };
-        self.apply_type_group(target, base, options, token)
+        let mut targets = Vec::with_capacity(names.len());
+        for name in names {
+            if name.is_empty() {
+                bail!("type: requires a category");
+            }
+            let normalized = name.to_ascii_lowercase();
+            let Some(target) = self.type_table.lookup(&normalized) else {
+                bail!("Unknown type category: {name}");
+            };
+            targets.push(target);
+        }
+        if targets.len() == 1 {
+            let target = targets.pop().expect("one category");
+            return self.apply_type_group(target, base, options, context, token);
+        }
+        let mut result = Vec::new();
+        for target in targets {
+            let Some(nodes) =
+                self.apply_type_group(target, base.clone(), options, context, token)?
+            else {
+                return Ok(None);
+            };
+            if union_in_place(&mut result, &nodes, token).is_none() {
+                return Ok(None);
+            }
+        }
+        Ok(Some(result))
     }
 
     fn evaluate_type_macro(
@@ -501,13 +1071,17 @@ impl SearchCache {
         name: &'static str,
         base: Option<Vec<SlabIndex>>,
         argument: Option<&FilterArgument>,
-        options: SearchOptions,
+        options: &SearchOptions,
+        context: QueryContext,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
         let group_nodes = self.apply_type_group(
-            lookup_type_group(name).expect("built-in macro should map to a known type group"),
+            self.type_table
+                .lookup(name)
+                .expect("built-in macro should map to a known type group"),
             base,
             options,
+            context,
             token,
         )?;
         let Some(mut nodes) = group_nodes else {
@@ -516,7 +1090,7 @@ impl SearchCache {
         let Some(argument) = argument else {
             return Ok(Some(nodes));
         };
-        let Some(matches) = self.evaluate_phrase(&argument.raw, options, token)? else {
+        let Some(matches) = self.evaluate_phrase(&argument.raw, options, context, token)? else {
             return Ok(None);
         };
         if intersect_in_place(&mut nodes, &matches, token).is_none() {
@@ -529,42 +1103,88 @@ impl SearchCache {
         &self,
         target: TypeFilterTarget,
         base: Option<Vec<SlabIndex>>,
-        options: SearchOptions,
+        options: &SearchOptions,
+        context: QueryContext,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
         match target {
             TypeFilterTarget::NodeType(file_type) => {
-                self.evaluate_type_filter(file_type, base, None, options, token)
+                self.evaluate_type_filter(file_type, base, None, options, context, token)
             }
-            TypeFilterTarget::Extensions(list) => self.filter_static_extensions(list, base, token),
+            TypeFilterTarget::Extensions(list) => self.filter_extensions(list, base, token),
+            TypeFilterTarget::Executables(list) => self.filter_executables(list, base, token),
         }
     }
 
-    fn filter_static_extensions(
+    /// Nodes matching `extensions`, plus files without an extension that have an execute bit
+    /// set, such as compiled binaries and scripts.
+    fn filter_executables(
         &self,
-        extensions: &'static [&'static str],
+        extensions: &[String],
         base: Option<Vec<SlabIndex>>,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
-        if extensions.is_empty() {
-            return Ok(Some(Vec::new()));
-        }
         let Some(nodes) = self.nodes_from_base(base, token) else {
             return Ok(None);
         };
         Ok(filter_nodes(nodes, token, |index| {
             let node = &self.file_nodes[index];
-            if node.metadata.file_type_hint() != NodeFileType::File {
-                return false;
+            if self.has_extension_in(index, extensions) {
+                return true;
             }
-            if let Some(ext) = extension_of(node.name_and_parent.as_str()) {
-                extensions.iter().any(|needle| *needle == ext)
-            } else {
-                false
+            node.metadata.file_type_hint() == NodeFileType::File
+                && extension_of(node.name_and_parent.as_str()).is_none()
+                && self.node_mode(index).is_some_and(|mode| mode & 0o111 != 0)
+        }))
+    }
+
+    /// Permission bits of `index` from its metadata, read from disk when they aren't recorded:
+    /// metadata isn't fetched yet, or came from a cache that didn't keep them (a real
+    /// `st_mode` is never 0, it includes the file type).
+    fn node_mode(&self, index: SlabIndex) -> Option<u32> {
+        if let Some(metadata) = self.file_nodes[index].metadata.as_ref() {
+            if metadata.mode() != 0 {
+                return Some(metadata.mode());
             }
+        }
+        let path = self.node_path(index)?;
+        let metadata = std::fs::symlink_metadata(path).ok()?;
+        Some(metadata.permissions().mode())
+    }
+
+    fn filter_extensions(
+        &self,
+        extensions: &[String],
+        base: Option<Vec<SlabIndex>>,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        if extensions.is_empty() {
+            return Ok(Some(Vec::new()));
+        }
+        let Some(nodes) = self.nodes_from_base(base, token) else {
+            return Ok(None);
+        };
+        Ok(filter_nodes(nodes, token, |index| {
+            self.has_extension_in(index, extensions)
         }))
     }
 
+    /// Whether `index` is a file, or a bundle folder, with one of `extensions`.
+    fn has_extension_in(&self, index: SlabIndex, extensions: &[String]) -> bool {
+        let node = &self.file_nodes[index];
+        let file_type = node.metadata.file_type_hint();
+        if file_type != NodeFileType::File && file_type != NodeFileType::Dir {
+            return false;
+        }
+        let Some(ext) = extension_of(node.name_and_parent.as_str()) else {
+            return false;
+        };
+        if file_type == NodeFileType::Dir && !BUNDLE_EXTENSIONS.contains(&ext.as_str()) {
+            return false;
+        }
+        extensions.contains(&ext)
+    }
+
     fn evaluate_size_filter(
         &mut self,
         argument: &FilterArgument,
@@ -594,7 +1214,7 @@ impl SearchCache {
         base: Option<Vec<SlabIndex>>,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
-        let context = DateContext::capture();
+        let context = DateContext::capture(self.date_reference.as_ref());
         let predicate = DatePredicate::parse(argument, &context)?;
         let Some(nodes) = self.nodes_from_base(base, token) else {
             return Ok(None);
@@ -611,7 +1231,7 @@ impl SearchCache {
         &mut self,
         argument: &FilterArgument,
         base: Option<Vec<SlabIndex>>,
-        options: SearchOptions,
+        options: &SearchOptions,
         token: CancellationToken,
     ) -> Result<Option<Vec<SlabIndex>>> {
         let ghost;
@@ -658,6 +1278,9 @@ impl SearchCache {
         let Ok(mut file) = File::open(path) else {
             return Some(false);
         };
+        if !self.content_limits_allow(&mut file) {
+            return Some(false);
+        }
 
         if needle.len() == 1 {
             let needle = needle[0];
@@ -743,6 +1366,37 @@ impl SearchCache {
         Some(false)
     }
 
+    /// Whether `file` passes the `content:` size cap and binary check. Leaves the file
+    /// positioned at its start.
+    fn content_limits_allow(&self, file: &mut File) -> bool {
+        let ContentSearchLimits {
+            max_file_bytes,
+            skip_binary,
+        } = self.content_limits;
+        if let Some(max_file_bytes) = max_file_bytes {
+            match file.metadata() {
+                Ok(metadata) if metadata.len() <= max_file_bytes => {}
+                _ => return false,
+            }
+        }
+        if skip_binary {
+            let mut head = Vec::new();
+            if file
+                .by_ref()
+                .take(BINARY_SNIFF_BYTES)
+                .read_to_end(&mut head)
+                .is_err()
+                || memchr::memchr(0, &head).is_some()
+            {
+                return false;
+            }
+            if file.seek(SeekFrom::Start(0)).is_err() {
+                return false;
+            }
+        }
+        true
+    }
+
     fn nodes_from_base(
         &self,
         base: Option<Vec<SlabIndex>>,
@@ -768,7 +1422,7 @@ impl SearchCache {
         .map(|value| value.get() as i64)
     }
 
-    fn ensure_metadata(&mut self, index: SlabIndex) -> SlabNodeMetadataCompact {
+    pub(crate) fn ensure_metadata(&mut self, index: SlabIndex) -> SlabNodeMetadataCompact {
         let current = self.file_nodes[index].metadata;
         if current.is_some() {
             return current;
@@ -778,13 +1432,85 @@ impl SearchCache {
             .expect("node index is not present in slab");
         let metadata = match std::fs::symlink_metadata(&path) {
             Ok(data) => SlabNodeMetadataCompact::some(data.into()),
-            Err(_) => SlabNodeMetadataCompact::unaccessible(),
+            Err(_) => SlabNodeMetadataCompact::unaccessible()
+                .with_file_type_hint(current.file_type_hint()),
         };
         self.file_nodes[index].metadata = metadata;
+        self.dirty.mark_node(index);
         metadata
     }
 }
 
+/// Property nodes are grouped by for the duplicate filters (`dupe:`, `sizedupe:`, ...).
+#[derive(Clone, Copy)]
+enum DuplicateKey {
+    Name,
+    NamePart,
+    Size,
+    DateModified,
+    Attributes,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum DuplicateValue {
+    Text(String),
+    Number(u64),
+    Attributes {
+        is_dir: bool,
+        mode: u32,
+        hidden: bool,
+    },
+}
+
+/// Plain name substring of a word or phrase term, see [`fuzzy_needle`].
+fn plain_needle(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Term(Term::Word(text) | Term::Phrase(text)) => fuzzy_needle(text),
+        _ => None,
+    }
+}
+
+/// Needles of an OR whose operands are all plain name substrings.
+fn plain_needles(parts: &[Expr]) -> Option<Vec<&str>> {
+    parts.iter().map(plain_needle).collect()
+}
+
+/// Whether occurrences of `left` and `right` in `name` are at most `distance` words apart,
+/// counted between the closest words of each. Words are runs of alphanumeric characters.
+fn words_within(name: &str, left: &str, right: &str, distance: u32) -> bool {
+    let mut word_starts = Vec::new();
+    let mut in_word = false;
+    for (offset, ch) in name.char_indices() {
+        let alphanumeric = ch.is_alphanumeric();
+        if alphanumeric && !in_word {
+            word_starts.push(offset);
+        }
+        in_word = alphanumeric;
+    }
+    let word_at = |offset: usize| {
+        word_starts
+            .partition_point(|&start| start <= offset)
+            .saturating_sub(1)
+    };
+    // First and last word of every occurrence.
+    let spans = |needle: &str| -> Vec<(usize, usize)> {
+        name.match_indices(needle)
+            .map(|(offset, text)| (word_at(offset), word_at(offset + text.len().max(1) - 1)))
+            .collect()
+    };
+    let right_spans = spans(right);
+    spans(left).into_iter().any(|(left_first, left_last)| {
+        right_spans.iter().any(|&(right_first, right_last)| {
+            let gap = if right_first > left_last {
+                right_first - left_last
+            } else {
+                left_first.saturating_sub(right_last)
+            };
+            gap <= distance as usize
+        })
+    })
+}
+
 fn normalize_extensions(argument: &FilterArgument) -> HashSet<String> {
     let mut values = HashSet::new();
     match &argument.kind {
@@ -821,79 +1547,6 @@ fn extension_of(name: &str) -> Option<String> {
     Some(name[pos + 1..].to_ascii_lowercase())
 }
 
-#[derive(Clone, Copy)]
-enum TypeFilterTarget {
-    NodeType(NodeFileType),
-    Extensions(&'static [&'static str]),
-}
-
-fn lookup_type_group(name: &str) -> Option<TypeFilterTarget> {
-    match name {
-        "file" | "files" => Some(TypeFilterTarget::NodeType(NodeFileType::File)),
-        "folder" | "folders" | "dir" | "directory" => {
-            Some(TypeFilterTarget::NodeType(NodeFileType::Dir))
-        }
-        "picture" | "pictures" | "image" | "images" | "photo" | "photos" => {
-            Some(TypeFilterTarget::Extensions(PICTURE_EXTENSIONS))
-        }
-        "video" | "videos" | "movie" | "movies" => {
-            Some(TypeFilterTarget::Extensions(VIDEO_EXTENSIONS))
-        }
-        "audio" | "audios" | "music" | "song" | "songs" => {
-            Some(TypeFilterTarget::Extensions(AUDIO_EXTENSIONS))
-        }
-        "doc" | "docs" | "document" | "documents" | "text" | "office" => {
-            Some(TypeFilterTarget::Extensions(DOCUMENT_EXTENSIONS))
-        }
-        "presentation" | "presentations" | "ppt" | "slides" => {
-            Some(TypeFilterTarget::Extensions(PRESENTATION_EXTENSIONS))
-        }
-        "spreadsheet" | "spreadsheets" | "xls" | "excel" | "sheet" | "sheets" => {
-            Some(TypeFilterTarget::Extensions(SPREADSHEET_EXTENSIONS))
-        }
-        "pdf" => Some(TypeFilterTarget::Extensions(PDF_EXTENSIONS)),
-        "archive" | "archives" | "compressed" | "zip" => {
-            Some(TypeFilterTarget::Extensions(ARCHIVE_EXTENSIONS))
-        }
-        "code" | "source" | "dev" => Some(TypeFilterTarget::Extensions(CODE_EXTENSIONS)),
-        "exe" | "exec" | "executable" | "executables" | "program" | "programs" | "app" | "apps" => {
-            Some(TypeFilterTarget::Extensions(EXECUTABLE_EXTENSIONS))
-        }
-        _ => None,
-    }
-}
-
-const PICTURE_EXTENSIONS: &[&str] = &[
-    "jpg", "jpeg", "png", "gif", "bmp", "tif", "tiff", "webp", "ico", "svg", "heic", "heif", "raw",
-    "arw", "cr2", "orf", "raf", "psd", "ai",
-];
-const VIDEO_EXTENSIONS: &[&str] = &[
-    "mp4", "m4v", "mov", "avi", "mkv", "wmv", "webm", "flv", "mpg", "mpeg", "3gp", "3g2", "ts",
-    "mts", "m2ts",
-];
-const AUDIO_EXTENSIONS: &[&str] = &[
-    "mp3", "wav", "flac", "aac", "ogg", "oga", "opus", "wma", "m4a", "alac", "aiff",
-];
-const DOCUMENT_EXTENSIONS: &[&str] = &[
-    "txt", "md", "rst", "doc", "docx", "rtf", "odt", "pdf", "pages", "rtfd",
-];
-const PRESENTATION_EXTENSIONS: &[&str] = &["ppt", "pptx", "key", "odp"];
-const SPREADSHEET_EXTENSIONS: &[&str] = &["xls", "xlsx", "csv", "numbers", "ods"];
-const PDF_EXTENSIONS: &[&str] = &["pdf"];
-const ARCHIVE_EXTENSIONS: &[&str] = &[
-    "zip", "rar", "7z", "tar", "gz", "tgz", "bz2", "xz", "zst", "cab", "iso", "dmg",
-];
-const CODE_EXTENSIONS: &[&str] = &[
-    "rs", "ts", "tsx", "js", "jsx", "c", "cc", "cpp", "cxx", "h", "hpp", "hh", "java", "cs", "py",
-    "go", "rb", "swift", "kt", "kts", "php", "html", "css", "scss", "sass", "less", "json", "yaml",
-    "yml", "toml", "ini", "cfg", "sh", "zsh", "fish", "ps1", "psm1", "sql", "lua", "pl", "pm", "r",
-    "m", "mm", "dart", "scala", "ex", "exs",
-];
-const EXECUTABLE_EXTENSIONS: &[&str] = &[
-    "exe", "msi", "bat", "cmd", "com", "ps1", "psm1", "app", "apk", "ipa", "jar", "bin", "run",
-    "pkg",
-];
-
 #[derive(Clone, Copy)]
 enum DateField {
     Modified,
@@ -906,12 +1559,20 @@ struct DateContext {
 }
 
 impl DateContext {
-    fn capture() -> Self {
-        let tz = TimeZone::system();
-        let zoned = Timestamp::now().to_zoned(tz.clone());
-        Self {
-            tz,
-            today: zoned.date(),
+    fn capture(reference: Option<&Zoned>) -> Self {
+        match reference {
+            Some(zoned) => Self {
+                tz: zoned.time_zone().clone(),
+                today: zoned.date(),
+            },
+            None => {
+                let tz = TimeZone::system();
+                let zoned = Timestamp::now().to_zoned(tz.clone());
+                Self {
+                    tz,
+                    today: zoned.date(),
+                }
+            }
         }
     }
 }
@@ -936,12 +1597,27 @@ impl DatePredicate {
     fn parse(argument: &FilterArgument, context: &DateContext) -> Result<Self> {
         match &argument.kind {
             ArgumentKind::Range(range) => {
+                // An excluded endpoint drops its whole period, like `>` and `<` do.
                 let start = match &range.start {
-                    Some(value) => Some(parse_date_value(value, context)?.start),
+                    Some(value) => {
+                        let value = parse_date_value(value, context)?;
+                        Some(if range.inclusive_start {
+                            value.start
+                        } else {
+                            value.end + 1
+                        })
+                    }
                     None => None,
                 };
                 let end = match &range.end {
-                    Some(value) => Some(parse_date_value(value, context)?.end),
+                    Some(value) => {
+                        let value = parse_date_value(value, context)?;
+                        Some(if range.inclusive_end {
+                            value.end
+                        } else {
+                            value.start.saturating_sub(1)
+                        })
+                    }
                     None => None,
                 };
                 if let (Some(s), Some(e)) = (start, end) {
@@ -977,7 +1653,15 @@ impl DatePredicate {
                 let value = parse_date_value(&argument.raw, context)?;
                 Ok(DatePredicate::range(Some(value.start), Some(value.end)))
             }
+            ArgumentKind::Date(spec) => {
+                let value = spec_range(*spec, context)
+                    .ok_or_else(|| anyhow!("Date {:?} is out of range", argument.raw))?;
+                Ok(DatePredicate::range(Some(value.start), Some(value.end)))
+            }
             ArgumentKind::List(_) => bail!("date filters do not accept lists"),
+            ArgumentKind::Dimensions { .. } => bail!("date filters do not accept dimensions"),
+            ArgumentKind::Attributes(_) => bail!("date filters do not accept attributes"),
+            ArgumentKind::Orientation(_) => bail!("date filters do not accept orientations"),
         }
     }
 
@@ -1017,8 +1701,9 @@ fn parse_date_value(raw: &str, context: &DateContext) -> Result<DateValue> {
     if trimmed.is_empty() {
         bail!("date filters require a value");
     }
-    if let Some(range) = keyword_range(trimmed, context) {
-        return Ok(range);
+    if let Some(spec) = DateSpec::parse(trimmed) {
+        return spec_range(spec, context)
+            .ok_or_else(|| anyhow!("Date {trimmed:?} is out of range"));
     }
     if let Some(date) = parse_absolute_date(trimmed) {
         if let Some(bounds) = day_bounds(date, context) {
@@ -1033,31 +1718,48 @@ fn parse_date_value(raw: &str, context: &DateContext) -> Result<DateValue> {
     bail!("Unrecognized date literal: {trimmed}");
 }
 
-fn keyword_range(keyword: &str, context: &DateContext) -> Option<DateValue> {
-    let lower = keyword.to_ascii_lowercase();
+fn spec_range(spec: DateSpec, context: &DateContext) -> Option<DateValue> {
+    match spec {
+        DateSpec::Keyword(keyword) => keyword_range(keyword, context),
+        DateSpec::Relative { n, unit } => {
+            // Units match the keywords: `past1months` is `pastmonth`.
+            let days = match unit {
+                DateUnit::Days => 1,
+                DateUnit::Weeks => 7,
+                DateUnit::Months => 30,
+                DateUnit::Years => 365,
+            };
+            trailing_range(context, i64::from(n).checked_mul(days)?)
+        }
+    }
+}
+
+fn keyword_range(keyword: DateKeyword, context: &DateContext) -> Option<DateValue> {
     let today = context.today;
     let year = today.year();
     let month = today.month();
-    match lower.as_str() {
-        "today" => day_bounds(today, context).map(|(s, e)| DateValue { start: s, end: e }),
-        "yesterday" => {
+    match keyword {
+        DateKeyword::Today => {
+            day_bounds(today, context).map(|(s, e)| DateValue { start: s, end: e })
+        }
+        DateKeyword::Yesterday => {
             let date = shift_days(today, -1)?;
             day_bounds(date, context).map(|(s, e)| DateValue { start: s, end: e })
         }
-        "thisweek" => {
+        DateKeyword::ThisWeek => {
             let weekday_offset = i64::from(today.weekday().to_monday_zero_offset());
             let start = shift_days(today, -weekday_offset)?;
             let end = shift_days(start, 6)?;
             range_from_dates(start, end, context)
         }
-        "lastweek" => {
+        DateKeyword::LastWeek => {
             let weekday_offset = i64::from(today.weekday().to_monday_zero_offset()) + 7;
             let start = shift_days(today, -weekday_offset)?;
             let end = shift_days(start, 6)?;
             range_from_dates(start, end, context)
         }
-        "thismonth" => month_range(year, month, context),
-        "lastmonth" => {
+        DateKeyword::ThisMonth => month_range(year, month, context),
+        DateKeyword::LastMonth => {
             let (year, month) = if month == 1 {
                 (year.checked_sub(1)?, 12)
             } else {
@@ -1065,12 +1767,11 @@ fn keyword_range(keyword: &str, context: &DateContext) -> Option<DateValue> {
             };
             month_range(year, month, context)
         }
-        "thisyear" => year_range(year, context),
-        "lastyear" => year_range(year.checked_sub(1)?, context),
-        "pastweek" => trailing_range(context, 7),
-        "pastmonth" => trailing_range(context, 30),
-        "pastyear" => trailing_range(context, 365),
-        _ => None,
+        DateKeyword::ThisYear => year_range(year, context),
+        DateKeyword::LastYear => year_range(year.checked_sub(1)?, context),
+        DateKeyword::PastWeek => trailing_range(context, 7),
+        DateKeyword::PastMonth => trailing_range(context, 30),
+        DateKeyword::PastYear => trailing_range(context, 365),
     }
 }
 
@@ -1182,7 +1883,7 @@ struct SizePredicate {
 
 enum SizePredicateKind {
     Comparison { op: ComparisonOp, value: u64 },
-    Range { min: Option<u64>, max: Option<u64> },
+    Range { min: Bound<u64>, max: Bound<u64> },
 }
 
 impl SizePredicate {
@@ -1216,8 +1917,8 @@ impl SizePredicate {
                 }
                 Ok(SizePredicate {
                     kind: SizePredicateKind::Range {
-                        min: start,
-                        max: end,
+                        min: size_bound(start, range.inclusive_start),
+                        max: size_bound(end, range.inclusive_end),
                     },
                 })
             }
@@ -1234,8 +1935,8 @@ impl SizePredicate {
         if let Some(range) = size_keyword(trimmed) {
             return Ok(SizePredicate {
                 kind: SizePredicateKind::Range {
-                    min: range.min,
-                    max: range.max,
+                    min: size_bound(range.min, true),
+                    max: size_bound(range.max, true),
                 },
             });
         }
@@ -1258,23 +1959,19 @@ impl SizePredicate {
                 ComparisonOp::Eq => size == *value,
                 ComparisonOp::Ne => size != *value,
             },
-            SizePredicateKind::Range { min, max } => {
-                if let Some(start) = min {
-                    if size < *start {
-                        return false;
-                    }
-                }
-                if let Some(end) = max {
-                    if size > *end {
-                        return false;
-                    }
-                }
-                true
-            }
+            SizePredicateKind::Range { min, max } => (*min, *max).contains(&size),
         }
     }
 }
 
+fn size_bound(value: Option<u64>, inclusive: bool) -> Bound<u64> {
+    match value {
+        Some(value) if inclusive => Bound::Included(value),
+        Some(value) => Bound::Excluded(value),
+        None => Bound::Unbounded,
+    }
+}
+
 struct SizeKeywordRange {
     min: Option<u64>,
     max: Option<u64>,
@@ -1319,7 +2016,8 @@ const KB: u64 = 1024;
 const MB: u64 = 1024 * 1024;
 
 fn parse_size_literal(raw: &str) -> Result<u64> {
-    let trimmed = raw.trim();
+    let trimmed = strip_digit_separators(raw.trim());
+    let trimmed = trimmed.as_ref();
     if trimmed.is_empty() {
         bail!("size: expected a number");
     }
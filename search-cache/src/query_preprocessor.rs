@@ -1,8 +1,8 @@
 use cardinal_syntax::{
-    ArgumentKind, ComparisonValue, Expr, Filter, FilterArgument, FilterKind, Query, RangeValue,
-    Term,
+    ArgumentKind, ComparisonValue, Expr, Filter, FilterArgument, FilterKind, ModifierScope, Query,
+    RangeValue, Term,
 };
-use std::env;
+use std::{env, path::PathBuf};
 
 pub(crate) fn expand_query_home_dirs(query: Query) -> Query {
     let Some(home) = home_dir() else { return query };
@@ -19,6 +19,15 @@ fn expand_expr(expr: Expr, home: &str) -> Expr {
         Expr::Empty => Expr::Empty,
         Expr::Term(term) => Expr::Term(expand_term(term, home)),
         Expr::Not(inner) => Expr::Not(Box::new(expand_expr(*inner, home))),
+        Expr::Near {
+            left,
+            right,
+            distance,
+        } => Expr::Near {
+            left: Box::new(expand_expr(*left, home)),
+            right: Box::new(expand_expr(*right, home)),
+            distance,
+        },
         Expr::And(parts) => Expr::And(
             parts
                 .into_iter()
@@ -53,11 +62,102 @@ fn expand_filter(mut filter: Filter, home: &str) -> Filter {
     filter
 }
 
+/// Drops bare modifier toggles (`case:`, `wfn:`) that cover no term: negated ones, OR
+/// alternatives, `NEAR` operands and the tail of an AND chain. The ones left switch matching
+/// for the terms after them (see `SearchCache::evaluate_and_from`) instead of being terms
+/// that match every node.
+pub(crate) fn strip_inert_toggles(mut query: Query) -> Query {
+    query.expr = strip_toggles(query.expr).unwrap_or(Expr::Empty);
+    query
+}
+
+fn strip_toggles(expr: Expr) -> Option<Expr> {
+    match expr {
+        Expr::Term(Term::Filter(filter)) if is_toggle(&filter) => None,
+        Expr::Not(inner) => strip_toggles(*inner).map(|inner| Expr::Not(Box::new(inner))),
+        Expr::Near {
+            left,
+            right,
+            distance,
+        } => match (strip_toggles(*left), strip_toggles(*right)) {
+            (Some(left), Some(right)) => Some(Expr::Near {
+                left: Box::new(left),
+                right: Box::new(right),
+                distance,
+            }),
+            (Some(side), None) | (None, Some(side)) => Some(side),
+            (None, None) => None,
+        },
+        Expr::Or(parts) => {
+            let mut kept: Vec<Expr> = parts.into_iter().filter_map(strip_toggles).collect();
+            match kept.len() {
+                0 => None,
+                1 => kept.pop(),
+                _ => Some(Expr::Or(kept)),
+            }
+        }
+        Expr::And(parts) => {
+            let mut kept = Vec::with_capacity(parts.len());
+            for part in parts {
+                match part {
+                    Expr::Term(Term::Filter(filter)) if is_toggle(&filter) && !filter.negated => {
+                        kept.push(Expr::Term(Term::Filter(filter)))
+                    }
+                    part => kept.extend(strip_toggles(part)),
+                }
+            }
+            while kept.last().is_some_and(
+                |part| matches!(part, Expr::Term(Term::Filter(filter)) if is_toggle(filter)),
+            ) {
+                kept.pop();
+            }
+            match kept.len() {
+                0 => None,
+                1 => kept.pop(),
+                _ => Some(Expr::And(kept)),
+            }
+        }
+        expr => Some(expr),
+    }
+}
+
+pub(crate) fn is_toggle(filter: &Filter) -> bool {
+    filter.modifier_scope() == Some(ModifierScope::Toggle)
+}
+
+/// Folders the query explicitly scopes itself to through `parent:`, `infolder:` or
+/// `nosubfolders:`. Negated filters exclude rather than scope, so they are skipped.
+pub(crate) fn scope_paths(expr: &Expr) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    collect_scope_paths(expr, &mut paths);
+    paths
+}
+
+fn collect_scope_paths(expr: &Expr, paths: &mut Vec<PathBuf>) {
+    match expr {
+        Expr::Term(Term::Filter(filter)) if filter_requires_path(&filter.kind) => {
+            if let Some(argument) = &filter.argument {
+                paths.push(PathBuf::from(&argument.raw));
+            }
+        }
+        Expr::And(parts) | Expr::Or(parts) => {
+            for part in parts {
+                collect_scope_paths(part, paths);
+            }
+        }
+        Expr::Near { left, right, .. } => {
+            collect_scope_paths(left, paths);
+            collect_scope_paths(right, paths);
+        }
+        Expr::Not(_) | Expr::Term(_) | Expr::Empty => {}
+    }
+}
+
 fn filter_requires_path(kind: &FilterKind) -> bool {
     // Only expand filters whose semantics require filesystem-like paths.
     matches!(
         kind,
-        FilterKind::Parent | FilterKind::InFolder | FilterKind::NoSubfolders
+        FilterKind::Parent | FilterKind::InFolder | FilterKind::NoSubfolders | FilterKind::Path
     )
 }
 
@@ -65,7 +165,12 @@ fn expand_filter_argument(argument: &mut FilterArgument, home: &str) {
     let raw = std::mem::take(&mut argument.raw);
     argument.raw = expand_text(raw, home);
     match &mut argument.kind {
-        ArgumentKind::Bare | ArgumentKind::Phrase => {}
+        ArgumentKind::Bare
+        | ArgumentKind::Phrase
+        | ArgumentKind::Dimensions { .. }
+        | ArgumentKind::Attributes(_)
+        | ArgumentKind::Date(_)
+        | ArgumentKind::Orientation(_) => {}
         ArgumentKind::List(values) => {
             for value in values.iter_mut() {
                 if let Some(expanded) = expand_home_prefix(value, home) {
@@ -191,6 +296,19 @@ mod tests {
         };
     }
 
+    #[test]
+    fn expands_path_fragment_filter() {
+        let query = expand("path:~/projects/src", "/Users/demo");
+        match query.expr {
+            Expr::Term(Term::Filter(filter)) => {
+                assert!(matches!(filter.kind, FilterKind::Path));
+                let argument = filter.argument.expect("argument");
+                assert_eq!(argument.raw, "/Users/demo/projects/src");
+            }
+            other => panic!("Unexpected expr: {other:?}"),
+        };
+    }
+
     #[test]
     fn ignores_non_path_filters() {
         let query = expand("ext:~", "/Users/demo");
@@ -278,8 +396,11 @@ mod tests {
                     start: Some("~".into()),
                     end: Some("~/scratch".into()),
                     separator: RangeSeparator::Dots,
+                    inclusive_start: true,
+                    inclusive_end: true,
                 }),
             }),
+            negated: false,
         };
         let filter = expand_filter_term(filter, "/Users/demo");
         let argument = filter.argument.expect("argument");
@@ -332,4 +453,24 @@ mod tests {
             other => panic!("Unexpected expr: {other:?}"),
         };
     }
+
+    #[test]
+    fn strips_toggles_that_cover_no_term() {
+        let strip = |input: &str| strip_inert_toggles(parse_query(input).unwrap()).expr;
+        let word = |text: &str| Expr::Term(Term::Word(text.to_string()));
+        assert_eq!(strip("report case:"), word("report"));
+        assert_eq!(strip("report !case:"), word("report"));
+        assert_eq!(strip("!case: report"), word("report"));
+        assert_eq!(strip("report | case:"), word("report"));
+        assert_eq!(strip("!case:"), Expr::Empty);
+        assert_eq!(strip("case:"), Expr::Empty);
+        match strip("case: report") {
+            Expr::And(parts) => {
+                assert_eq!(parts.len(), 2);
+                assert!(matches!(&parts[0], Expr::Term(Term::Filter(filter)) if is_toggle(filter)));
+                assert_eq!(parts[1], word("report"));
+            }
+            other => panic!("Unexpected expr: {other:?}"),
+        }
+    }
 }
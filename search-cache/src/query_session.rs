@@ -0,0 +1,292 @@
+use crate::{
+    ResultOrder, SearchCache, SearchOptions, SearchOutcome, SlabIndex, build_segment_matchers,
+    highlight::derive_highlight_terms, query_preprocessor::expand_query_home_dirs,
+};
+use anyhow::{Result, anyhow};
+use cardinal_syntax::{Expr, Term, optimize_query, parse_query};
+use query_segmentation::{Segment, query_segmentation};
+use search_cancel::{CANCEL_CHECK_INTERVAL, CancellationToken};
+
+/// Search-as-you-type helper remembering the previous query and its matches.
+///
+/// When the new query provably narrows the previous one (both are a single plain substring and
+/// the new one contains the old one, e.g. `rep` -> `repo`), only the previous matches are
+/// re-checked instead of scanning every name. Anything else, including any change to the cache
+/// in between, falls back to [`SearchCache::search_with_options`].
+#[derive(Debug, Default)]
+pub struct QuerySession {
+    previous: Option<PreviousQuery>,
+}
+
+#[derive(Debug)]
+struct PreviousQuery {
+    needle: String,
+    options: SearchOptions,
+    generation: u64,
+    nodes: Vec<SlabIndex>,
+}
+
+impl PreviousQuery {
+    fn is_narrowed_by(&self, needle: &str, options: &SearchOptions, generation: u64) -> bool {
+        if self.generation != generation || self.options != *options {
+            return false;
+        }
+        // Fuzzy matches and relevance scores depend on the whole needle, so dropping the
+        // matches that don't contain it isn't enough.
+        if options.fuzzy_distance.is_some() || matches!(options.order, ResultOrder::Ranked(_)) {
+            return false;
+        }
+        if !options.case_insensitive {
+            return needle.contains(&self.needle);
+        }
+        // Non-ASCII case folding can change lengths, so containment isn't provable there.
+        needle.is_ascii()
+            && self.needle.is_ascii()
+            && needle
+                .to_ascii_lowercase()
+                .contains(&self.needle.to_ascii_lowercase())
+    }
+}
+
+impl QuerySession {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets the previous query, the next search is a full one.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    /// Same results as [`SearchCache::search_with_options`].
+    pub fn search(
+        &mut self,
+        cache: &mut SearchCache,
+        line: &str,
+        options: &SearchOptions,
+        cancellation_token: CancellationToken,
+    ) -> Result<SearchOutcome> {
+        let Some(PlainQuery { needle, highlights }) = plain_substring(line) else {
+            self.previous = None;
+            return cache.search_with_options(line, options, cancellation_token);
+        };
+
+        let outcome = match self.previous.take() {
+            Some(previous) if previous.is_narrowed_by(&needle, options, cache.generation) => {
+                let nodes = cache.refine_by_substring(
+                    &previous.nodes,
+                    &needle,
+                    options,
+                    cancellation_token,
+                )?;
+                SearchOutcome { nodes, highlights }
+            }
+            _ => cache.search_with_options(line, options, cancellation_token)?,
+        };
+        if let Some(nodes) = &outcome.nodes {
+            self.previous = Some(PreviousQuery {
+                needle,
+                options: options.clone(),
+                generation: cache.generation,
+                nodes: nodes.clone(),
+            });
+        }
+        Ok(outcome)
+    }
+}
+
+impl SearchCache {
+    /// Keeps the nodes of `candidates` whose name contains `needle`, in their original order.
+    fn refine_by_substring(
+        &self,
+        candidates: &[SlabIndex],
+        needle: &str,
+        options: &SearchOptions,
+        token: CancellationToken,
+    ) -> Result<Option<Vec<SlabIndex>>> {
+        let matchers = build_segment_matchers(&[Segment::Substr(needle)], options)
+            .map_err(|err| anyhow!("Invalid regex pattern: {err}"))?;
+        let matcher = &matchers[0];
+        let mut nodes = Vec::with_capacity(candidates.len());
+        for (i, &index) in candidates.iter().enumerate() {
+            if i % CANCEL_CHECK_INTERVAL == 0 && token.is_cancelled() {
+                return Ok(None);
+            }
+            if matcher.matches(self.file_nodes[index].name_and_parent.as_str()) {
+                nodes.push(index);
+            }
+        }
+        Ok(Some(nodes))
+    }
+}
+
+struct PlainQuery {
+    needle: String,
+    highlights: Vec<String>,
+}
+
+/// Recognizes queries that are one substring match on names: a single word or phrase without
+/// path separators or wildcards.
+fn plain_substring(line: &str) -> Option<PlainQuery> {
+    let parsed = parse_query(line).ok()?;
+    let optimized = optimize_query(expand_query_home_dirs(parsed));
+    let (Expr::Term(Term::Word(text)) | Expr::Term(Term::Phrase(text))) = &optimized.expr else {
+        return None;
+    };
+    match query_segmentation(text).as_slice() {
+        [Segment::Substr(needle)] if !needle.contains(['*', '?']) => Some(PlainQuery {
+            needle: needle.to_string(),
+            highlights: derive_highlight_terms(&optimized.expr),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    fn full_search(cache: &mut SearchCache, line: &str, options: &SearchOptions) -> Vec<SlabIndex> {
+        cache
+            .search_with_options(line, options, CancellationToken::noop())
+            .unwrap()
+            .nodes
+            .unwrap()
+    }
+
+    fn corpus() -> (TempDir, SearchCache) {
+        let tmp = TempDir::new("query_session").unwrap();
+        fs::create_dir(tmp.path().join("reports")).unwrap();
+        for name in [
+            "report.txt",
+            "Report Q1.md",
+            "reply.eml",
+            "repo.toml",
+            "reports/final_report.pdf",
+            "reports/notes.txt",
+            "misc.rs",
+        ] {
+            fs::write(tmp.path().join(name), b"x").unwrap();
+        }
+        let cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+        (tmp, cache)
+    }
+
+    #[test]
+    fn narrowing_sequence_matches_full_searches() {
+        let (_tmp, mut cache) = corpus();
+        for options in [
+            SearchOptions::default(),
+            SearchOptions {
+                case_insensitive: true,
+                ..Default::default()
+            },
+        ] {
+            let mut session = QuerySession::new();
+            for line in ["r", "re", "rep", "repo", "repor", "report", "report."] {
+                let outcome = session
+                    .search(&mut cache, line, &options, CancellationToken::noop())
+                    .unwrap();
+                assert_eq!(
+                    outcome.nodes.unwrap(),
+                    full_search(&mut cache, line, &options),
+                    "{line} {options:?}"
+                );
+                assert_eq!(outcome.highlights, [line.to_string()]);
+            }
+        }
+    }
+
+    #[test]
+    fn narrowing_needs_containment_same_options_and_generation() {
+        let (_tmp, mut cache) = corpus();
+        let mut session = QuerySession::new();
+        session
+            .search(
+                &mut cache,
+                "rep",
+                &SearchOptions::default(),
+                CancellationToken::noop(),
+            )
+            .unwrap();
+        let previous = session.previous.as_ref().unwrap();
+        assert!(previous.is_narrowed_by("report", &SearchOptions::default(), cache.generation));
+        assert!(previous.is_narrowed_by("xrepx", &SearchOptions::default(), cache.generation));
+        assert!(!previous.is_narrowed_by("re", &SearchOptions::default(), cache.generation));
+        assert!(!previous.is_narrowed_by(
+            "report",
+            &SearchOptions {
+                case_insensitive: true,
+                ..Default::default()
+            },
+            cache.generation
+        ));
+        assert!(!previous.is_narrowed_by(
+            "report",
+            &SearchOptions::default(),
+            cache.generation + 1
+        ));
+
+        let fuzzy = SearchOptions {
+            fuzzy_distance: Some(1),
+            ..Default::default()
+        };
+        session
+            .search(&mut cache, "rep", &fuzzy, CancellationToken::noop())
+            .unwrap();
+        let previous = session.previous.as_ref().unwrap();
+        assert!(!previous.is_narrowed_by("report", &fuzzy, cache.generation));
+    }
+
+    #[test]
+    fn widening_and_structured_queries_fall_back_to_full_search() {
+        let (_tmp, mut cache) = corpus();
+        let mut session = QuerySession::new();
+        let options = SearchOptions::default();
+        for line in [
+            "report",
+            "rep",
+            "reports/",
+            "report ext:txt",
+            "report",
+            "rep*",
+            "repo",
+        ] {
+            let outcome = session
+                .search(&mut cache, line, &options, CancellationToken::noop())
+                .unwrap();
+            assert_eq!(
+                outcome.nodes.unwrap(),
+                full_search(&mut cache, line, &options),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn cache_changes_invalidate_the_session() {
+        let (tmp, mut cache) = corpus();
+        let mut session = QuerySession::new();
+        let options = SearchOptions::default();
+        session
+            .search(&mut cache, "rep", &options, CancellationToken::noop())
+            .unwrap();
+
+        fs::write(tmp.path().join("report_v2.txt"), b"x").unwrap();
+        cache.rescan();
+
+        let outcome = session
+            .search(&mut cache, "report", &options, CancellationToken::noop())
+            .unwrap();
+        let nodes = outcome.nodes.unwrap();
+        assert_eq!(nodes, full_search(&mut cache, "report", &options));
+        assert!(
+            nodes
+                .iter()
+                .filter_map(|&index| cache.node_path(index))
+                .any(|path| path.ends_with("report_v2.txt"))
+        );
+    }
+}
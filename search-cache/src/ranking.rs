@@ -0,0 +1,101 @@
+use crate::SearchResultNode;
+use cardinal_syntax::{Expr, Term};
+use query_segmentation::{Segment, query_segmentation};
+
+const SECONDS_PER_DAY: f32 = 24.0 * 60.0 * 60.0;
+
+/// Weights used by [`crate::ResultOrder::Ranked`] to score results. Every weight
+/// can be set to zero to ignore that signal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankingWeights {
+    /// Added when the file name, with or without its extension, equals a query term.
+    pub exact_name: f32,
+    /// Added when the file name starts with a query term (and isn't an exact match).
+    pub prefix: f32,
+    /// Subtracted for every component of the path.
+    pub depth_penalty: f32,
+    /// Added for a file modified right now, halved every `recency_half_life_days`.
+    pub recency: f32,
+    pub recency_half_life_days: f32,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            exact_name: 10.0,
+            prefix: 5.0,
+            depth_penalty: 0.1,
+            recency: 2.0,
+            recency_half_life_days: 30.0,
+        }
+    }
+}
+
+impl RankingWeights {
+    /// Relevance of `node` for the lowercased name `terms`, `now` being a Unix timestamp.
+    pub(crate) fn score(&self, node: &SearchResultNode, terms: &[String], now: i64) -> f32 {
+        let mut score = 0.0;
+        if let Some(name) = node.path.file_name() {
+            let name = name.to_string_lossy().to_lowercase();
+            let stem = name
+                .rsplit_once('.')
+                .map_or(name.as_str(), |(stem, _)| stem);
+            score += terms
+                .iter()
+                .map(|term| {
+                    if name == *term || stem == term {
+                        self.exact_name
+                    } else if name.starts_with(term.as_str()) {
+                        self.prefix
+                    } else {
+                        0.0
+                    }
+                })
+                .fold(0.0, f32::max);
+        }
+        score -= self.depth_penalty * node.path.components().count() as f32;
+        if self.recency != 0.0 {
+            let mtime = node.metadata.as_ref().and_then(|metadata| metadata.mtime());
+            if let Some(mtime) = mtime {
+                let age_days = (now - i64::from(mtime.get())).max(0) as f32 / SECONDS_PER_DAY;
+                let half_lives = age_days / self.recency_half_life_days.max(f32::EPSILON);
+                score += self.recency * 0.5f32.powf(half_lives);
+            }
+        }
+        score
+    }
+}
+
+/// Lowercased name terms of `expr`: the last path segment of words and phrases, negated ones
+/// excluded.
+pub(crate) fn ranking_terms(expr: &Expr) -> Vec<String> {
+    let mut terms = Vec::new();
+    collect_ranking_terms(expr, &mut terms);
+    terms
+}
+
+fn collect_ranking_terms(expr: &Expr, terms: &mut Vec<String>) {
+    match expr {
+        Expr::Term(Term::Word(text) | Term::Phrase(text)) => {
+            if let Some(segment) = query_segmentation(text).last() {
+                let (Segment::Substr(value)
+                | Segment::Prefix(value)
+                | Segment::Suffix(value)
+                | Segment::Exact(value)) = segment;
+                if !value.is_empty() {
+                    terms.push(value.to_lowercase());
+                }
+            }
+        }
+        Expr::And(parts) | Expr::Or(parts) => {
+            for part in parts {
+                collect_ranking_terms(part, terms);
+            }
+        }
+        Expr::Near { left, right, .. } => {
+            collect_ranking_terms(left, terms);
+            collect_ranking_terms(right, terms);
+        }
+        Expr::Not(_) | Expr::Term(_) | Expr::Empty => {}
+    }
+}
@@ -1,9 +1,39 @@
+use crate::{ResultOrder, SlabIndex};
 use query_segmentation::Segment;
 use regex::{Regex, RegexBuilder};
+use std::path::PathBuf;
 
-#[derive(Debug, Clone, Copy, Default)]
+/// How [`crate::SearchCache::search_with_options`] and
+/// [`crate::SearchCache::query_files_with_options`] run a query. Every field combines with the
+/// others.
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct SearchOptions {
     pub case_insensitive: bool,
+    /// Results at or below any of these folders are left out, so e.g. `/System` stays out of
+    /// everyday searches without typing `!infolder:` each time. A `parent:`, `infolder:` or
+    /// `nosubfolders:` filter pointing at or into an excluded folder lifts that exclusion for
+    /// the query: an explicit scope wins.
+    pub excluded_prefixes: Vec<PathBuf>,
+    /// Plain word terms also match names containing them with at most this many typos
+    /// (inserted, removed or replaced characters), closer matches first. Filters, phrases and
+    /// regexes stay exact.
+    pub fuzzy_distance: Option<u8>,
+    /// Only these nodes can match, typically the results of an earlier search, and they keep
+    /// their order. Refining `a`'s results with `b` finds what `a b` finds.
+    pub within: Option<Vec<SlabIndex>>,
+    pub order: ResultOrder,
+}
+
+/// How much of a name a word or phrase has to cover, see `wholeword:` and `wholefilename:`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum NameMatch {
+    /// Anywhere in the name, the default.
+    #[default]
+    Substring,
+    /// Only at word boundaries: `report` matches `report.txt` but not `reports`.
+    WholeWord,
+    /// The entire name: `report` matches `report` but not `report.txt`.
+    WholeFilename,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -16,8 +46,20 @@ pub(crate) enum SegmentKind {
 
 #[derive(Clone, Debug)]
 pub(crate) enum SegmentMatcher {
-    Plain { kind: SegmentKind, needle: String },
-    Regex { regex: Regex },
+    Plain {
+        kind: SegmentKind,
+        needle: String,
+    },
+    Regex {
+        regex: Regex,
+    },
+    /// Case-sensitive wildcard. `literal` is its longest run without `*`/`?`,
+    /// which every match contains, so a substring search can narrow the
+    /// candidates before `regex` runs.
+    Glob {
+        regex: Regex,
+        literal: String,
+    },
 }
 
 impl SegmentMatcher {
@@ -29,7 +71,9 @@ impl SegmentMatcher {
                 SegmentKind::Suffix => candidate.ends_with(needle),
                 SegmentKind::Exact => candidate == needle,
             },
-            SegmentMatcher::Regex { regex } => regex.is_match(candidate),
+            SegmentMatcher::Regex { regex } | SegmentMatcher::Glob { regex, .. } => {
+                regex.is_match(candidate)
+            }
         }
     }
 }
@@ -52,16 +96,69 @@ fn wildcard_to_regex(pattern: &str) -> String {
     regex
 }
 
+fn longest_literal(pattern: &str) -> &str {
+    pattern
+        .split(['*', '?'])
+        .rev()
+        .max_by_key(|chunk| chunk.len())
+        .unwrap_or_default()
+}
+
+/// [`build_segment_matchers`] with the segments widened to cover `name_match`. Path
+/// anchoring (a leading or trailing `/`) already pins a side of the name, so only the open
+/// sides of each segment are affected. Wildcards always match the whole name.
+pub(crate) fn build_name_matchers(
+    segments: &[Segment<'_>],
+    name_match: NameMatch,
+    options: &SearchOptions,
+) -> Result<Vec<SegmentMatcher>, regex::Error> {
+    match name_match {
+        NameMatch::Substring => build_segment_matchers(segments, options),
+        NameMatch::WholeFilename => {
+            let exact: Vec<Segment<'_>> = segments
+                .iter()
+                .map(|segment| Segment::Exact(segment.value()))
+                .collect();
+            build_segment_matchers(&exact, options)
+        }
+        NameMatch::WholeWord => segments
+            .iter()
+            .map(|segment| {
+                const START: &str = r"(?:^|\W)";
+                const END: &str = r"(?:\W|$)";
+                let value = segment.value();
+                let base = regex::escape(value);
+                let pattern = match segment_kind(segment) {
+                    _ if segment.is_wildcard() => None,
+                    SegmentKind::Substr => Some(format!("{START}(?:{base}){END}")),
+                    SegmentKind::Prefix => Some(format!("^(?:{base}){END}")),
+                    SegmentKind::Suffix => Some(format!("{START}(?:{base})$")),
+                    SegmentKind::Exact => None,
+                };
+                let Some(pattern) = pattern else {
+                    let mut matchers =
+                        build_segment_matchers(std::slice::from_ref(segment), options)?;
+                    return Ok(matchers.remove(0));
+                };
+                let regex = RegexBuilder::new(&pattern)
+                    .case_insensitive(options.case_insensitive)
+                    .build()?;
+                Ok(SegmentMatcher::Regex { regex })
+            })
+            .collect(),
+    }
+}
+
 pub(crate) fn build_segment_matchers(
     segments: &[Segment<'_>],
-    options: SearchOptions,
+    options: &SearchOptions,
 ) -> Result<Vec<SegmentMatcher>, regex::Error> {
     segments
         .iter()
         .map(|segment| {
             let kind = segment_kind(segment);
-            let value = segment_value(segment);
-            let is_wildcard = value.contains("*") || value.contains('?');
+            let value = segment.value();
+            let is_wildcard = segment.is_wildcard();
             if options.case_insensitive || is_wildcard {
                 let pattern = if is_wildcard {
                     // Wildcard pattern is /exact/ by default, so we don't need to
@@ -78,7 +175,15 @@ pub(crate) fn build_segment_matchers(
                 };
                 let mut builder = RegexBuilder::new(&pattern);
                 builder.case_insensitive(options.case_insensitive);
-                builder.build().map(|regex| SegmentMatcher::Regex { regex })
+                let regex = builder.build()?;
+                if is_wildcard && !options.case_insensitive {
+                    Ok(SegmentMatcher::Glob {
+                        regex,
+                        literal: longest_literal(value).to_string(),
+                    })
+                } else {
+                    Ok(SegmentMatcher::Regex { regex })
+                }
             } else {
                 Ok(SegmentMatcher::Plain {
                     kind,
@@ -98,20 +203,11 @@ fn segment_kind(segment: &Segment<'_>) -> SegmentKind {
     }
 }
 
-fn segment_value<'s>(segment: &Segment<'s>) -> &'s str {
-    match segment {
-        Segment::Substr(value)
-        | Segment::Prefix(value)
-        | Segment::Suffix(value)
-        | Segment::Exact(value) => value,
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::{
-        SearchOptions, SegmentKind, SegmentMatcher, build_segment_matchers, segment_kind,
-        segment_value, wildcard_to_regex,
+        NameMatch, SearchOptions, SegmentKind, SegmentMatcher, build_name_matchers,
+        build_segment_matchers, longest_literal, segment_kind, wildcard_to_regex,
     };
     use query_segmentation::Segment;
 
@@ -154,6 +250,13 @@ mod tests {
         assert_eq!(wildcard_to_regex(""), "^$");
     }
 
+    #[test]
+    fn longest_literal_picks_widest_chunk() {
+        assert_eq!(longest_literal("foo*barbaz?x"), "barbaz");
+        assert_eq!(longest_literal("a*b"), "a");
+        assert_eq!(longest_literal("*?*"), "");
+    }
+
     // --- segment_kind mapping ---
 
     #[test]
@@ -176,14 +279,6 @@ mod tests {
         ));
     }
 
-    #[test]
-    fn segment_value_extraction() {
-        assert_eq!(segment_value(&Segment::Substr("abc")), "abc");
-        assert_eq!(segment_value(&Segment::Prefix("def")), "def");
-        assert_eq!(segment_value(&Segment::Suffix("ghi")), "ghi");
-        assert_eq!(segment_value(&Segment::Exact("jkl")), "jkl");
-    }
-
     // --- build_segment_matchers (plain, no wildcard, case-sensitive) ---
 
     #[test]
@@ -196,14 +291,15 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).expect("ok");
+        let matchers = build_segment_matchers(&segments, &opts).expect("ok");
         assert_eq!(matchers.len(), 4);
         // All should be Plain
         for (m, s) in matchers.iter().zip(segments.iter()) {
             match m {
                 SegmentMatcher::Plain { kind, needle } => {
-                    assert_eq!(needle, segment_value(s));
+                    assert_eq!(needle, s.value());
                     assert_eq!(*kind as u8, segment_kind(s) as u8);
                 }
                 _ => panic!("Expected Plain matcher"),
@@ -223,8 +319,9 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).expect("ok");
+        let matchers = build_segment_matchers(&segments, &opts).expect("ok");
         assert_eq!(matchers.len(), 4);
         let patterns: Vec<_> = matchers
             .iter()
@@ -246,14 +343,16 @@ mod tests {
         let segments = [Segment::Exact("foo*bar?baz")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).expect("ok");
+        let matchers = build_segment_matchers(&segments, &opts).expect("ok");
         assert_eq!(matchers.len(), 1);
         match &matchers[0] {
-            SegmentMatcher::Regex { regex } => {
+            SegmentMatcher::Glob { regex, literal } => {
                 assert_eq!(regex.as_str(), "^foo.*bar.baz$");
+                assert_eq!(literal, "foo");
             }
-            _ => panic!("Expected regex for wildcard segment"),
+            _ => panic!("Expected glob for wildcard segment"),
         }
     }
 
@@ -262,14 +361,15 @@ mod tests {
         let segments = [Segment::Substr("A*B")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).expect("ok");
+        let matchers = build_segment_matchers(&segments, &opts).expect("ok");
         match &matchers[0] {
-            SegmentMatcher::Regex { regex } => {
+            SegmentMatcher::Glob { regex, .. } => {
                 assert!(!regex.is_match("aXXb"));
                 assert!(regex.is_match("AXXB"));
             }
-            _ => panic!("Expected regex matcher"),
+            _ => panic!("Expected glob matcher"),
         }
     }
 
@@ -278,8 +378,9 @@ mod tests {
         let segments = [Segment::Substr("A*B")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).expect("ok");
+        let matchers = build_segment_matchers(&segments, &opts).expect("ok");
         match &matchers[0] {
             SegmentMatcher::Regex { regex } => {
                 assert!(regex.is_match("aXXb"));
@@ -338,8 +439,9 @@ mod tests {
         let segments = [Segment::Substr("abc")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
-        let m = build_segment_matchers(&segments, opts).unwrap().remove(0);
+        let m = build_segment_matchers(&segments, &opts).unwrap().remove(0);
         match m {
             SegmentMatcher::Regex { regex } => {
                 assert!(regex.is_match("zzzAbCzzz"));
@@ -353,8 +455,9 @@ mod tests {
         let segments = [Segment::Prefix("abc")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
-        let m = build_segment_matchers(&segments, opts).unwrap().remove(0);
+        let m = build_segment_matchers(&segments, &opts).unwrap().remove(0);
         match m {
             SegmentMatcher::Regex { regex } => {
                 assert!(regex.is_match("AbCzzz"));
@@ -369,8 +472,9 @@ mod tests {
         let segments = [Segment::Suffix("abc")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
-        let m = build_segment_matchers(&segments, opts).unwrap().remove(0);
+        let m = build_segment_matchers(&segments, &opts).unwrap().remove(0);
         match m {
             SegmentMatcher::Regex { regex } => {
                 assert!(regex.is_match("zzzAbC"));
@@ -385,8 +489,9 @@ mod tests {
         let segments = [Segment::Exact("abc")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
-        let m = build_segment_matchers(&segments, opts).unwrap().remove(0);
+        let m = build_segment_matchers(&segments, &opts).unwrap().remove(0);
         match m {
             SegmentMatcher::Regex { regex } => {
                 assert!(regex.is_match("AbC"));
@@ -403,18 +508,19 @@ mod tests {
         let segments = [
             Segment::Substr("abc"),   // plain
             Segment::Prefix("pre"),   // plain
-            Segment::Suffix("*wild"), // wildcard => regex
-            Segment::Exact("ex?act"), // wildcard => regex
+            Segment::Suffix("*wild"), // wildcard => glob
+            Segment::Exact("ex?act"), // wildcard => glob
         ];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).unwrap();
+        let matchers = build_segment_matchers(&segments, &opts).unwrap();
         assert_eq!(matchers.len(), 4);
         assert!(matches!(matchers[0], SegmentMatcher::Plain { .. }));
         assert!(matches!(matchers[1], SegmentMatcher::Plain { .. }));
-        assert!(matches!(matchers[2], SegmentMatcher::Regex { .. }));
-        assert!(matches!(matchers[3], SegmentMatcher::Regex { .. }));
+        assert!(matches!(matchers[2], SegmentMatcher::Glob { .. }));
+        assert!(matches!(matchers[3], SegmentMatcher::Glob { .. }));
     }
 
     #[test]
@@ -427,8 +533,9 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).unwrap();
+        let matchers = build_segment_matchers(&segments, &opts).unwrap();
         for m in matchers {
             assert!(matches!(m, SegmentMatcher::Regex { .. }));
         }
@@ -441,16 +548,17 @@ mod tests {
         let segments = [Segment::Exact("a+b*(c?)")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).unwrap();
+        let matchers = build_segment_matchers(&segments, &opts).unwrap();
         match &matchers[0] {
-            SegmentMatcher::Regex { regex } => {
+            SegmentMatcher::Glob { regex, .. } => {
                 // '?' is treated as wildcard -> '.'
                 assert_eq!(regex.as_str(), "^a\\+b.*\\(c.\\)$");
                 assert!(regex.is_match("a+bZZZ(c?)"));
                 assert!(!regex.is_match("abZZZ(c?)"));
             }
-            _ => panic!("Expected regex"),
+            _ => panic!("Expected glob"),
         }
     }
 
@@ -461,8 +569,9 @@ mod tests {
         let segments = [Segment::Substr("Café")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).unwrap();
+        let matchers = build_segment_matchers(&segments, &opts).unwrap();
         match &matchers[0] {
             SegmentMatcher::Plain { needle, .. } => {
                 assert_eq!(needle, "Café");
@@ -476,8 +585,9 @@ mod tests {
         let segments = [Segment::Exact("Café")];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).unwrap();
+        let matchers = build_segment_matchers(&segments, &opts).unwrap();
         match &matchers[0] {
             SegmentMatcher::Regex { regex } => {
                 assert!(regex.is_match("café"));
@@ -498,8 +608,9 @@ mod tests {
         ];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).unwrap();
+        let matchers = build_segment_matchers(&segments, &opts).unwrap();
         assert_eq!(matchers.len(), 3);
         match &matchers[0] {
             SegmentMatcher::Regex { regex } => assert!(regex.as_str().starts_with("^(?:")),
@@ -526,8 +637,9 @@ mod tests {
         let segments = [Segment::Exact(&long)];
         let opts = SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).unwrap();
+        let matchers = build_segment_matchers(&segments, &opts).unwrap();
         assert_eq!(matchers.len(), 1);
         match &matchers[0] {
             SegmentMatcher::Regex { regex } => {
@@ -544,14 +656,15 @@ mod tests {
         let segments = [Segment::Exact("a*b*c?d")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).unwrap();
+        let matchers = build_segment_matchers(&segments, &opts).unwrap();
         match &matchers[0] {
-            SegmentMatcher::Regex { regex } => {
+            SegmentMatcher::Glob { regex, .. } => {
                 assert!(regex.is_match("aZZbYYcXd"));
                 assert!(!regex.is_match("abYcXdX"));
             }
-            _ => panic!("Expected regex"),
+            _ => panic!("Expected glob"),
         }
     }
 
@@ -562,8 +675,9 @@ mod tests {
         let segments = [Segment::Substr("mid")];
         let opts = SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         };
-        let matchers = build_segment_matchers(&segments, opts).unwrap();
+        let matchers = build_segment_matchers(&segments, &opts).unwrap();
         match &matchers[0] {
             SegmentMatcher::Plain { needle, .. } => {
                 assert_eq!(needle, "mid");
@@ -578,4 +692,67 @@ mod tests {
             _ => panic!("Expected plain matcher"),
         }
     }
+
+    // --- build_name_matchers ---
+
+    fn name_matches(segment: Segment<'_>, name_match: NameMatch, candidate: &str) -> bool {
+        let opts = SearchOptions {
+            case_insensitive: false,
+            ..Default::default()
+        };
+        build_name_matchers(&[segment], name_match, &opts).unwrap()[0].matches(candidate)
+    }
+
+    #[test]
+    fn whole_filename_covers_every_segment_kind() {
+        for segment in [
+            Segment::Substr("report"),
+            Segment::Prefix("report"),
+            Segment::Suffix("report"),
+        ] {
+            assert!(name_matches(segment, NameMatch::WholeFilename, "report"));
+            assert!(!name_matches(
+                segment,
+                NameMatch::WholeFilename,
+                "report.txt"
+            ));
+            assert!(!name_matches(
+                segment,
+                NameMatch::WholeFilename,
+                "my report"
+            ));
+        }
+        assert!(name_matches(
+            Segment::Substr("rep*"),
+            NameMatch::WholeFilename,
+            "report.txt"
+        ));
+    }
+
+    #[test]
+    fn whole_word_needs_boundaries_on_open_sides() {
+        let word = Segment::Substr("report");
+        for name in ["report", "report.txt", "old report.md", "q1-report"] {
+            assert!(name_matches(word, NameMatch::WholeWord, name), "{name}");
+        }
+        for name in ["reports.txt", "unreported", "report_final"] {
+            assert!(!name_matches(word, NameMatch::WholeWord, name), "{name}");
+        }
+        // A leading `/` already anchors the start.
+        let prefix = Segment::Prefix("report");
+        assert!(name_matches(prefix, NameMatch::WholeWord, "report.txt"));
+        assert!(!name_matches(prefix, NameMatch::WholeWord, "reports"));
+        assert!(!name_matches(prefix, NameMatch::WholeWord, "old report"));
+        // Metacharacters in the needle stay literal.
+        assert!(name_matches(
+            Segment::Substr("a.b"),
+            NameMatch::WholeWord,
+            "x a.b y"
+        ));
+        assert!(!name_matches(
+            Segment::Substr("a.b"),
+            NameMatch::WholeWord,
+            "x acb y"
+        ));
+    }
 }
@@ -114,6 +114,18 @@ impl<T> ThinSlab<T> {
     pub fn iter(&self) -> ThinSlabIter<'_, T> {
         ThinSlabIter(self.0.iter())
     }
+
+    /// Overwrites (`Some`) or vacates (`None`) the given slots, see [`Slab::patch`].
+    pub fn patch(
+        &mut self,
+        changes: impl IntoIterator<Item = (SlabIndex, Option<T>)>,
+    ) -> io::Result<()> {
+        self.0.patch(
+            changes
+                .into_iter()
+                .map(|(index, value)| (index.get(), value)),
+        )
+    }
 }
 
 impl<T> std::ops::Index<SlabIndex> for ThinSlab<T> {
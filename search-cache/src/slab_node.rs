@@ -96,7 +96,7 @@ impl NameAndParent {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlabNode {
     pub name_and_parent: NameAndParent,
     pub children: ThinVec<SlabIndex>,
@@ -142,6 +142,20 @@ impl<'a> SlabNodeMetadata<'a> {
     pub fn mtime(&self) -> Option<NonZeroU32> {
         NonZeroU32::new(self.0.mtime)
     }
+
+    pub fn uid(&self) -> u32 {
+        self.0.uid
+    }
+
+    pub fn gid(&self) -> u32 {
+        self.0.gid
+    }
+
+    /// Permission bits as in `st_mode`, 0 for nodes loaded from a cache written before they
+    /// were recorded.
+    pub fn mode(&self) -> u32 {
+        self.0.mode
+    }
 }
 
 /// Use a compact form so that
@@ -151,6 +165,9 @@ pub struct SlabNodeMetadataCompact {
     // Actually a Option<NonZeroU32>, but using u32 here due to https://github.com/serde-rs/serde/issues/1834
     ctime: u32,
     mtime: u32,
+    uid: u32,
+    gid: u32,
+    mode: u32,
 }
 
 impl SlabNodeMetadataCompact {
@@ -159,6 +176,9 @@ impl SlabNodeMetadataCompact {
             state_type_and_size: StateTypeSize::unaccessible(),
             ctime: 0,
             mtime: 0,
+            uid: 0,
+            gid: 0,
+            mode: 0,
         }
     }
 
@@ -168,6 +188,9 @@ impl SlabNodeMetadataCompact {
             size,
             ctime,
             mtime,
+            uid,
+            gid,
+            mode,
         }: fswalk::NodeMetadata,
     ) -> Self {
         Self {
@@ -180,6 +203,9 @@ impl SlabNodeMetadataCompact {
                 .and_then(|x| std::num::NonZeroU32::try_from(x).ok())
                 .map(|x| x.get())
                 .unwrap_or_default(),
+            uid,
+            gid,
+            mode,
         }
     }
 
@@ -188,6 +214,9 @@ impl SlabNodeMetadataCompact {
             state_type_and_size: StateTypeSize::none(),
             ctime: 0,
             mtime: 0,
+            uid: 0,
+            gid: 0,
+            mode: 0,
         }
     }
 
@@ -217,10 +246,79 @@ impl SlabNodeMetadataCompact {
     pub fn file_type_hint(&self) -> NodeFileType {
         self.state_type_and_size.r#type()
     }
+
+    /// Records the type known from elsewhere (the walk, a node's position) when
+    /// there is no metadata to take it from.
+    pub fn with_file_type_hint(self, r#type: NodeFileType) -> Self {
+        Self {
+            state_type_and_size: self.state_type_and_size.with_type(r#type),
+            ..self
+        }
+    }
+}
+
+/// [`SlabNode`] as cache schema version 6 stored it, before metadata kept owners and
+/// permission bits.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SlabNodeV6 {
+    pub(crate) name_and_parent: NameAndParent,
+    pub(crate) children: ThinVec<SlabIndex>,
+    pub(crate) metadata: SlabNodeMetadataCompactV6,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SlabNodeMetadataCompactV6 {
+    state_type_and_size: StateTypeSize,
+    ctime: u32,
+    mtime: u32,
+}
+
+/// Encodes a node the way version 6 did, for testing the migration.
+#[cfg(test)]
+impl From<&SlabNode> for SlabNodeV6 {
+    fn from(node: &SlabNode) -> Self {
+        Self {
+            name_and_parent: node.name_and_parent,
+            children: node.children.clone(),
+            metadata: SlabNodeMetadataCompactV6 {
+                state_type_and_size: node.metadata.state_type_and_size,
+                ctime: node.metadata.ctime,
+                mtime: node.metadata.mtime,
+            },
+        }
+    }
+}
+
+impl From<&SlabNodeV6> for SlabNode {
+    fn from(
+        SlabNodeV6 {
+            name_and_parent,
+            children,
+            metadata,
+        }: &SlabNodeV6,
+    ) -> Self {
+        Self {
+            name_and_parent: *name_and_parent,
+            children: children.clone(),
+            // Owners and permission bits weren't recorded, they stay unknown until the node
+            // is refreshed.
+            metadata: SlabNodeMetadataCompact {
+                state_type_and_size: metadata.state_type_and_size,
+                ctime: metadata.ctime,
+                mtime: metadata.mtime,
+                uid: 0,
+                gid: 0,
+                mode: 0,
+            },
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SearchResultNode {
     pub path: std::path::PathBuf,
     pub metadata: SlabNodeMetadataCompact,
+    /// Known even when `metadata` is missing or unaccessible, e.g. after a
+    /// permission denied error.
+    pub file_type: NodeFileType,
 }
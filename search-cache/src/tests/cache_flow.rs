@@ -1,5 +1,12 @@
 use super::prelude::*;
+use crate::{
+    CacheCompression, CacheVersionMismatch, NodeChanges, SlabNodeMetadataCompact, ThinSlab,
+    persistent::{CACHE_SCHEMA_VERSION, LEGACY_SCHEMA_VERSION, PersistentStorageV6},
+    read_cache_from_file, read_cache_node_count,
+    slab_node::SlabNodeV6,
+};
 use cardinal_sdk::{EventFlag, FsEvent};
+use std::path::Path;
 
 #[test]
 fn test_search_empty_returns_all_nodes() {
@@ -25,6 +32,231 @@ fn test_node_path_root_and_child() {
     assert!(full.ends_with(PathBuf::from("dir1/file_x")));
 }
 
+#[test]
+fn test_path_of_reconstructs_nested_path() {
+    let tmp = TempDir::new("path_of").unwrap();
+    fs::create_dir_all(tmp.path().join("a/b/c")).unwrap();
+    fs::write(tmp.path().join("a/b/c/deep.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let idxs = cache.search("deep.txt").unwrap();
+    assert_eq!(idxs.len(), 1);
+    assert_eq!(
+        cache.path_of(idxs[0]),
+        Some(tmp.path().join("a/b/c/deep.txt"))
+    );
+    let dir = cache
+        .node_index_for_raw_path(&tmp.path().join("a/b"))
+        .unwrap();
+    assert_eq!(cache.path_of(dir), Some(tmp.path().join("a/b")));
+}
+
+#[test]
+fn test_path_of_root_is_watch_root() {
+    let tmp = TempDir::new("path_of_root").unwrap();
+    let cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let root = cache.node_index_for_raw_path(tmp.path()).unwrap();
+    assert_eq!(cache.path_of(root), Some(tmp.path().to_path_buf()));
+}
+
+#[test]
+fn test_path_of_stale_index_is_none() {
+    let tmp = TempDir::new("path_of_stale").unwrap();
+    fs::create_dir(tmp.path().join("gone")).unwrap();
+    fs::write(tmp.path().join("gone/child.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let dir = cache
+        .node_index_for_raw_path(&tmp.path().join("gone"))
+        .unwrap();
+    let child = cache
+        .node_index_for_raw_path(&tmp.path().join("gone/child.txt"))
+        .unwrap();
+
+    fs::remove_dir_all(tmp.path().join("gone")).unwrap();
+    let id = cache.last_event_id() + 1;
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: tmp.path().join("gone"),
+            id,
+            flag: EventFlag::ItemRemoved,
+        }])
+        .unwrap();
+    assert_eq!(cache.path_of(dir), None);
+    assert_eq!(cache.path_of(child), None);
+}
+
+#[test]
+fn test_handle_fs_events_reports_node_changes() {
+    let tmp = TempDir::new("node_changes").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let mut id = cache.last_event_id();
+    let mut apply = |cache: &mut SearchCache, path: PathBuf, flag: EventFlag| {
+        id += 1;
+        cache
+            .handle_fs_events(vec![FsEvent { path, id, flag }])
+            .unwrap()
+    };
+
+    // Creating a folder with a file reports both new nodes.
+    fs::create_dir(tmp.path().join("docs")).unwrap();
+    fs::write(tmp.path().join("docs/report.txt"), b"v1").unwrap();
+    let changes = apply(
+        &mut cache,
+        tmp.path().join("docs"),
+        EventFlag::ItemCreated | EventFlag::ItemIsDir,
+    );
+    let docs = cache
+        .node_index_for_raw_path(&tmp.path().join("docs"))
+        .unwrap();
+    let report = cache
+        .node_index_for_raw_path(&tmp.path().join("docs/report.txt"))
+        .unwrap();
+    let mut added = vec![docs, report];
+    added.sort();
+    assert_eq!(
+        changes,
+        NodeChanges {
+            added,
+            ..Default::default()
+        }
+    );
+
+    // Rewriting the file rescans it in place.
+    fs::write(tmp.path().join("docs/report.txt"), b"version 2").unwrap();
+    let changes = apply(
+        &mut cache,
+        tmp.path().join("docs/report.txt"),
+        EventFlag::ItemModified | EventFlag::ItemIsFile,
+    );
+    assert_eq!(
+        changes,
+        NodeChanges {
+            modified: vec![report],
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        cache.file_nodes[report].metadata.as_ref().unwrap().size(),
+        9
+    );
+
+    // Deleting it reports the index it had.
+    fs::remove_file(tmp.path().join("docs/report.txt")).unwrap();
+    let changes = apply(
+        &mut cache,
+        tmp.path().join("docs/report.txt"),
+        EventFlag::ItemRemoved | EventFlag::ItemIsFile,
+    );
+    assert_eq!(
+        changes,
+        NodeChanges {
+            removed: vec![report],
+            ..Default::default()
+        }
+    );
+    assert_eq!(cache.path_of(report), None);
+    assert_eq!(cache.path_of(docs), Some(tmp.path().join("docs")));
+}
+
+#[test]
+fn test_must_scan_subdirs_rescans_only_that_folder() {
+    let tmp = TempDir::new("must_scan_subdirs").unwrap();
+    fs::create_dir_all(tmp.path().join("project/src")).unwrap();
+    fs::create_dir(tmp.path().join("other")).unwrap();
+    fs::write(tmp.path().join("project/src/old.rs"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let old = cache.search("old.rs").unwrap()[0];
+
+    // Changes FSEvents only reports as "something changed under project".
+    fs::remove_file(tmp.path().join("project/src/old.rs")).unwrap();
+    fs::create_dir(tmp.path().join("project/src/nested")).unwrap();
+    fs::write(tmp.path().join("project/src/nested/new.rs"), b"x").unwrap();
+    fs::write(tmp.path().join("other/untouched.rs"), b"x").unwrap();
+    let id = cache.last_event_id() + 1;
+    let changes = cache
+        .handle_fs_events(vec![FsEvent {
+            path: tmp.path().join("project"),
+            id,
+            flag: EventFlag::MustScanSubDirs,
+        }])
+        .expect("a subfolder event shouldn't rebuild the whole cache");
+
+    assert!(changes.removed.contains(&old));
+    assert!(cache.search("old.rs").unwrap().is_empty());
+    let new = cache.search("new.rs").unwrap();
+    assert_eq!(new.len(), 1);
+    assert_eq!(
+        cache.node_path(new[0]),
+        Some(tmp.path().join("project/src/nested/new.rs"))
+    );
+    // Only the flagged folder was re-read.
+    assert!(cache.search("untouched.rs").unwrap().is_empty());
+}
+
+#[test]
+fn test_renames_keep_node_indices() {
+    let tmp = TempDir::new("rename_events").unwrap();
+    fs::create_dir_all(tmp.path().join("drafts/images")).unwrap();
+    fs::create_dir(tmp.path().join("archive")).unwrap();
+    fs::write(tmp.path().join("drafts/report.txt"), b"x").unwrap();
+    fs::write(tmp.path().join("drafts/images/chart.png"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let mut id = cache.last_event_id();
+    let mut rename = |cache: &mut SearchCache, from: &str, to: &str, flag: EventFlag| {
+        fs::rename(tmp.path().join(from), tmp.path().join(to)).unwrap();
+        let flag = flag | EventFlag::ItemRenamed;
+        id += 2;
+        cache
+            .handle_fs_events(vec![
+                FsEvent {
+                    path: tmp.path().join(from),
+                    id: id - 1,
+                    flag,
+                },
+                FsEvent {
+                    path: tmp.path().join(to),
+                    id,
+                    flag,
+                },
+            ])
+            .unwrap()
+    };
+
+    let report = cache.search("report.txt").unwrap()[0];
+    let changes = rename(
+        &mut cache,
+        "drafts/report.txt",
+        "drafts/summary.txt",
+        EventFlag::ItemIsFile,
+    );
+    assert_eq!(
+        changes,
+        NodeChanges {
+            renamed: vec![report],
+            ..Default::default()
+        }
+    );
+    assert!(cache.search("report.txt").unwrap().is_empty());
+    assert_eq!(cache.search("summary.txt").unwrap(), vec![report]);
+    assert_eq!(
+        cache.node_path(report),
+        Some(tmp.path().join("drafts/summary.txt"))
+    );
+
+    // Moving a folder carries its subtree along.
+    let drafts = cache.search("/drafts/").unwrap()[0];
+    let chart = cache.search("chart.png").unwrap()[0];
+    let changes = rename(&mut cache, "drafts", "archive/2024", EventFlag::ItemIsDir);
+    assert!(changes.added.is_empty() && changes.removed.is_empty());
+    assert!(changes.renamed.contains(&drafts) && changes.renamed.contains(&chart));
+    assert_eq!(
+        cache.node_path(chart),
+        Some(tmp.path().join("archive/2024/images/chart.png"))
+    );
+    assert_eq!(cache.search("archive/2024/images/").unwrap().len(), 1);
+    assert_eq!(cache.search("summary.txt").unwrap(), vec![report]);
+    assert!(cache.search("drafts").unwrap().is_empty());
+}
+
 #[test]
 fn test_remove_node_path_nonexistent_returns_none() {
     let tmp = TempDir::new("remove_node_none").unwrap();
@@ -80,6 +312,48 @@ fn test_expand_file_nodes_fetch_metadata() {
     assert!(nodes2[0].metadata.is_some());
 }
 
+#[test]
+fn test_expand_file_nodes_reports_file_type() {
+    let tmp = TempDir::new("expand_type").unwrap();
+    fs::create_dir(tmp.path().join("albums")).unwrap();
+    fs::write(tmp.path().join("albums/song.mp3"), b"x").unwrap();
+    fs::write(tmp.path().join("notes.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let albums = cache.search("albums").unwrap()[0];
+    let song = cache.search("song.mp3").unwrap()[0];
+    let notes = cache.search("notes.txt").unwrap()[0];
+
+    let types = |nodes: Vec<crate::SearchResultNode>| {
+        nodes
+            .into_iter()
+            .map(|node| node.file_type)
+            .collect::<Vec<_>>()
+    };
+    let expected = [NodeFileType::Dir, NodeFileType::File, NodeFileType::File];
+    let query = cache
+        .query_files("albums".into(), CancellationToken::noop())
+        .unwrap()
+        .unwrap();
+    assert_eq!(types(query), [NodeFileType::Dir]);
+    assert_eq!(
+        types(cache.expand_file_nodes(&[albums, song, notes])),
+        expected
+    );
+
+    // Metadata can no longer be read, as after a permission denied error, but the type
+    // recorded by the walk survives.
+    fs::remove_dir_all(tmp.path().join("albums")).unwrap();
+    fs::remove_file(tmp.path().join("notes.txt")).unwrap();
+    cache.file_nodes[albums].metadata =
+        SlabNodeMetadataCompact::none().with_file_type_hint(NodeFileType::Dir);
+    for node in [song, notes] {
+        cache.file_nodes[node].metadata = SlabNodeMetadataCompact::none();
+    }
+    let nodes = cache.expand_file_nodes(&[albums, song, notes]);
+    assert!(nodes.iter().all(|node| node.metadata.is_unaccessible()));
+    assert_eq!(types(nodes), expected);
+}
+
 #[test]
 fn test_persistent_roundtrip() {
     let tmp = TempDir::new("persist_round").unwrap();
@@ -92,3 +366,384 @@ fn test_persistent_roundtrip() {
         SearchCache::try_read_persistent_cache(tmp.path(), &cache_path, None, None).unwrap();
     assert_eq!(loaded.get_total_files(), original_total);
 }
+
+#[test]
+fn test_persistent_accepts_matching_schema_version() {
+    let tmp = TempDir::new("persist_version_ok").unwrap();
+    fs::write(tmp.path().join("a.bin"), b"data").unwrap();
+    let cache_path = tmp.path().join("cache.zstd");
+    SearchCache::walk_fs(tmp.path().to_path_buf())
+        .flush_to_file(&cache_path)
+        .unwrap();
+
+    let bytes = fs::read(&cache_path).unwrap();
+    assert_eq!(
+        u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+        CACHE_SCHEMA_VERSION
+    );
+    let mut loaded =
+        SearchCache::try_read_persistent_cache(tmp.path(), &cache_path, None, None).unwrap();
+    assert_eq!(loaded.search("a.bin").unwrap().len(), 1);
+}
+
+#[test]
+fn test_persistent_migrates_previous_schema_version() {
+    let tmp = TempDir::new("persist_migrate").unwrap();
+    fs::create_dir(tmp.path().join("dir")).unwrap();
+    fs::write(tmp.path().join("dir/a.bin"), b"data").unwrap();
+    fs::write(tmp.path().join("b.bin"), b"data").unwrap();
+    let cache_path = tmp.path().join("cache.zstd");
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    // Leaves a vacant slot in the slab.
+    fs::remove_file(tmp.path().join("b.bin")).unwrap();
+    let id = cache.last_event_id() + 1;
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: tmp.path().join("b.bin"),
+            id,
+            flag: EventFlag::ItemRemoved | EventFlag::ItemIsFile,
+        }])
+        .unwrap();
+    let all = cache.search("").unwrap();
+    // The size filter records the metadata on the node.
+    let a_bin = cache.search("a.bin size:>0").unwrap();
+    assert_eq!(a_bin.len(), 1);
+    let metadata = cache.file_nodes[a_bin[0]].metadata;
+    assert_ne!(metadata.as_ref().unwrap().mode(), 0);
+    cache.flush_to_file(&cache_path).unwrap();
+
+    // Rewrite the snapshot the way version 6 encoded it.
+    let storage = read_cache_from_file(&cache_path).unwrap();
+    let mut slab = ThinSlab::new();
+    slab.patch(
+        storage
+            .slab
+            .iter()
+            .map(|(index, node)| (index, Some(SlabNodeV6::from(node)))),
+    )
+    .unwrap();
+    let legacy = PersistentStorageV6 {
+        version: typed_num::Num,
+        snapshot_id: storage.snapshot_id,
+        last_event_id: storage.last_event_id,
+        path: storage.path,
+        slab_root: storage.slab_root,
+        slab,
+        name_index: storage.name_index,
+    };
+    let mut bytes = b"CRDL".to_vec();
+    bytes.extend_from_slice(&LEGACY_SCHEMA_VERSION.to_le_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(&(legacy.slab.len() as u64).to_le_bytes());
+    bytes.extend(postcard::to_stdvec(&legacy).unwrap());
+    fs::write(&cache_path, bytes).unwrap();
+
+    let mut loaded =
+        SearchCache::try_read_persistent_cache(tmp.path(), &cache_path, None, None).unwrap();
+    assert_eq!(loaded.search("").unwrap(), all);
+    assert_eq!(loaded.search("a.bin size:>0").unwrap(), a_bin);
+    assert!(loaded.search("b.bin").unwrap().is_empty());
+    let migrated = loaded.file_nodes[a_bin[0]].metadata;
+    let migrated = migrated.as_ref().unwrap();
+    assert_eq!(migrated.size(), metadata.as_ref().unwrap().size());
+    assert_eq!(migrated.mode(), 0);
+
+    // Written back in the current version.
+    loaded.flush_to_file(&cache_path).unwrap();
+    let bytes = fs::read(&cache_path).unwrap();
+    assert_eq!(
+        u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+        CACHE_SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn test_persistent_header_records_node_count() {
+    let tmp = TempDir::new("persist_node_count").unwrap();
+    fs::create_dir(tmp.path().join("dir")).unwrap();
+    fs::write(tmp.path().join("dir/a.bin"), b"data").unwrap();
+    fs::write(tmp.path().join("b.bin"), b"data").unwrap();
+    let cache_path = tmp.path().join("cache.zstd");
+    let cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let total = cache.get_total_files();
+    cache.flush_to_file(&cache_path).unwrap();
+
+    assert_eq!(read_cache_node_count(&cache_path).unwrap(), total as u64);
+    assert!(read_cache_node_count(&tmp.path().join("missing")).is_err());
+}
+
+#[test]
+fn test_persistent_rejects_other_schema_version() {
+    let tmp = TempDir::new("persist_version").unwrap();
+    fs::write(tmp.path().join("a.bin"), b"data").unwrap();
+    let cache_path = tmp.path().join("cache.zstd");
+    SearchCache::walk_fs(tmp.path().to_path_buf())
+        .flush_to_file(&cache_path)
+        .unwrap();
+
+    let mut bytes = fs::read(&cache_path).unwrap();
+    assert_eq!(&bytes[..4], b"CRDL");
+    let expected = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    bytes[4] += 1;
+    fs::write(&cache_path, &bytes).unwrap();
+
+    let err = SearchCache::try_read_persistent_cache(tmp.path(), &cache_path, None, None)
+        .expect_err("bumped schema version must be rejected");
+    assert_eq!(
+        err.downcast_ref::<CacheVersionMismatch>(),
+        Some(&CacheVersionMismatch {
+            found: expected + 1,
+            expected,
+        })
+    );
+}
+
+#[test]
+fn test_persistent_compression_roundtrip() {
+    let tmp = TempDir::new("persist_compression").unwrap();
+    let root = tmp.path().join("root");
+    for dir in 0..20 {
+        let dir = root.join(format!("module_{dir}"));
+        fs::create_dir_all(&dir).unwrap();
+        for file in 0..20 {
+            fs::write(dir.join(format!("source_file_{file}.rs")), b"").unwrap();
+        }
+    }
+    let query = |cache: &mut SearchCache| {
+        let mut paths: Vec<PathBuf> = cache
+            .query_files("source_file_1".to_string(), CancellationToken::noop())
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|node| node.path)
+            .collect();
+        paths.sort();
+        paths
+    };
+    let mut original = SearchCache::walk_fs(root.clone());
+    let expected = query(&mut original);
+    let total = original.get_total_files();
+
+    let plain_path = tmp.path().join("plain.cache");
+    let zstd_path = tmp.path().join("zstd.cache");
+    SearchCache::walk_fs(root.clone())
+        .flush_to_file_with_compression(&plain_path, CacheCompression::None)
+        .unwrap();
+    original
+        .flush_to_file_with_compression(&zstd_path, CacheCompression::Zstd { level: 19 })
+        .unwrap();
+
+    let plain_len = fs::metadata(&plain_path).unwrap().len();
+    let zstd_len = fs::metadata(&zstd_path).unwrap().len();
+    assert!(
+        zstd_len < plain_len,
+        "compressed {zstd_len} bytes, plain {plain_len} bytes"
+    );
+
+    for cache_path in [&plain_path, &zstd_path] {
+        let mut loaded =
+            SearchCache::try_read_persistent_cache(&root, cache_path, None, None).unwrap();
+        assert_eq!(loaded.get_total_files(), total);
+        assert_eq!(query(&mut loaded), expected);
+    }
+}
+
+#[test]
+fn test_persistent_rejects_headerless_file() {
+    let tmp = TempDir::new("persist_headerless").unwrap();
+    let cache_path = tmp.path().join("cache.zstd");
+    // Caches written before the header existed start directly with the zstd frame.
+    fs::write(&cache_path, [0x28, 0xb5, 0x2f, 0xfd, 0, 0, 0, 0, 0]).unwrap();
+    assert!(SearchCache::try_read_persistent_cache(tmp.path(), &cache_path, None, None).is_err());
+}
+
+/// Every path in `cache`, sorted, together with the event id it resumes from.
+fn persisted_state(cache: &mut SearchCache) -> (Vec<PathBuf>, u64) {
+    let mut paths: Vec<PathBuf> = cache
+        .search_empty(CancellationToken::noop())
+        .unwrap()
+        .into_iter()
+        .map(|index| cache.node_path(index).unwrap())
+        .collect();
+    paths.sort();
+    (paths, cache.last_event_id())
+}
+
+/// A tree whose uncompressed snapshot dwarfs the journal of a few changes.
+fn journal_fixture(name: &str) -> (TempDir, PathBuf) {
+    let tmp = TempDir::new(name).unwrap();
+    let root = tmp.path().join("root");
+    for dir in 0..10 {
+        let dir = root.join(format!("module_{dir}"));
+        fs::create_dir_all(&dir).unwrap();
+        for file in 0..20 {
+            fs::write(dir.join(format!("source_file_{file}.rs")), b"").unwrap();
+        }
+    }
+    let cache_path = tmp.path().join("cache.db");
+    SearchCache::walk_fs(root.clone())
+        .flush_to_file_with_compression(&cache_path, CacheCompression::None)
+        .unwrap();
+    (tmp, root)
+}
+
+fn change_tree(root: &Path) {
+    fs::remove_dir_all(root.join("module_3")).unwrap();
+    fs::create_dir(root.join("module_new")).unwrap();
+    fs::write(root.join("module_new/fresh.rs"), b"").unwrap();
+    fs::write(root.join("module_0/extra.rs"), b"").unwrap();
+}
+
+fn report_changes(cache: &mut SearchCache, root: &Path) {
+    let mut id = cache.last_event_id();
+    for (path, flag) in [
+        (root.join("module_3"), EventFlag::ItemRemoved),
+        (root.join("module_new"), EventFlag::ItemCreated),
+        (root.join("module_0/extra.rs"), EventFlag::ItemCreated),
+    ] {
+        id += 1;
+        cache
+            .handle_fs_events(vec![FsEvent { path, id, flag }])
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_incremental_flush_reloads_like_full_flush() {
+    let (tmp, root) = journal_fixture("persist_journal");
+    let cache_path = tmp.path().join("cache.db");
+    let full_path = tmp.path().join("full.db");
+    fs::copy(&cache_path, &full_path).unwrap();
+    let snapshot = fs::read(&cache_path).unwrap();
+
+    let mut cache = SearchCache::try_read_persistent_cache(&root, &cache_path, None, None).unwrap();
+    let mut full = SearchCache::try_read_persistent_cache(&root, &full_path, None, None).unwrap();
+    change_tree(&root);
+    report_changes(&mut cache, &root);
+    report_changes(&mut full, &root);
+    let expected = persisted_state(&mut cache);
+    cache.flush_incremental(&cache_path).unwrap();
+    assert_eq!(fs::read(&cache_path).unwrap(), snapshot);
+    assert!(tmp.path().join("cache.journal").exists());
+    full.flush_to_file(&full_path).unwrap();
+
+    let mut incremental =
+        SearchCache::try_read_persistent_cache(&root, &cache_path, None, None).unwrap();
+    let mut full = SearchCache::try_read_persistent_cache(&root, &full_path, None, None).unwrap();
+    assert_eq!(persisted_state(&mut incremental), expected);
+    assert_eq!(persisted_state(&mut full), expected);
+    for query in ["fresh", "extra", "source_file_1", "module_3"] {
+        assert_eq!(
+            incremental.search(query).unwrap().len(),
+            full.search(query).unwrap().len(),
+            "{query}"
+        );
+    }
+    assert!(incremental.search("module_3").unwrap().is_empty());
+
+    // A second session appends to the same journal.
+    fs::write(root.join("module_1/later.rs"), b"").unwrap();
+    let id = incremental.last_event_id() + 1;
+    incremental
+        .handle_fs_events(vec![FsEvent {
+            path: root.join("module_1/later.rs"),
+            id,
+            flag: EventFlag::ItemCreated,
+        }])
+        .unwrap();
+    let expected = persisted_state(&mut incremental);
+    incremental.flush_incremental(&cache_path).unwrap();
+    assert_eq!(fs::read(&cache_path).unwrap(), snapshot);
+    let mut reloaded =
+        SearchCache::try_read_persistent_cache(&root, &cache_path, None, None).unwrap();
+    assert_eq!(persisted_state(&mut reloaded), expected);
+    assert_eq!(reloaded.search("later.rs").unwrap().len(), 1);
+}
+
+#[test]
+fn test_incremental_flush_compacts_large_journal() {
+    let (tmp, root) = journal_fixture("persist_journal_compact");
+    let cache_path = tmp.path().join("cache.db");
+    let journal_path = tmp.path().join("cache.journal");
+
+    let mut cache = SearchCache::try_read_persistent_cache(&root, &cache_path, None, None).unwrap();
+    // Replacing most of the tree makes the journal outgrow the snapshot.
+    let mut id = cache.last_event_id();
+    for dir in 0..10 {
+        let path = root.join(format!("module_{dir}"));
+        fs::remove_dir_all(&path).unwrap();
+        id += 1;
+        cache
+            .handle_fs_events(vec![FsEvent {
+                path,
+                id,
+                flag: EventFlag::ItemRemoved,
+            }])
+            .unwrap();
+    }
+    let replacement = root.join("replacement");
+    fs::create_dir(&replacement).unwrap();
+    for file in 0..300 {
+        fs::write(
+            replacement.join(format!("replacement_source_{file}.rs")),
+            b"",
+        )
+        .unwrap();
+    }
+    cache
+        .handle_fs_events(vec![FsEvent {
+            path: replacement,
+            id: id + 1,
+            flag: EventFlag::ItemCreated,
+        }])
+        .unwrap();
+    let expected = persisted_state(&mut cache);
+    cache.flush_incremental(&cache_path).unwrap();
+    assert!(!journal_path.exists());
+
+    let mut reloaded =
+        SearchCache::try_read_persistent_cache(&root, &cache_path, None, None).unwrap();
+    assert_eq!(persisted_state(&mut reloaded), expected);
+}
+
+#[test]
+fn test_incremental_flush_without_snapshot_writes_everything() {
+    let tmp = TempDir::new("persist_journal_fresh").unwrap();
+    fs::write(tmp.path().join("a.bin"), b"data").unwrap();
+    let cache_path = tmp.path().join("cache.db");
+    SearchCache::walk_fs(tmp.path().to_path_buf())
+        .flush_incremental(&cache_path)
+        .unwrap();
+    assert!(!tmp.path().join("cache.journal").exists());
+    let mut loaded =
+        SearchCache::try_read_persistent_cache(tmp.path(), &cache_path, None, None).unwrap();
+    assert_eq!(loaded.search("a.bin").unwrap().len(), 1);
+}
+
+#[test]
+fn test_torn_journal_record_is_dropped() {
+    let (tmp, root) = journal_fixture("persist_journal_torn");
+    let cache_path = tmp.path().join("cache.db");
+    let journal_path = tmp.path().join("cache.journal");
+
+    let mut cache = SearchCache::try_read_persistent_cache(&root, &cache_path, None, None).unwrap();
+    change_tree(&root);
+    report_changes(&mut cache, &root);
+    let expected = persisted_state(&mut cache);
+    cache.flush_incremental(&cache_path).unwrap();
+
+    // A flush interrupted halfway through its record.
+    let mut journal = fs::read(&journal_path).unwrap();
+    journal.extend_from_slice(&[200, 0, 0, 0, 1, 2, 3]);
+    fs::write(&journal_path, &journal).unwrap();
+
+    let mut loaded =
+        SearchCache::try_read_persistent_cache(&root, &cache_path, None, None).unwrap();
+    assert_eq!(persisted_state(&mut loaded), expected);
+    // The torn journal can't be appended to, so the next flush starts over.
+    loaded.flush_incremental(&cache_path).unwrap();
+    assert!(!journal_path.exists());
+    let mut reloaded =
+        SearchCache::try_read_persistent_cache(&root, &cache_path, None, None).unwrap();
+    assert_eq!(persisted_state(&mut reloaded), expected);
+}
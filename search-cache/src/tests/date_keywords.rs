@@ -46,6 +46,65 @@ fn test_date_filters_cover_keywords_and_ranges() {
     assert_file_hits(&cache, &dc_hyphen, &["very_old.txt"]);
 }
 
+#[test]
+fn date_filters_resolve_against_injected_reference() {
+    use jiff::{civil::date, tz::TimeZone};
+
+    let tmp = TempDir::new("date_reference").unwrap();
+    for name in ["fresh.txt", "mid_2023.txt", "eve_2022.txt"] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let utc = |y, m, d, h| {
+        date(y, m, d)
+            .at(h, 0, 0, 0)
+            .to_zoned(TimeZone::UTC)
+            .unwrap()
+            .timestamp()
+            .as_second()
+    };
+    let fresh = cache.search("fresh.txt").unwrap()[0];
+    let mid = cache.search("mid_2023.txt").unwrap()[0];
+    let eve = cache.search("eve_2022.txt").unwrap()[0];
+    set_file_times(&mut cache, fresh, utc(2024, 3, 15, 9), utc(2024, 3, 15, 9));
+    set_file_times(&mut cache, mid, utc(2023, 6, 1, 9), utc(2024, 3, 14, 9));
+    set_file_times(
+        &mut cache,
+        eve,
+        utc(2022, 12, 31, 23),
+        utc(2022, 12, 31, 23),
+    );
+
+    let now = date(2024, 3, 15)
+        .at(12, 0, 0, 0)
+        .to_zoned(TimeZone::UTC)
+        .unwrap();
+    cache.set_date_reference(Some(now));
+
+    let hits = cache.search("dm:today").unwrap();
+    assert_file_hits(&cache, &hits, &["fresh.txt"]);
+    let hits = cache.search("dm:yesterday").unwrap();
+    assert_file_hits(&cache, &hits, &["mid_2023.txt"]);
+    let hits = cache.search("dm:pastweek").unwrap();
+    assert_file_hits(&cache, &hits, &["fresh.txt", "mid_2023.txt"]);
+    let hits = cache.search("dc:2023-01-01..2023-12-31").unwrap();
+    assert_file_hits(&cache, &hits, &["mid_2023.txt"]);
+
+    // Relative spans count back from today like the `past` keywords.
+    let hits = cache.search("dm:past1day").unwrap();
+    assert_file_hits(&cache, &hits, &["fresh.txt", "mid_2023.txt"]);
+    let hits = cache.search("dc:past1years").unwrap();
+    assert_file_hits(&cache, &hits, &["fresh.txt", "mid_2023.txt"]);
+    let hits = cache.search("dc:last15months").unwrap();
+    assert_file_hits(
+        &cache,
+        &hits,
+        &["fresh.txt", "mid_2023.txt", "eve_2022.txt"],
+    );
+    let hits = cache.search("dc:<past3months").unwrap();
+    assert_file_hits(&cache, &hits, &["mid_2023.txt", "eve_2022.txt"]);
+}
+
 #[test]
 fn date_filter_reuses_existing_and_base() {
     let tmp = TempDir::new("date_filter_base").unwrap();
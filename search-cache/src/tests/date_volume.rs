@@ -488,7 +488,7 @@ fn segment_10_stress_queries() {
     let outcome = cache
         .search_with_options(
             "dm:2024-01-01-2024-03-01",
-            crate::SearchOptions::default(),
+            &crate::SearchOptions::default(),
             token,
         )
         .unwrap();
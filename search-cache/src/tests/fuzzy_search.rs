@@ -0,0 +1,70 @@
+use super::prelude::*;
+
+fn fuzzy_names(cache: &mut SearchCache, query: &str, max_distance: u8) -> Vec<String> {
+    let options = SearchOptions {
+        fuzzy_distance: Some(max_distance),
+        ..Default::default()
+    };
+    cache
+        .query_files_with_options(query.to_string(), &options, CancellationToken::noop())
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .filter_map(|node| {
+            node.path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .collect()
+}
+
+fn corpus(name: &str, files: &[&str]) -> (TempDir, SearchCache) {
+    let tmp = TempDir::new(name).unwrap();
+    for file in files {
+        fs::write(tmp.path().join(file), b"x").unwrap();
+    }
+    let cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    (tmp, cache)
+}
+
+#[test]
+fn misspelled_word_matches_within_distance() {
+    let (_tmp, mut cache) = corpus("fuzzy_distance", &["report.txt", "notes.md"]);
+    assert_eq!(fuzzy_names(&mut cache, "reprot", 2), ["report.txt"]);
+    assert!(fuzzy_names(&mut cache, "reprot", 1).is_empty());
+    assert!(cache.search("reprot").unwrap().is_empty());
+}
+
+#[test]
+fn closer_matches_rank_first() {
+    let (_tmp, mut cache) = corpus(
+        "fuzzy_rank",
+        &[
+            "raport.txt",
+            "repost.txt",
+            "reprt.txt",
+            "report.txt",
+            "other.txt",
+        ],
+    );
+    let names = fuzzy_names(&mut cache, "report", 1);
+    assert_eq!(names[0], "report.txt");
+    assert_eq!(names.len(), 4);
+    assert!(!names.contains(&"other.txt".to_string()));
+}
+
+#[test]
+fn filters_and_phrases_stay_exact() {
+    let (_tmp, mut cache) = corpus("fuzzy_exact", &["report.txt", "report.md", "raport.md"]);
+    assert_eq!(fuzzy_names(&mut cache, "reprot ext:txt", 2), ["report.txt"]);
+    assert!(fuzzy_names(&mut cache, "reprot ext:tx", 2).is_empty());
+    assert!(fuzzy_names(&mut cache, "\"reprot\"", 2).is_empty());
+    assert!(fuzzy_names(&mut cache, "regex:^reprot", 2).is_empty());
+}
+
+#[test]
+fn fuzzy_mode_does_not_leak_into_later_searches() {
+    let (_tmp, mut cache) = corpus("fuzzy_reset", &["report.txt"]);
+    assert_eq!(fuzzy_names(&mut cache, "reprot", 2), ["report.txt"]);
+    assert!(cache.search("reprot").unwrap().is_empty());
+}
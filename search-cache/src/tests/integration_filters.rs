@@ -1,4 +1,6 @@
-use super::prelude::*;
+use super::{prelude::*, support::assert_file_hits};
+use crate::{CustomFilters, SlabNode, UnknownCustomFilter};
+use cardinal_syntax::FilterArgument;
 
 #[test]
 fn test_combined_filters_all_match() {
@@ -301,3 +303,34 @@ fn test_nosubfolders_only_filters_target_tree() {
     assert_eq!(paths.len(), 1);
     assert_eq!(paths[0], projects.join("report.txt"));
 }
+
+#[test]
+fn test_registered_custom_filter_narrows_results() {
+    let tmp = TempDir::new("custom_filter_registry").unwrap();
+    fs::write(tmp.path().join("alpha_notes.txt"), b"x").unwrap();
+    fs::write(tmp.path().join("beta_notes.txt"), b"x").unwrap();
+    fs::write(tmp.path().join("alpha_plan.md"), b"x").unwrap();
+
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    assert!(cache.search("proj:alpha").is_err());
+
+    let mut filters = CustomFilters::default();
+    filters.register("proj", |node: &SlabNode, arg: Option<&FilterArgument>| {
+        arg.is_some_and(|arg| node.name_and_parent.as_str().starts_with(arg.raw.as_str()))
+    });
+    cache.set_custom_filters(filters.clone());
+
+    let results = cache.search("proj:alpha").unwrap();
+    assert_file_hits(&cache, &results, &["alpha_notes.txt", "alpha_plan.md"]);
+    let results = cache.search("notes PROJ:alpha").unwrap();
+    assert_file_hits(&cache, &results, &["alpha_notes.txt"]);
+    let results = cache.search("ext:txt !proj:alpha").unwrap();
+    assert_file_hits(&cache, &results, &["beta_notes.txt"]);
+
+    // Filters without a handler are ignored once configured to match everything.
+    assert!(cache.search("notes other:").is_err());
+    filters.set_unknown(UnknownCustomFilter::MatchAll);
+    cache.set_custom_filters(filters);
+    let results = cache.search("notes other:").unwrap();
+    assert_file_hits(&cache, &results, &["alpha_notes.txt", "beta_notes.txt"]);
+}
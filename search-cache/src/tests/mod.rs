@@ -1,7 +1,7 @@
 #![allow(clippy::too_many_lines)]
 
 mod prelude {
-    pub(super) use crate::SearchCache;
+    pub(super) use crate::{SearchCache, SearchOptions};
     pub(super) use fswalk::NodeFileType;
     pub(super) use jiff::Timestamp;
     pub(super) use search_cancel::CancellationToken;
@@ -15,8 +15,11 @@ mod cache_flow;
 mod date_edges;
 mod date_keywords;
 mod date_volume;
+mod fuzzy_search;
 mod integration_filters;
 mod query_logic;
+mod ranking;
 mod size_filters;
+mod sorting;
 mod traversal;
 mod type_filters;
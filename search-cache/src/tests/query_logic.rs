@@ -1,4 +1,4 @@
-use super::prelude::*;
+use super::{prelude::*, support::assert_file_hits};
 
 #[test]
 fn test_query_and_or_not_dedup_and_filtering() {
@@ -35,6 +35,81 @@ fn test_regex_prefix_in_queries() {
     assert_eq!(idxs.len(), 2);
 }
 
+#[test]
+fn test_regex_matches_file_names() {
+    let tmp = TempDir::new("query_regex_names").unwrap();
+    for name in [
+        "holiday.jpg",
+        "scan.jpeg",
+        "photo.jpg.bak",
+        "report_2025.pdf",
+        "old_report_2025.pdf",
+        "report_2025.pdf.bak",
+        "v12.txt",
+    ] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    let jpegs = cache.search(r"regex:\.jpe?g$").unwrap();
+    assert_file_hits(&cache, &jpegs, &["holiday.jpg", "scan.jpeg"]);
+
+    let years = cache.search("regex:[0-9]{4}").unwrap();
+    assert_file_hits(
+        &cache,
+        &years,
+        &[
+            "old_report_2025.pdf",
+            "report_2025.pdf",
+            "report_2025.pdf.bak",
+        ],
+    );
+
+    // Anchors pin the start and end of the name, not of the path.
+    let anchored = cache.search(r"regex:^report.*\d{4}\.pdf$").unwrap();
+    assert_file_hits(&cache, &anchored, &["report_2025.pdf"]);
+    let unanchored = cache.search(r"regex:report.*\d{4}\.pdf").unwrap();
+    assert_eq!(unanchored.len(), 3);
+}
+
+#[test]
+fn test_regex_with_slash_matches_full_paths() {
+    let tmp = TempDir::new("query_regex_paths").unwrap();
+    fs::create_dir_all(tmp.path().join("2025/q1")).unwrap();
+    fs::create_dir_all(tmp.path().join("archive")).unwrap();
+    fs::write(tmp.path().join("2025/q1/summary.pdf"), b"x").unwrap();
+    fs::write(tmp.path().join("2025/plan.pdf"), b"x").unwrap();
+    fs::write(tmp.path().join("archive/2025.pdf"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    let under_year = cache.search(r"regex:/2025/.*\.pdf$").unwrap();
+    assert_file_hits(&cache, &under_year, &["plan.pdf", "summary.pdf"]);
+    let direct = cache.search(r"regex:/2025/[^/]+\.pdf$").unwrap();
+    assert_file_hits(&cache, &direct, &["plan.pdf"]);
+    let named = cache.search(r"regex:/2025\.pdf$").unwrap();
+    assert_file_hits(&cache, &named, &["2025.pdf"]);
+
+    let root = regex::escape(&tmp.path().to_string_lossy());
+    let anchored = cache.search(&format!("regex:^{root}/archive/")).unwrap();
+    assert_file_hits(&cache, &anchored, &["2025.pdf"]);
+    // Combines with other terms like any other regex.
+    let combined = cache.search(r"regex:/2025/ summary").unwrap();
+    assert_file_hits(&cache, &combined, &["summary.pdf"]);
+}
+
+#[test]
+fn test_invalid_regex_is_an_error() {
+    let tmp = TempDir::new("query_regex_invalid").unwrap();
+    fs::write(tmp.path().join("a.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    let err = cache.search("regex:(unclosed").unwrap_err();
+    assert!(
+        err.to_string().contains("Invalid regex pattern"),
+        "unexpected error: {err}"
+    );
+}
+
 #[test]
 fn test_ext_list_and_intersection() {
     let tmp = TempDir::new("query_ext_list").unwrap();
@@ -139,3 +214,354 @@ fn test_extension_case_sensitivity_in_type_filter() {
     let results = cache.search("type:picture").unwrap();
     assert_eq!(results.len(), 3, "Should match case-insensitively");
 }
+
+#[test]
+fn test_near_requires_words_close_together() {
+    let tmp = TempDir::new("query_near").unwrap();
+    for name in [
+        "error_timeout.log",
+        "error connection timeout.log",
+        "error in the slow upstream timeout.log",
+        "timeout-then-error.log",
+        "error.log",
+    ] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    let hits = cache.search("error NEAR/1 timeout").unwrap();
+    assert_file_hits(&cache, &hits, &["error_timeout.log"]);
+
+    let hits = cache.search("error NEAR/2 timeout").unwrap();
+    assert_file_hits(
+        &cache,
+        &hits,
+        &[
+            "error_timeout.log",
+            "error connection timeout.log",
+            "timeout-then-error.log",
+        ],
+    );
+
+    // Plain AND ignores the distance.
+    let hits = cache.search("error timeout").unwrap();
+    assert_eq!(hits.len(), 4);
+}
+
+#[test]
+fn test_near_with_phrases_and_complex_operands() {
+    let tmp = TempDir::new("query_near_complex").unwrap();
+    for name in [
+        "quarterly sales report.pdf",
+        "sales of the quarterly report.pdf",
+        "Sales Report.md",
+    ] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    let hits = cache.search("\"quarterly sales\" NEAR/1 report").unwrap();
+    assert_file_hits(&cache, &hits, &["quarterly sales report.pdf"]);
+
+    // Case-insensitive matching folds the name before measuring.
+    let hits = cache
+        .search_with_options(
+            "sales NEAR/1 report",
+            &crate::SearchOptions {
+                case_insensitive: true,
+                ..Default::default()
+            },
+            CancellationToken::noop(),
+        )
+        .unwrap()
+        .nodes
+        .unwrap();
+    assert_file_hits(
+        &cache,
+        &hits,
+        &["quarterly sales report.pdf", "Sales Report.md"],
+    );
+
+    // A group operand only needs to match, like AND.
+    let hits = cache.search("<sales|Sales> NEAR/1 ext:pdf").unwrap();
+    assert_file_hits(
+        &cache,
+        &hits,
+        &[
+            "quarterly sales report.pdf",
+            "sales of the quarterly report.pdf",
+        ],
+    );
+}
+
+#[test]
+fn test_parse_errors_reach_the_caller() {
+    let tmp = TempDir::new("query_parse_error").unwrap();
+    fs::write(tmp.path().join("report.txt"), b"r").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    for (query, position) in [("report \"unterminated", 7), ("a (b c", 6)] {
+        let err = cache
+            .query_files(query.to_string(), CancellationToken::noop())
+            .expect_err("malformed query must fail");
+        let parse_error = err
+            .downcast_ref::<cardinal_syntax::ParseError>()
+            .unwrap_or_else(|| panic!("{query}: {err:?} should carry a ParseError"));
+        assert_eq!(parse_error.position, position, "{query}");
+        assert!(!parse_error.message.is_empty());
+        assert!(format!("{err:#}").contains(&parse_error.message));
+    }
+
+    let hits = cache.search("report").unwrap();
+    assert_file_hits(&cache, &hits, &["report.txt"]);
+}
+
+#[test]
+fn test_wildcard_words_reject_literal_chunk_false_positives() {
+    let tmp = TempDir::new("query_glob").unwrap();
+    for name in ["axb", "ab", "ba", "cab", "abc", "AyyB"] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    // Every name contains the literal chunk `a`; only the glob decides.
+    let hits = cache.search("a*b").unwrap();
+    assert_file_hits(&cache, &hits, &["ab", "axb"]);
+
+    let hits = cache.search("?b").unwrap();
+    assert_file_hits(&cache, &hits, &["ab"]);
+
+    // The case-sensitive substring prefilter must not hide `AyyB`.
+    let hits = cache
+        .search_with_options(
+            "a*b",
+            &crate::SearchOptions {
+                case_insensitive: true,
+                ..Default::default()
+            },
+            CancellationToken::noop(),
+        )
+        .unwrap()
+        .nodes
+        .unwrap();
+    assert_file_hits(&cache, &hits, &["AyyB", "ab", "axb"]);
+}
+
+#[test]
+fn test_whole_filename_and_whole_word_toggles() {
+    let tmp = TempDir::new("query_whole_names").unwrap();
+    for name in [
+        "report",
+        "report.txt",
+        "reports.txt",
+        "old report.md",
+        "notes.txt",
+    ] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    let hits = cache.search("report").unwrap();
+    assert_file_hits(
+        &cache,
+        &hits,
+        &["report", "report.txt", "reports.txt", "old report.md"],
+    );
+
+    let hits = cache.search("wholefilename: report").unwrap();
+    assert_file_hits(&cache, &hits, &["report"]);
+    // A toggle covers the terms after it.
+    let hits = cache.search("wfn: report | notes.txt").unwrap();
+    assert_file_hits(&cache, &hits, &["report", "notes.txt"]);
+    let hits = cache.search("notes wfn: report").unwrap();
+    assert!(hits.is_empty());
+    let hits = cache.search("report wfn:").unwrap();
+    assert_eq!(hits.len(), 4);
+
+    let hits = cache.search("wholeword: report").unwrap();
+    assert_file_hits(&cache, &hits, &["report", "report.txt", "old report.md"]);
+    let hits = cache.search("ww: wfn: report").unwrap();
+    assert_file_hits(&cache, &hits, &["report"]);
+
+    // With an argument the modifier only covers that term.
+    let hits = cache.search("wfn:report | notes").unwrap();
+    assert_file_hits(&cache, &hits, &["report", "notes.txt"]);
+    let hits = cache.search("wfn: nowholefilename:report").unwrap();
+    assert_file_hits(
+        &cache,
+        &hits,
+        &["report", "report.txt", "reports.txt", "old report.md"],
+    );
+
+    // The negative toggles are the default.
+    let hits = cache
+        .search("nowholeword: nowholefilename: report")
+        .unwrap();
+    assert_eq!(hits.len(), 4);
+}
+
+#[test]
+fn test_negated_or_alternative_name_toggles_do_nothing() {
+    let tmp = TempDir::new("query_inert_toggles").unwrap();
+    for name in ["report", "report.txt", "notes.txt"] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    for query in [
+        "report !wfn:",
+        "!wfn: report",
+        "report | wfn:",
+        "wfn: | report",
+        "report !ww:",
+    ] {
+        let hits = cache.search(query).unwrap();
+        assert_file_hits(&cache, &hits, &["report", "report.txt"]);
+    }
+    let everything = cache.search("").unwrap();
+    assert_eq!(cache.search("!wfn:").unwrap(), everything);
+    // Inside a group the toggle only covers the rest of the group.
+    let hits = cache.search("(wfn: report) | notes").unwrap();
+    assert_file_hits(&cache, &hits, &["report", "notes.txt"]);
+}
+
+#[test]
+fn test_or_of_words_matches_per_term_union() {
+    let tmp = TempDir::new("query_or_words").unwrap();
+    fs::create_dir(tmp.path().join("bar_dir")).unwrap();
+    for name in [
+        "foo.txt",
+        "Foo Report.md",
+        "bar_dir/inner bar.txt",
+        "bazooka.png",
+        "a.b.log",
+        "axb.log",
+        "other.txt",
+    ] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    for case_insensitive in [false, true] {
+        let options = crate::SearchOptions {
+            case_insensitive,
+            ..Default::default()
+        };
+        let mut search = |query: &str| {
+            cache
+                .search_with_options(query, &options, CancellationToken::noop())
+                .unwrap()
+                .nodes
+                .unwrap()
+        };
+        for terms in [
+            &["foo", "bar", "baz"][..],
+            &["foo", "\"inner bar\""],
+            &["a.b", "missing"],
+        ] {
+            let mut naive: Vec<_> = terms.iter().flat_map(|term| search(term)).collect();
+            naive.sort_unstable();
+            naive.dedup();
+            let mut single_pass = search(&terms.join("|"));
+            assert_eq!(
+                single_pass.len(),
+                naive.len(),
+                "{terms:?} returned duplicates"
+            );
+            single_pass.sort_unstable();
+            assert_eq!(
+                single_pass, naive,
+                "{terms:?}, case_insensitive={case_insensitive}"
+            );
+        }
+    }
+
+    // Needles are literal, `a.b` doesn't match `axb`.
+    let hits = cache.search("a.b|missing").unwrap();
+    assert_file_hits(&cache, &hits, &["a.b.log"]);
+}
+
+#[test]
+fn test_folded_negated_filters_match_expression_not() {
+    use cardinal_syntax::{fold_negated_filters, optimize_query, parse_query};
+
+    let tmp = TempDir::new("query_folded_not").unwrap();
+    for name in ["report.txt", "report.tmp", "notes.md", "draft.tmp"] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    for query in ["!ext:tmp", "report !ext:tmp", "!ext:tmp|notes"] {
+        let mut expected = cache.search(query).unwrap();
+        let folded = fold_negated_filters(optimize_query(parse_query(query).unwrap()));
+        let mut hits = cache
+            .evaluate_expr(
+                &folded.expr,
+                &SearchOptions::default(),
+                Default::default(),
+                CancellationToken::noop(),
+            )
+            .unwrap()
+            .unwrap();
+        expected.sort_unstable();
+        hits.sort_unstable();
+        assert_eq!(hits, expected, "{query}");
+    }
+}
+
+#[test]
+fn test_query_within_matches_combined_query() {
+    let tmp = TempDir::new("query_within").unwrap();
+    fs::create_dir(tmp.path().join("reports")).unwrap();
+    for name in [
+        "report.txt",
+        "report draft.md",
+        "reports/q1 report.txt",
+        "reports/summary.txt",
+        "notes.txt",
+    ] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let paths = |nodes: Vec<crate::SearchResultNode>| {
+        let mut paths: Vec<_> = nodes.into_iter().map(|node| node.path).collect();
+        paths.sort();
+        paths
+    };
+
+    let broad = cache.search("report").unwrap();
+    let parent = format!("parent:{}", tmp.path().join("reports").display());
+    for refine in ["ext:txt", "!draft", "q1|draft", &parent, "folder:"] {
+        let options = SearchOptions {
+            within: Some(broad.clone()),
+            ..Default::default()
+        };
+        let refined = cache
+            .query_files_with_options(refine.to_string(), &options, CancellationToken::noop())
+            .unwrap()
+            .unwrap();
+        let combined = cache
+            .query_files(format!("report {refine}"), CancellationToken::noop())
+            .unwrap()
+            .unwrap();
+        assert_eq!(paths(refined), paths(combined), "{refine}");
+    }
+
+    // Refining keeps the order of the given results.
+    let mut base = cache.search("ext:txt").unwrap();
+    base.reverse();
+    let options = SearchOptions {
+        within: Some(base.clone()),
+        ..Default::default()
+    };
+    let refined = cache
+        .query_files_with_options("".into(), &options, CancellationToken::noop())
+        .unwrap()
+        .unwrap();
+    let expected: Vec<_> = base
+        .iter()
+        .map(|&index| cache.node_path(index).unwrap())
+        .collect();
+    let refined: Vec<_> = refined.into_iter().map(|node| node.path).collect();
+    assert_eq!(refined, expected);
+}
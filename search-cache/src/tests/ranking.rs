@@ -0,0 +1,106 @@
+use super::{prelude::*, support::set_file_times};
+use crate::{RankingWeights, ResultOrder};
+use jiff::Zoned;
+
+fn ranked_names(cache: &mut SearchCache, query: &str, weights: &RankingWeights) -> Vec<String> {
+    let options = SearchOptions {
+        order: ResultOrder::Ranked(*weights),
+        ..Default::default()
+    };
+    cache
+        .query_files_with_options(query.to_string(), &options, CancellationToken::noop())
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .filter_map(|node| {
+            node.path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .collect()
+}
+
+fn index_of(cache: &mut SearchCache, name: &str) -> crate::SlabIndex {
+    cache.search(name).unwrap()[0]
+}
+
+#[test]
+fn whole_name_outranks_deep_substring() {
+    let tmp = TempDir::new("ranking_whole_name").unwrap();
+    fs::write(tmp.path().join("my_old_report_draft.txt"), b"x").unwrap();
+    fs::write(tmp.path().join("report.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    // Index order has the draft first.
+    assert_eq!(
+        cache.search("report").unwrap(),
+        [
+            index_of(&mut cache, "my_old_report_draft.txt"),
+            index_of(&mut cache, "report.txt")
+        ]
+    );
+    assert_eq!(
+        ranked_names(&mut cache, "report", &RankingWeights::default()),
+        ["report.txt", "my_old_report_draft.txt"]
+    );
+    let options = SearchOptions {
+        order: ResultOrder::Ranked(RankingWeights::default()),
+        ..Default::default()
+    };
+    let nodes = cache
+        .search_with_options("report", &options, CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    assert_eq!(nodes[0], index_of(&mut cache, "report.txt"));
+}
+
+#[test]
+fn prefix_and_shallow_paths_rank_higher() {
+    let tmp = TempDir::new("ranking_prefix_depth").unwrap();
+    fs::create_dir_all(tmp.path().join("a/b")).unwrap();
+    fs::write(tmp.path().join("old_report.md"), b"x").unwrap();
+    fs::write(tmp.path().join("report_v2.md"), b"x").unwrap();
+    fs::write(tmp.path().join("a/b/report_v1.md"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    assert_eq!(
+        ranked_names(&mut cache, "report", &RankingWeights::default()),
+        ["report_v2.md", "report_v1.md", "old_report.md"]
+    );
+}
+
+#[test]
+fn recency_and_weights_are_tunable() {
+    let tmp = TempDir::new("ranking_recency").unwrap();
+    fs::write(tmp.path().join("report.txt"), b"x").unwrap();
+    fs::write(tmp.path().join("report_fresh.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let now: Zoned = "2024-06-01T00:00:00+00:00[UTC]".parse().unwrap();
+    let now_secs = now.timestamp().as_second();
+    cache.set_date_reference(Some(now));
+    let old = index_of(&mut cache, "report.txt");
+    let fresh = index_of(&mut cache, "report_fresh.txt");
+    set_file_times(
+        &mut cache,
+        old,
+        now_secs - 365 * 86_400,
+        now_secs - 365 * 86_400,
+    );
+    set_file_times(&mut cache, fresh, now_secs - 3_600, now_secs - 3_600);
+
+    assert_eq!(
+        ranked_names(&mut cache, "report", &RankingWeights::default()),
+        ["report.txt", "report_fresh.txt"]
+    );
+    let recency_only = RankingWeights {
+        exact_name: 0.0,
+        prefix: 0.0,
+        depth_penalty: 0.0,
+        ..RankingWeights::default()
+    };
+    assert_eq!(
+        ranked_names(&mut cache, "report", &recency_only),
+        ["report_fresh.txt", "report.txt"]
+    );
+}
@@ -1,4 +1,7 @@
-use super::prelude::*;
+use super::{
+    prelude::*,
+    support::{assert_file_hits, set_file_size},
+};
 
 #[test]
 fn test_size_filters() {
@@ -23,6 +26,32 @@ fn test_size_filters() {
     assert!(ranged_path.ends_with(PathBuf::from("medium.bin")));
 }
 
+#[test]
+fn test_size_filters_against_stamped_metadata() {
+    let tmp = TempDir::new("query_size_stamped").unwrap();
+    for name in ["small.bin", "mid.bin", "big.bin"] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    for (name, size) in [
+        ("small.bin", 500),
+        ("mid.bin", 5 * 1024 * 1024),
+        ("big.bin", 2 * 1024 * 1024 * 1024),
+    ] {
+        let index = cache.search(name).unwrap()[0];
+        set_file_size(&mut cache, index, size);
+    }
+
+    let hits = cache.search("size:>1GB").unwrap();
+    assert_file_hits(&cache, &hits, &["big.bin"]);
+    let hits = cache.search("size:<10mb").unwrap();
+    assert_file_hits(&cache, &hits, &["small.bin", "mid.bin"]);
+    let hits = cache.search("size:1mb..10mb").unwrap();
+    assert_file_hits(&cache, &hits, &["mid.bin"]);
+    let hits = cache.search("size:..5mb").unwrap();
+    assert_file_hits(&cache, &hits, &["small.bin", "mid.bin"]);
+}
+
 #[test]
 fn test_size_filter_persists_metadata_on_nodes() {
     let tmp = TempDir::new("query_size_cache").unwrap();
@@ -907,6 +936,48 @@ fn test_size_range_inclusive_bounds() {
     assert_eq!(results.len(), 3, "Range should include both bounds");
 }
 
+#[test]
+fn test_size_range_exclusive_bounds() {
+    use cardinal_syntax::{ArgumentKind, Expr, Term, parse_query};
+
+    let tmp = TempDir::new("size_range_exclusive").unwrap();
+    fs::write(tmp.path().join("1kb.bin"), vec![0u8; 1024]).unwrap();
+    fs::write(tmp.path().join("2kb.bin"), vec![0u8; 2048]).unwrap();
+    fs::write(tmp.path().join("3kb.bin"), vec![0u8; 3072]).unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    let mut evaluate = |inclusive_start: bool, inclusive_end: bool| {
+        let mut expr = parse_query("size:1kb..3kb").unwrap().expr;
+        let Expr::Term(Term::Filter(filter)) = &mut expr else {
+            panic!("expected filter");
+        };
+        let ArgumentKind::Range(range) = &mut filter.argument.as_mut().unwrap().kind else {
+            panic!("expected range");
+        };
+        assert!(range.inclusive_start && range.inclusive_end);
+        range.inclusive_start = inclusive_start;
+        range.inclusive_end = inclusive_end;
+        let mut names: Vec<String> = cache
+            .evaluate_expr(
+                &expr,
+                &SearchOptions::default(),
+                Default::default(),
+                CancellationToken::noop(),
+            )
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|index| cache.file_nodes[index].name_and_parent.as_str().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(evaluate(true, true), ["1kb.bin", "2kb.bin", "3kb.bin"]);
+    assert_eq!(evaluate(false, true), ["2kb.bin", "3kb.bin"]);
+    assert_eq!(evaluate(true, false), ["1kb.bin", "2kb.bin"]);
+    assert_eq!(evaluate(false, false), ["2kb.bin"]);
+}
+
 #[test]
 fn test_size_with_multiple_and_conditions() {
     let tmp = TempDir::new("size_multi_and").unwrap();
@@ -1463,3 +1534,71 @@ fn test_size_decimal_rounding() {
     let results2 = cache.search("size:>=1.5kb").unwrap();
     assert_eq!(results2.len(), 1);
 }
+
+#[test]
+fn test_size_values_with_digit_group_separators() {
+    let tmp = TempDir::new("size_grouped").unwrap();
+    for name in ["small.bin", "exact.bin", "big.bin"] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    for (name, size) in [
+        ("small.bin", 500),
+        ("exact.bin", 1_000_000),
+        ("big.bin", 2_000 * 1024),
+    ] {
+        let index = cache.search(name).unwrap()[0];
+        set_file_size(&mut cache, index, size);
+    }
+
+    let hits = cache.search("size:1_000_000").unwrap();
+    assert_file_hits(&cache, &hits, &["exact.bin"]);
+    let hits = cache.search("size:1,000,000").unwrap();
+    assert_file_hits(&cache, &hits, &["exact.bin"]);
+    let hits = cache.search("size:>1,000").unwrap();
+    assert_file_hits(&cache, &hits, &["exact.bin", "big.bin"]);
+    let hits = cache.search("size:>1_000kb").unwrap();
+    assert_file_hits(&cache, &hits, &["big.bin"]);
+    let hits = cache.search("size:1,000..1_000_000").unwrap();
+    assert_file_hits(&cache, &hits, &["exact.bin"]);
+}
+
+#[test]
+fn directory_size_sums_files_recursively() {
+    use std::os::unix::fs::symlink;
+
+    let tmp = TempDir::new("directory_size").unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("photos/2024")).unwrap();
+    fs::create_dir(root.join("empty")).unwrap();
+    fs::write(root.join("top.bin"), vec![0u8; 5]).unwrap();
+    fs::write(root.join("photos/a.jpg"), vec![0u8; 100]).unwrap();
+    fs::write(root.join("photos/2024/b.jpg"), vec![0u8; 250]).unwrap();
+    fs::write(root.join("photos/2024/c.jpg"), vec![0u8; 1]).unwrap();
+    symlink(root.join("top.bin"), root.join("photos/link.bin")).unwrap();
+    let mut cache = SearchCache::walk_fs(root.to_path_buf());
+    let index_of = |cache: &SearchCache, path: &str| {
+        cache
+            .node_index_for_raw_path(&root.join(path))
+            .unwrap_or_else(|| panic!("{path} not indexed"))
+    };
+
+    let photos = index_of(&cache, "photos");
+    let year = index_of(&cache, "photos/2024");
+    let empty = index_of(&cache, "empty");
+    let file = index_of(&cache, "photos/a.jpg");
+    // The symlink counts as zero, not as the 5 bytes it points to.
+    assert_eq!(cache.directory_size(photos), 100 + 250 + 1);
+    assert_eq!(cache.directory_size(year), 250 + 1);
+    assert_eq!(cache.directory_size(empty), 0);
+    assert_eq!(cache.directory_size(file), 100);
+    let root_index = cache.file_nodes.root();
+    assert_eq!(cache.directory_size(root_index), 5 + 100 + 250 + 1);
+
+    // Entries whose metadata can't be read count as zero.
+    let c = index_of(&cache, "photos/2024/c.jpg");
+    fs::remove_file(root.join("photos/2024/c.jpg")).unwrap();
+    cache.file_nodes[c].metadata =
+        crate::SlabNodeMetadataCompact::none().with_file_type_hint(NodeFileType::File);
+    assert_eq!(cache.directory_size(year), 250);
+}
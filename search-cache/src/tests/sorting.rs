@@ -0,0 +1,293 @@
+use super::{prelude::*, support::set_file_metadata};
+use crate::{ResultOrder, SortKey};
+
+fn sorted_by(key: SortKey, ascending: bool) -> SearchOptions {
+    SearchOptions {
+        order: ResultOrder::Sorted { key, ascending },
+        ..Default::default()
+    }
+}
+
+fn sorted_names(cache: &mut SearchCache, sort: SortKey, ascending: bool) -> Vec<String> {
+    cache
+        .query_files_with_options(
+            "ext:txt".into(),
+            &sorted_by(sort, ascending),
+            CancellationToken::noop(),
+        )
+        .expect("query should succeed")
+        .expect("noop cancellation token should not cancel")
+        .into_iter()
+        .map(|node| {
+            node.path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+}
+
+fn sorting_fixture() -> (TempDir, SearchCache) {
+    let tmp = TempDir::new("query_sorting").unwrap();
+    fs::create_dir(tmp.path().join("nested")).unwrap();
+    for name in ["b.txt", "a.txt", "c.txt", "nested/a.txt"] {
+        fs::write(tmp.path().join(name), b"x").unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    // (size, created, modified); duplicates on every key exercise the path tie-break.
+    for (name, size, created, modified) in [
+        ("b.txt", 300, 1_000, 4_000),
+        ("a.txt", 100, 2_000, 4_000),
+        ("c.txt", 100, 3_000, 1_000),
+        ("nested/a.txt", 200, 1_000, 2_000),
+    ] {
+        let index = cache
+            .node_index_for_raw_path(&tmp.path().join(name))
+            .unwrap();
+        set_file_metadata(&mut cache, index, size, created, modified);
+    }
+    (tmp, cache)
+}
+
+#[test]
+fn sort_by_name_falls_back_to_path() {
+    let (_tmp, mut cache) = sorting_fixture();
+    assert_eq!(
+        sorted_names(&mut cache, SortKey::Name, true),
+        ["a.txt", "a.txt", "b.txt", "c.txt"]
+    );
+    assert_eq!(
+        sorted_names(&mut cache, SortKey::Name, false),
+        ["c.txt", "b.txt", "a.txt", "a.txt"]
+    );
+
+    let paths: Vec<PathBuf> = cache
+        .query_files_with_options(
+            "a.txt".into(),
+            &sorted_by(SortKey::Name, false),
+            CancellationToken::noop(),
+        )
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    let mut expected = paths.clone();
+    expected.sort();
+    assert_eq!(paths, expected, "equal names stay in path order");
+}
+
+#[test]
+fn sort_by_size() {
+    let (_tmp, mut cache) = sorting_fixture();
+    // a.txt and c.txt share 100 bytes, root a.txt sorts before c.txt by path.
+    assert_eq!(
+        sorted_names(&mut cache, SortKey::Size, true),
+        ["a.txt", "c.txt", "a.txt", "b.txt"]
+    );
+    assert_eq!(
+        sorted_names(&mut cache, SortKey::Size, false),
+        ["b.txt", "a.txt", "a.txt", "c.txt"]
+    );
+}
+
+#[test]
+fn sort_by_date_modified() {
+    let (_tmp, mut cache) = sorting_fixture();
+    assert_eq!(
+        sorted_names(&mut cache, SortKey::DateModified, true),
+        ["c.txt", "a.txt", "a.txt", "b.txt"]
+    );
+    assert_eq!(
+        sorted_names(&mut cache, SortKey::DateModified, false),
+        ["a.txt", "b.txt", "a.txt", "c.txt"]
+    );
+}
+
+#[test]
+fn sort_by_date_created() {
+    let (_tmp, mut cache) = sorting_fixture();
+    assert_eq!(
+        sorted_names(&mut cache, SortKey::DateCreated, true),
+        ["b.txt", "a.txt", "a.txt", "c.txt"]
+    );
+    assert_eq!(
+        sorted_names(&mut cache, SortKey::DateCreated, false),
+        ["c.txt", "a.txt", "b.txt", "a.txt"]
+    );
+}
+
+#[test]
+fn sorting_combines_with_within_and_exclusions() {
+    let (tmp, mut cache) = sorting_fixture();
+    let within = cache.search("a.txt | b.txt").unwrap();
+    let options = SearchOptions {
+        within: Some(within),
+        excluded_prefixes: vec![tmp.path().join("nested")],
+        ..sorted_by(SortKey::Size, false)
+    };
+    let paths: Vec<PathBuf> = cache
+        .query_files_with_options("ext:txt".into(), &options, CancellationToken::noop())
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    assert_eq!(paths, [tmp.path().join("b.txt"), tmp.path().join("a.txt")]);
+}
+
+#[test]
+fn query_page_walks_the_sorted_results_without_gaps() {
+    let tmp = TempDir::new("query_page").unwrap();
+    for dir in ["left", "right"] {
+        fs::create_dir(tmp.path().join(dir)).unwrap();
+        for file in 0..12 {
+            fs::write(tmp.path().join(format!("{dir}/file_{file}.txt")), b"x").unwrap();
+        }
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    // Few distinct sizes and times, so most pages end inside a run of equal keys.
+    for (i, index) in cache.search("ext:txt").unwrap().into_iter().enumerate() {
+        let i = i as i64;
+        set_file_metadata(&mut cache, index, (i % 3) as u64, i % 2, i % 4);
+    }
+
+    for sort in [
+        SortKey::Name,
+        SortKey::Size,
+        SortKey::DateModified,
+        SortKey::DateCreated,
+    ] {
+        for ascending in [true, false] {
+            let expected: Vec<PathBuf> = cache
+                .query_files_with_options(
+                    "ext:txt".into(),
+                    &sorted_by(sort, ascending),
+                    CancellationToken::noop(),
+                )
+                .unwrap()
+                .unwrap()
+                .into_iter()
+                .map(|node| node.path)
+                .collect();
+            assert_eq!(expected.len(), 24);
+            for limit in [1, 5, 24, 100] {
+                let mut paged = Vec::new();
+                let mut cursor = None;
+                loop {
+                    let (page, next) = cache
+                        .query_page(
+                            "ext:txt".into(),
+                            &sorted_by(sort, ascending),
+                            cursor.as_ref(),
+                            limit,
+                            CancellationToken::noop(),
+                        )
+                        .unwrap()
+                        .unwrap();
+                    assert!(page.len() <= limit);
+                    paged.extend(page.into_iter().map(|node| node.path));
+                    match next {
+                        Some(next) => cursor = Some(next),
+                        None => break,
+                    }
+                }
+                assert_eq!(
+                    paged, expected,
+                    "{sort:?} ascending={ascending} limit={limit}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn query_page_rejects_mismatched_cursor() {
+    let (_tmp, mut cache) = sorting_fixture();
+    let (page, cursor) = cache
+        .query_page(
+            "ext:txt".into(),
+            &sorted_by(SortKey::Size, true),
+            None,
+            2,
+            CancellationToken::noop(),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.len(), 2);
+    let cursor = cursor.expect("two more results remain");
+    assert!(
+        cache
+            .query_page(
+                "ext:txt".into(),
+                &sorted_by(SortKey::Size, false),
+                Some(&cursor),
+                2,
+                CancellationToken::noop(),
+            )
+            .is_err()
+    );
+    assert!(
+        cache
+            .query_page(
+                "ext:txt".into(),
+                &SearchOptions::default(),
+                None,
+                2,
+                CancellationToken::noop()
+            )
+            .is_err()
+    );
+    assert!(
+        cache
+            .query_page(
+                "ext:txt".into(),
+                &sorted_by(SortKey::Size, true),
+                None,
+                0,
+                CancellationToken::noop()
+            )
+            .is_err()
+    );
+}
+
+#[test]
+fn query_page_sees_index_changes_between_pages() {
+    let (tmp, mut cache) = sorting_fixture();
+    let page = |cache: &mut SearchCache, cursor: Option<&crate::PageCursor>| {
+        cache
+            .query_page(
+                "ext:txt".into(),
+                &sorted_by(SortKey::Name, true),
+                cursor,
+                2,
+                CancellationToken::noop(),
+            )
+            .unwrap()
+            .unwrap()
+    };
+    let (first, cursor) = page(&mut cache, None);
+    let names: Vec<_> = first.iter().map(|node| node.path.clone()).collect();
+    assert_eq!(
+        names,
+        [tmp.path().join("a.txt"), tmp.path().join("nested/a.txt")]
+    );
+
+    fs::write(tmp.path().join("bb.txt"), b"x").unwrap();
+    let id = cache.last_event_id() + 1;
+    cache
+        .handle_fs_events(vec![cardinal_sdk::FsEvent {
+            path: tmp.path().join("bb.txt"),
+            id,
+            flag: cardinal_sdk::EventFlag::ItemCreated | cardinal_sdk::EventFlag::ItemIsFile,
+        }])
+        .unwrap();
+
+    let (second, cursor) = page(&mut cache, cursor.as_ref());
+    let names: Vec<_> = second.iter().map(|node| node.path.clone()).collect();
+    assert_eq!(names, [tmp.path().join("b.txt"), tmp.path().join("bb.txt")]);
+    let (third, cursor) = page(&mut cache, cursor.as_ref());
+    assert_eq!(third.len(), 1);
+    assert!(cursor.is_none());
+}
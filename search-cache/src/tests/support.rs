@@ -10,12 +10,29 @@ pub(super) fn set_file_times(
     index: SlabIndex,
     created: i64,
     modified: i64,
+) {
+    set_file_metadata(cache, index, 0, created, modified);
+}
+
+pub(super) fn set_file_size(cache: &mut SearchCache, index: SlabIndex, size: u64) {
+    set_file_metadata(cache, index, size, 0, 0);
+}
+
+pub(super) fn set_file_metadata(
+    cache: &mut SearchCache,
+    index: SlabIndex,
+    size: u64,
+    created: i64,
+    modified: i64,
 ) {
     let metadata = NodeMetadata {
         r#type: NodeFileType::File,
-        size: 0,
+        size,
         ctime: NonZeroU64::new(created as u64),
         mtime: NonZeroU64::new(modified as u64),
+        uid: 0,
+        gid: 0,
+        mode: 0,
     };
     cache.file_nodes[index].metadata = SlabNodeMetadataCompact::some(metadata);
 }
@@ -1,4 +1,8 @@
-use super::prelude::*;
+use super::{
+    prelude::*,
+    support::{assert_file_hits, node_name},
+};
+use crate::TypeTable;
 
 #[test]
 fn test_type_and_macro_filters() {
@@ -24,6 +28,31 @@ fn test_type_and_macro_filters() {
     assert!(doc_path.ends_with(PathBuf::from("notes.txt")));
 }
 
+#[test]
+fn test_type_filter_list_unions_categories() {
+    let tmp = TempDir::new("query_type_list").unwrap();
+    fs::write(tmp.path().join("photo.png"), b"x").unwrap();
+    fs::write(tmp.path().join("clip.mp4"), b"x").unwrap();
+    fs::write(tmp.path().join("notes.txt"), b"x").unwrap();
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    for query in [
+        "type:picture,video",
+        "type:picture;video",
+        "type:video,picture",
+    ] {
+        let results = cache.search(query).unwrap();
+        assert_file_hits(&cache, &results, &["clip.mp4", "photo.png"]);
+    }
+    let scoped = cache.search("photo type:picture,video").unwrap();
+    assert_file_hits(&cache, &scoped, &["photo.png"]);
+    let err = cache.search("type:picture,bogus").unwrap_err();
+    assert!(
+        err.to_string().contains("Unknown type category: bogus"),
+        "{err}"
+    );
+}
+
 #[test]
 fn test_audio_macro_with_argument_behaves_like_and() {
     let tmp = TempDir::new("query_audio_argument").unwrap();
@@ -457,6 +486,35 @@ fn test_type_executable_comprehensive() {
     assert_eq!(apps.len(), 14);
 }
 
+#[test]
+fn test_exe_matches_extensionless_files_with_execute_bits() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = TempDir::new("exe_mode_bits").unwrap();
+    for (name, mode) in [("rg", 0o755), ("deploy", 0o700), ("README", 0o644)] {
+        let path = tmp.path().join(name);
+        fs::write(&path, b"x").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+    fs::write(tmp.path().join("tool.exe"), b"x").unwrap();
+    // An execute bit alone doesn't make a file with a non-exe extension an executable.
+    let script = tmp.path().join("notes.txt");
+    fs::write(&script, b"x").unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    let expected = ["deploy", "rg", "tool.exe"];
+    let by_macro = cache.search("exe:").unwrap();
+    assert_file_hits(&cache, &by_macro, &expected);
+    let by_type = cache.search("type:exe").unwrap();
+    assert_file_hits(&cache, &by_type, &expected);
+
+    // Recorded metadata is consulted once it's loaded.
+    let sized = cache.search("size:>0 exe:").unwrap();
+    assert_file_hits(&cache, &sized, &expected);
+}
+
 #[test]
 fn test_type_file_folder_filters() {
     let tmp = TempDir::new("type_file_folder").unwrap();
@@ -1543,3 +1601,84 @@ fn test_type_spreadsheet_all_variants() {
     let results = cache.search("type:spreadsheet").unwrap();
     assert_eq!(results.len(), 5);
 }
+
+#[test]
+fn test_exe_matches_app_bundles_but_not_other_folders() {
+    let tmp = TempDir::new("type_exe_bundle").unwrap();
+    fs::create_dir_all(tmp.path().join("Calculator.app/Contents")).unwrap();
+    fs::create_dir(tmp.path().join("archive.zip")).unwrap();
+    fs::write(tmp.path().join("tool.pkg"), b"x").unwrap();
+    fs::write(tmp.path().join("notes.txt"), b"x").unwrap();
+
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+
+    let mut names: Vec<String> = cache
+        .search("exe:")
+        .unwrap()
+        .into_iter()
+        .map(|index| node_name(&cache, index))
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["Calculator.app", "tool.pkg"]);
+
+    let archives = cache.search("type:archive").unwrap();
+    assert!(archives.is_empty(), "plain folders never match extensions");
+}
+
+#[test]
+fn test_type_table_can_be_overridden() {
+    let tmp = TempDir::new("type_table_override").unwrap();
+    fs::write(tmp.path().join("photo.jpg"), b"x").unwrap();
+    fs::write(tmp.path().join("scan.png"), b"x").unwrap();
+    fs::write(tmp.path().join("novel.epub"), b"x").unwrap();
+    fs::write(tmp.path().join("notes.txt"), b"x").unwrap();
+
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let pictures = cache.search("type:picture").unwrap();
+    assert_file_hits(&cache, &pictures, &["photo.jpg", "scan.png"]);
+
+    let mut table = TypeTable::default();
+    table.set_category("images", [".PNG"]);
+    table.set_category("ebook", ["epub", "mobi"]);
+    cache.set_type_table(table);
+
+    let pictures = cache.search("type:picture").unwrap();
+    assert_file_hits(&cache, &pictures, &["scan.png"]);
+    let ebooks = cache.search("type:ebook").unwrap();
+    assert_file_hits(&cache, &ebooks, &["novel.epub"]);
+    let docs = cache.search("doc:").unwrap();
+    assert_file_hits(&cache, &docs, &["notes.txt"]);
+}
+
+#[test]
+fn test_type_table_can_be_extended() {
+    let tmp = TempDir::new("type_table_extend").unwrap();
+    fs::write(tmp.path().join("photo.jpg"), b"x").unwrap();
+    fs::write(tmp.path().join("modern.avif"), b"x").unwrap();
+    fs::write(tmp.path().join("render.xyz"), b"x").unwrap();
+
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    let pictures = cache.search("type:picture").unwrap();
+    assert_file_hits(&cache, &pictures, &["modern.avif", "photo.jpg"]);
+
+    let mut table = TypeTable::default();
+    table.extend_category("pictures", [".XYZ", "jpg"]);
+    cache.set_type_table(table);
+
+    let pictures = cache.search("type:picture").unwrap();
+    assert_file_hits(
+        &cache,
+        &pictures,
+        &["modern.avif", "photo.jpg", "render.xyz"],
+    );
+    assert_eq!(
+        cache
+            .type_table
+            .extensions("picture")
+            .unwrap()
+            .iter()
+            .filter(|ext| *ext == "jpg")
+            .count(),
+        1
+    );
+}
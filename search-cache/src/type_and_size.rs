@@ -36,6 +36,10 @@ impl StateTypeSize {
     pub fn size(&self) -> u64 {
         self.0 & ((1u64 << 60) - 1)
     }
+
+    pub fn with_type(self, r#type: NodeFileType) -> Self {
+        Self::new(self.state(), r#type, self.size())
+    }
 }
 
 #[derive(Debug, Clone, Copy, enumn::N, PartialEq, Eq)]
@@ -0,0 +1,172 @@
+use fswalk::NodeFileType;
+use hashbrown::HashMap;
+
+/// Extension lists backing `type:` categories and the `audio:`/`video:`/`doc:`/`exe:` macros.
+///
+/// Starts out with the built-in categories. Callers can replace the extensions of a category
+/// (aliases such as `image` resolve to the same category as `picture`) or register new ones.
+#[derive(Debug, Clone)]
+pub struct TypeTable {
+    categories: HashMap<String, Vec<String>>,
+}
+
+pub(crate) enum TypeFilterTarget<'a> {
+    NodeType(NodeFileType),
+    Extensions(&'a [String]),
+    /// The `exe` category: its extensions, plus extensionless files with an execute bit set.
+    Executables(&'a [String]),
+}
+
+impl Default for TypeTable {
+    fn default() -> Self {
+        let categories = BUILTIN_CATEGORIES
+            .iter()
+            .map(|(name, extensions)| {
+                (
+                    name.to_string(),
+                    extensions.iter().map(|ext| ext.to_string()).collect(),
+                )
+            })
+            .collect();
+        Self { categories }
+    }
+}
+
+impl TypeTable {
+    /// Replaces the extensions matched by `name`, or adds `name` as a new category.
+    /// Extensions are matched case-insensitively and may be given with or without a leading dot.
+    pub fn set_category<I, S>(&mut self, name: &str, extensions: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let extensions = normalize_extensions(extensions).collect();
+        self.categories.insert(category_key(name), extensions);
+    }
+
+    /// Adds extensions to the category `name`, keeping the ones it already matches.
+    pub fn extend_category<I, S>(&mut self, name: &str, extensions: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let current = self.categories.entry(category_key(name)).or_default();
+        for ext in normalize_extensions(extensions) {
+            if !current.contains(&ext) {
+                current.push(ext);
+            }
+        }
+    }
+
+    /// Extensions currently matched by the category `name`, if it exists.
+    pub fn extensions(&self, name: &str) -> Option<&[String]> {
+        let name = name.trim().to_ascii_lowercase();
+        let key = canonical_category(&name).unwrap_or(&name);
+        self.categories.get(key).map(Vec::as_slice)
+    }
+
+    pub(crate) fn lookup(&self, name: &str) -> Option<TypeFilterTarget<'_>> {
+        match name {
+            "file" | "files" => Some(TypeFilterTarget::NodeType(NodeFileType::File)),
+            "folder" | "folders" | "dir" | "directory" => {
+                Some(TypeFilterTarget::NodeType(NodeFileType::Dir))
+            }
+            _ => {
+                let extensions = self.extensions(name)?;
+                Some(if category_key(name) == "exe" {
+                    TypeFilterTarget::Executables(extensions)
+                } else {
+                    TypeFilterTarget::Extensions(extensions)
+                })
+            }
+        }
+    }
+}
+
+/// Directory extensions that are macOS bundles, i.e. show up as a single item in Finder.
+/// They're matched by extension like regular files.
+pub(crate) const BUNDLE_EXTENSIONS: &[&str] = &["app"];
+
+fn category_key(name: &str) -> String {
+    let name = name.trim().to_ascii_lowercase();
+    canonical_category(&name).map_or(name, str::to_string)
+}
+
+fn normalize_extensions<I, S>(extensions: I) -> impl Iterator<Item = String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    extensions
+        .into_iter()
+        .map(|ext| {
+            ext.as_ref()
+                .trim()
+                .trim_start_matches('.')
+                .to_ascii_lowercase()
+        })
+        .filter(|ext| !ext.is_empty())
+}
+
+fn canonical_category(name: &str) -> Option<&'static str> {
+    let canonical = match name {
+        "picture" | "pictures" | "image" | "images" | "photo" | "photos" => "picture",
+        "video" | "videos" | "movie" | "movies" => "video",
+        "audio" | "audios" | "music" | "song" | "songs" => "audio",
+        "doc" | "docs" | "document" | "documents" | "text" | "office" => "doc",
+        "presentation" | "presentations" | "ppt" | "slides" => "presentation",
+        "spreadsheet" | "spreadsheets" | "xls" | "excel" | "sheet" | "sheets" => "spreadsheet",
+        "pdf" => "pdf",
+        "archive" | "archives" | "compressed" | "zip" => "archive",
+        "code" | "source" | "dev" => "code",
+        "exe" | "exec" | "executable" | "executables" | "program" | "programs" | "app" | "apps" => {
+            "exe"
+        }
+        _ => return None,
+    };
+    Some(canonical)
+}
+
+const BUILTIN_CATEGORIES: &[(&str, &[&str])] = &[
+    ("picture", PICTURE_EXTENSIONS),
+    ("video", VIDEO_EXTENSIONS),
+    ("audio", AUDIO_EXTENSIONS),
+    ("doc", DOCUMENT_EXTENSIONS),
+    ("presentation", PRESENTATION_EXTENSIONS),
+    ("spreadsheet", SPREADSHEET_EXTENSIONS),
+    ("pdf", PDF_EXTENSIONS),
+    ("archive", ARCHIVE_EXTENSIONS),
+    ("code", CODE_EXTENSIONS),
+    ("exe", EXECUTABLE_EXTENSIONS),
+];
+
+const PICTURE_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "bmp", "tif", "tiff", "webp", "avif", "jxl", "ico", "svg", "heic",
+    "heif", "raw", "arw", "cr2", "orf", "raf", "psd", "ai",
+];
+const VIDEO_EXTENSIONS: &[&str] = &[
+    "mp4", "m4v", "mov", "avi", "mkv", "wmv", "webm", "flv", "mpg", "mpeg", "3gp", "3g2", "ts",
+    "mts", "m2ts",
+];
+const AUDIO_EXTENSIONS: &[&str] = &[
+    "mp3", "wav", "flac", "aac", "ogg", "oga", "opus", "wma", "m4a", "alac", "aiff",
+];
+const DOCUMENT_EXTENSIONS: &[&str] = &[
+    "txt", "md", "rst", "doc", "docx", "rtf", "odt", "pdf", "pages", "rtfd",
+];
+const PRESENTATION_EXTENSIONS: &[&str] = &["ppt", "pptx", "key", "odp"];
+const SPREADSHEET_EXTENSIONS: &[&str] = &["xls", "xlsx", "csv", "numbers", "ods"];
+const PDF_EXTENSIONS: &[&str] = &["pdf"];
+const ARCHIVE_EXTENSIONS: &[&str] = &[
+    "zip", "rar", "7z", "tar", "gz", "tgz", "bz2", "xz", "zst", "cab", "iso", "dmg",
+];
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "c", "cc", "cpp", "cxx", "h", "hpp", "hh", "java", "cs", "py",
+    "go", "rb", "swift", "kt", "kts", "php", "html", "css", "scss", "sass", "less", "json", "yaml",
+    "yml", "toml", "ini", "cfg", "sh", "zsh", "fish", "ps1", "psm1", "sql", "lua", "pl", "pm", "r",
+    "m", "mm", "dart", "scala", "ex", "exs",
+];
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "exe", "msi", "bat", "cmd", "com", "ps1", "psm1", "app", "apk", "ipa", "jar", "bin", "run",
+    "pkg",
+];
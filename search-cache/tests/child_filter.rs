@@ -0,0 +1,87 @@
+//! `child:` keeps folders that directly contain a matching item.
+
+mod common;
+
+use common::{paths, relative_paths};
+use search_cache::SearchCache;
+use search_cancel::CancellationToken;
+use std::path::PathBuf;
+use tempdir::TempDir;
+
+// root/
+//   Music/
+//     Rock/       song.mp3, cover.jpg
+//     Jazz/       take.flac
+//     Mixed/      intro.MP3, deep/inner.mp3
+//     Empty/
+fn build_cache() -> (TempDir, SearchCache) {
+    common::build_cache(
+        "child_filter",
+        &[
+            "Music/Rock/song.mp3",
+            "Music/Rock/cover.jpg",
+            "Music/Jazz/take.flac",
+            "Music/Mixed/intro.MP3",
+            "Music/Mixed/deep/inner.mp3",
+            "Music/Empty/",
+        ],
+    )
+}
+
+#[test]
+fn folders_with_matching_direct_child_are_included() {
+    let (tmp, mut cache) = build_cache();
+    assert_eq!(
+        relative_paths(&mut cache, tmp.path(), "child:*.mp3"),
+        paths(&["Music/Mixed/deep", "Music/Rock"])
+    );
+    assert_eq!(
+        relative_paths(&mut cache, tmp.path(), "child:*.mp3;*.flac"),
+        paths(&["Music/Jazz", "Music/Mixed/deep", "Music/Rock"])
+    );
+    assert_eq!(
+        relative_paths(&mut cache, tmp.path(), "child:cover"),
+        paths(&["Music/Rock"])
+    );
+}
+
+#[test]
+fn negated_child_excludes_music_folders() {
+    let (tmp, mut cache) = build_cache();
+    let music = tmp.path().join("Music");
+    assert_eq!(
+        relative_paths(
+            &mut cache,
+            tmp.path(),
+            &format!("parent:{} !child:*.mp3", music.display())
+        ),
+        paths(&["Music/Empty", "Music/Jazz", "Music/Mixed"])
+    );
+}
+
+#[test]
+fn child_pattern_follows_case_sensitivity() {
+    let (tmp, mut cache) = build_cache();
+    let insensitive = cache
+        .search_with_options(
+            "child:*.mp3",
+            &search_cache::SearchOptions {
+                case_insensitive: true,
+                ..Default::default()
+            },
+            CancellationToken::noop(),
+        )
+        .unwrap()
+        .nodes
+        .unwrap();
+    let mut found: Vec<PathBuf> = insensitive
+        .into_iter()
+        .filter_map(|index| cache.node_path(index))
+        .map(|path| path.strip_prefix(tmp.path()).unwrap().to_path_buf())
+        .collect();
+    found.sort();
+    assert_eq!(
+        found,
+        paths(&["Music/Mixed", "Music/Mixed/deep", "Music/Rock"])
+    );
+}
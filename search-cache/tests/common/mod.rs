@@ -0,0 +1,41 @@
+//! Fixtures and helpers shared by the filter tests.
+#![allow(dead_code)]
+
+use search_cache::SearchCache;
+use search_cancel::CancellationToken;
+use std::path::{Path, PathBuf};
+use tempdir::TempDir;
+
+/// Walks a fresh temporary folder holding `entries`, relative paths of empty files, or of
+/// folders when they end in `/`. Missing parent folders are created.
+pub fn build_cache(name: &str, entries: &[&str]) -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new(name).unwrap();
+    for entry in entries {
+        let full = temp_dir.path().join(entry);
+        if entry.ends_with('/') {
+            std::fs::create_dir_all(full).unwrap();
+        } else {
+            std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+            std::fs::File::create(full).unwrap();
+        }
+    }
+    let cache = SearchCache::walk_fs(temp_dir.path().to_path_buf());
+    (temp_dir, cache)
+}
+
+/// Everything `query` matches, relative to `root` and sorted.
+pub fn relative_paths(cache: &mut SearchCache, root: &Path, query: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = cache
+        .query_files(query.to_string(), CancellationToken::noop())
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|node| node.path.strip_prefix(root).unwrap().to_path_buf())
+        .collect();
+    paths.sort();
+    paths
+}
+
+pub fn paths(values: &[&str]) -> Vec<PathBuf> {
+    values.iter().map(PathBuf::from).collect()
+}
@@ -1,4 +1,6 @@
-use search_cache::{SearchCache, SearchOptions, SlabIndex};
+use search_cache::{
+    BINARY_SNIFF_BYTES, ContentSearchLimits, SearchCache, SearchOptions, SlabIndex,
+};
 use search_cancel::CancellationToken;
 use std::fs;
 use tempdir::TempDir;
@@ -23,8 +25,9 @@ fn content_filter_rejects_empty_needle() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let result = cache.search_with_options(
         r#"content:"""#,
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     );
@@ -50,8 +53,9 @@ fn content_filter_single_byte_exact_match() {
     // Case sensitive: only lowercase 'a' should match
     let indices = guard_indices(cache.search_with_options(
         "content:a",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -62,8 +66,9 @@ fn content_filter_single_byte_exact_match() {
     // Case sensitive: only uppercase 'A' should match
     let indices = guard_indices(cache.search_with_options(
         "content:A",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -86,8 +91,9 @@ fn content_filter_single_byte_case_insensitive() {
     // Case insensitive: both 'a' and 'A' should match
     let indices = guard_indices(cache.search_with_options(
         "content:a",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -98,8 +104,9 @@ fn content_filter_single_byte_case_insensitive() {
 
     let indices = guard_indices(cache.search_with_options(
         "content:A",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: true,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -121,8 +128,9 @@ fn content_filter_needle_spans_exact_buffer_boundary() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:BOUNDARY",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -147,8 +155,9 @@ fn content_filter_needle_spans_three_chunks() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:LONGNEEDLE",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -173,8 +182,9 @@ fn content_filter_needle_exceeds_buffer_size() {
     let query = format!("content:{needle}");
     let indices = guard_indices(cache.search_with_options(
         &query,
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -196,8 +206,9 @@ fn content_filter_needle_equals_overlap_size() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:AB",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -215,8 +226,9 @@ fn content_filter_file_smaller_than_buffer() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:content",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -234,8 +246,9 @@ fn content_filter_empty_file_returns_no_match() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:anything",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -253,8 +266,9 @@ fn content_filter_needle_at_file_start() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:START",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -272,8 +286,9 @@ fn content_filter_needle_at_file_end() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:END",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -291,15 +306,16 @@ fn content_filter_multiple_occurrences_in_file() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:foo",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
     assert_eq!(indices.len(), 1); // Still only one file matches
 }
 
-/// Test binary content with null bytes
+/// Test binary content with null bytes: skipped by default, searchable on request
 #[test]
 fn content_filter_binary_with_null_bytes() {
     let temp_dir = TempDir::new("content_binary").unwrap();
@@ -309,16 +325,107 @@ fn content_filter_binary_with_null_bytes() {
     binary.extend_from_slice(b"TARGET");
     binary.extend_from_slice(&[0u8, 255u8, 128u8]);
     fs::write(dir.join("binary.bin"), &binary).unwrap();
+    fs::write(dir.join("text.txt"), b"plain TARGET text").unwrap();
 
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:TARGET",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
     assert_eq!(indices.len(), 1);
+    let nodes = cache.expand_file_nodes(&indices);
+    assert!(nodes[0].path.ends_with("text.txt"));
+
+    cache.set_content_limits(ContentSearchLimits {
+        skip_binary: false,
+        ..ContentSearchLimits::default()
+    });
+    let indices = guard_indices(cache.search_with_options(
+        "content:TARGET",
+        &SearchOptions {
+            case_insensitive: false,
+            ..Default::default()
+        },
+        CancellationToken::noop(),
+    ));
+    assert_eq!(indices.len(), 2);
+}
+
+/// Test binary detection only looks at the beginning of the file
+#[test]
+fn content_filter_binary_detection_limited_to_file_head() {
+    let temp_dir = TempDir::new("content_binary_tail").unwrap();
+    let dir = temp_dir.path();
+
+    let mut late_nul = vec![b'a'; BINARY_SNIFF_BYTES as usize];
+    late_nul.extend_from_slice(b"\0TARGET");
+    fs::write(dir.join("late_nul.txt"), &late_nul).unwrap();
+
+    let mut cache = SearchCache::walk_fs(dir.to_path_buf());
+    let indices = guard_indices(cache.search_with_options(
+        "content:TARGET",
+        &SearchOptions {
+            case_insensitive: false,
+            ..Default::default()
+        },
+        CancellationToken::noop(),
+    ));
+    assert_eq!(indices.len(), 1);
+}
+
+/// Test files above the size cap are never read
+#[test]
+fn content_filter_respects_size_cap() {
+    let temp_dir = TempDir::new("content_size_cap").unwrap();
+    let dir = temp_dir.path();
+
+    fs::write(dir.join("small.txt"), b"needle").unwrap();
+    let mut large = vec![b'x'; 1024];
+    large.extend_from_slice(b"needle");
+    fs::write(dir.join("large.txt"), &large).unwrap();
+    fs::write(dir.join("other.txt"), b"haystack").unwrap();
+
+    let mut cache = SearchCache::walk_fs(dir.to_path_buf());
+    let search = |cache: &mut SearchCache| {
+        let indices = guard_indices(cache.search_with_options(
+            "content:needle",
+            &SearchOptions {
+                case_insensitive: false,
+                ..Default::default()
+            },
+            CancellationToken::noop(),
+        ));
+        let mut names: Vec<String> = cache
+            .expand_file_nodes(&indices)
+            .into_iter()
+            .map(|node| {
+                node.path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(search(&mut cache), ["large.txt", "small.txt"]);
+
+    cache.set_content_limits(ContentSearchLimits {
+        max_file_bytes: Some(1024),
+        ..ContentSearchLimits::default()
+    });
+    assert_eq!(search(&mut cache), ["small.txt"]);
+
+    cache.set_content_limits(ContentSearchLimits {
+        max_file_bytes: None,
+        ..ContentSearchLimits::default()
+    });
+    assert_eq!(search(&mut cache), ["large.txt", "small.txt"]);
 }
 
 /// Test UTF-8 multi-byte characters
@@ -334,8 +441,9 @@ fn content_filter_utf8_multibyte_characters() {
     // Search for Chinese characters
     let indices = guard_indices(cache.search_with_options(
         "content:世界",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -344,8 +452,9 @@ fn content_filter_utf8_multibyte_characters() {
     // Search for emoji
     let indices = guard_indices(cache.search_with_options(
         "content:🦀",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -367,8 +476,9 @@ fn content_filter_utf8_split_across_boundary() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:世界",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -392,8 +502,9 @@ fn content_filter_special_characters() {
     // Special symbols (use quotes to preserve the content)
     let indices = guard_indices(cache.search_with_options(
         r#"content:"!@#$%""#,
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -402,8 +513,9 @@ fn content_filter_special_characters() {
     // Test parentheses
     let indices = guard_indices(cache.search_with_options(
         r#"content:"&*()""#,
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -422,8 +534,9 @@ fn content_filter_ignores_directories() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:content",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -449,8 +562,9 @@ fn content_filter_combined_with_extension() {
     // Search for .txt files containing "Bearer"
     let indices = guard_indices(cache.search_with_options(
         "*.txt content:Bearer",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -461,8 +575,9 @@ fn content_filter_combined_with_extension() {
     // Search for .md files containing "Bearer"
     let indices = guard_indices(cache.search_with_options(
         "*.md content:Bearer",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -488,8 +603,9 @@ fn content_filter_combined_with_infolder() {
     let query = format!("infolder:{target_path} content:\"secret key\"");
     let indices = guard_indices(cache.search_with_options(
         &query,
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -512,8 +628,9 @@ fn content_filter_combined_with_size() {
     // Search for files > 1KB containing 't'
     let indices = guard_indices(cache.search_with_options(
         "size:>1kb content:t",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -536,8 +653,9 @@ fn content_filter_with_not_operator() {
     // Search for .txt files that don't contain "secret"
     let indices = guard_indices(cache.search_with_options(
         "*.txt !content:secret",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -561,8 +679,9 @@ fn content_filter_with_or_operator() {
     // Search for files containing either TODO or FIXME
     let indices = guard_indices(cache.search_with_options(
         "content:TODO | content:FIXME",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -592,8 +711,9 @@ fn content_filter_respects_cancellation() {
 
     let result = cache.search_with_options(
         "content:needle",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         token,
     );
@@ -626,8 +746,9 @@ fn content_filter_handles_unreadable_file() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:secret",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -656,8 +777,9 @@ fn content_filter_regex_special_chars_treated_literally() {
     // These should be treated as literal strings, not regex
     let indices = guard_indices(cache.search_with_options(
         "content:.*",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -665,8 +787,9 @@ fn content_filter_regex_special_chars_treated_literally() {
 
     let indices = guard_indices(cache.search_with_options(
         "content:[test]+",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -674,8 +797,9 @@ fn content_filter_regex_special_chars_treated_literally() {
 
     let indices = guard_indices(cache.search_with_options(
         "content:(group)?",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -697,8 +821,9 @@ fn content_filter_handles_long_lines() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:NEEDLE",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -718,8 +843,9 @@ fn content_filter_whitespace_in_needle() {
     // Search with space (use quotes to preserve the space)
     let indices = guard_indices(cache.search_with_options(
         r#"content:"word three""#,
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -728,8 +854,9 @@ fn content_filter_whitespace_in_needle() {
     // Search with multiple spaces
     let indices = guard_indices(cache.search_with_options(
         r#"content:"three   spaced""#,
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -750,8 +877,9 @@ fn content_filter_case_insensitive_mixed_case() {
     for needle in ["thisismixedcase", "THISISMIXEDCASE", "ThIsIsMiXeDCaSe"] {
         let indices = guard_indices(cache.search_with_options(
             &format!(r#"content:"{needle}""#),
-            SearchOptions {
+            &SearchOptions {
                 case_insensitive: true,
+                ..Default::default()
             },
             CancellationToken::noop(),
         ));
@@ -773,8 +901,9 @@ fn content_filter_file_exact_buffer_size() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:TARGET",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -793,8 +922,9 @@ fn content_filter_no_false_positive_at_eof() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:TARGET",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -812,8 +942,9 @@ fn content_filter_repeated_pattern_in_needle() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:aaaaaa",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -821,8 +952,9 @@ fn content_filter_repeated_pattern_in_needle() {
 
     let indices = guard_indices(cache.search_with_options(
         "content:BBBBBB",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -844,8 +976,9 @@ fn content_filter_all_ascii_printable() {
     // Test finding a substring
     let indices = guard_indices(cache.search_with_options(
         "content:@ABCDEF",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -864,8 +997,9 @@ fn content_filter_high_bytes() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "content:AB",
-        SearchOptions {
+        &SearchOptions {
             case_insensitive: false,
+            ..Default::default()
         },
         CancellationToken::noop(),
     ));
@@ -0,0 +1,139 @@
+//! `dupe:`, `namepartdupe:`, `sizedupe:`, `dmdupe:` and `attribdupe:` keep items sharing a
+//! key with at least one other candidate.
+
+mod common;
+
+use common::{paths, relative_paths};
+use search_cache::SearchCache;
+use std::{
+    fs::{self, File, Permissions},
+    os::unix::fs::PermissionsExt,
+    time::{Duration, SystemTime},
+};
+use tempdir::TempDir;
+
+// root/
+//   a/  report.txt (3 bytes), notes.md (5 bytes), Photo.JPG (7 bytes)
+//   b/  REPORT.txt (3 bytes), photo.jpg (9 bytes), notes.txt (11 bytes)
+//   c/  unique.rs (13 bytes)
+fn build_cache() -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new("duplicate_filters").unwrap();
+    for (i, (file, len)) in [
+        ("a/report.txt", 3),
+        ("a/notes.md", 5),
+        ("a/Photo.JPG", 7),
+        ("b/REPORT.txt", 3),
+        ("b/photo.jpg", 9),
+        ("b/notes.txt", 11),
+        ("c/unique.rs", 13),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let full = temp_dir.path().join(file);
+        fs::create_dir_all(full.parent().unwrap()).unwrap();
+        fs::write(&full, vec![b'x'; len]).unwrap();
+        // Distinct modification times unless a test says otherwise.
+        let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_600_000_000 + i as u64);
+        File::options()
+            .write(true)
+            .open(&full)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+    }
+    let cache = SearchCache::walk_fs(temp_dir.path().to_path_buf());
+    (temp_dir, cache)
+}
+
+#[test]
+fn dupe_groups_names_case_insensitively() {
+    let (tmp, mut cache) = build_cache();
+    assert_eq!(
+        relative_paths(&mut cache, tmp.path(), "dupe:"),
+        paths(&["a/Photo.JPG", "a/report.txt", "b/REPORT.txt", "b/photo.jpg"])
+    );
+}
+
+#[test]
+fn namepartdupe_ignores_extensions() {
+    let (tmp, mut cache) = build_cache();
+    assert_eq!(
+        relative_paths(&mut cache, tmp.path(), "namepartdupe:"),
+        paths(&[
+            "a/Photo.JPG",
+            "a/notes.md",
+            "a/report.txt",
+            "b/REPORT.txt",
+            "b/notes.txt",
+            "b/photo.jpg",
+        ])
+    );
+}
+
+#[test]
+fn sizedupe_only_compares_files() {
+    let (tmp, mut cache) = build_cache();
+    assert_eq!(
+        relative_paths(&mut cache, tmp.path(), "sizedupe:"),
+        paths(&["a/report.txt", "b/REPORT.txt"])
+    );
+}
+
+#[test]
+fn dmdupe_groups_modification_times() {
+    let (tmp, mut cache) = build_cache();
+    assert!(relative_paths(&mut cache, tmp.path(), "dmdupe:").is_empty());
+
+    let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    for file in ["a/notes.md", "c/unique.rs"] {
+        File::options()
+            .write(true)
+            .open(tmp.path().join(file))
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+    }
+    let mut cache = SearchCache::walk_fs(tmp.path().to_path_buf());
+    assert_eq!(
+        relative_paths(&mut cache, tmp.path(), "dmdupe:"),
+        paths(&["a/notes.md", "c/unique.rs"])
+    );
+}
+
+#[test]
+fn attribdupe_groups_permissions() {
+    let (tmp, mut cache) = build_cache();
+    for file in ["a/notes.md", "b/photo.jpg"] {
+        fs::set_permissions(tmp.path().join(file), Permissions::from_mode(0o600)).unwrap();
+    }
+    fs::set_permissions(
+        tmp.path().join("c/unique.rs"),
+        Permissions::from_mode(0o640),
+    )
+    .unwrap();
+    assert_eq!(
+        relative_paths(&mut cache, tmp.path(), "ext:md;jpg;rs attribdupe:"),
+        paths(&["a/notes.md", "b/photo.jpg"])
+    );
+}
+
+#[test]
+fn duplicates_are_grouped_within_the_other_terms() {
+    let (tmp, mut cache) = build_cache();
+    // Only `.txt` files are candidates, so the photos and `notes.md` no longer pair up.
+    assert_eq!(
+        relative_paths(&mut cache, tmp.path(), "ext:txt namepartdupe:"),
+        paths(&["a/report.txt", "b/REPORT.txt"])
+    );
+    assert_eq!(
+        relative_paths(&mut cache, tmp.path(), "notes namepartdupe:"),
+        paths(&["a/notes.md", "b/notes.txt"])
+    );
+    assert!(relative_paths(&mut cache, tmp.path(), "notes sizedupe:").is_empty());
+    // Duplicate filters are evaluated after the other terms wherever they are written.
+    assert_eq!(
+        relative_paths(&mut cache, tmp.path(), "namepartdupe: ext:txt"),
+        paths(&["a/report.txt", "b/REPORT.txt"])
+    );
+}
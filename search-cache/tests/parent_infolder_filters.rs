@@ -1,20 +1,18 @@
 //! Tests for parent: and infolder: filter improvements in commit a688ace.
 //! Verifies the optimized implementation that directly accesses child nodes
-//! instead of scanning the entire file tree.
+//! instead of scanning the entire file tree. Also covers nosubfolders:, path:
+//! and excluded prefixes, which scope results the same way.
 
-use search_cache::SearchCache;
+mod common;
+
+use common::{paths, relative_paths};
+use search_cache::{SearchCache, SearchOptions};
 use search_cancel::CancellationToken;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tempdir::TempDir;
 
 /// Build a test cache with nested directory structure.
 fn build_nested_cache() -> (SearchCache, PathBuf) {
-    let temp_dir = TempDir::new("parent_infolder_test").unwrap();
-    let root_path = temp_dir.path().to_path_buf();
-    // Leak the TempDir so files remain accessible.
-    std::mem::forget(temp_dir);
-
-    // Create nested structure:
     // root/
     //   file1.txt
     //   src/
@@ -27,25 +25,21 @@ fn build_nested_cache() -> (SearchCache, PathBuf) {
     //     test1.rs
     //   docs/
     //     readme.md
-    let files = [
-        "file1.txt",
-        "src/main.rs",
-        "src/lib.rs",
-        "src/utils/helper.rs",
-        "src/utils/config.rs",
-        "tests/test1.rs",
-        "docs/readme.md",
-    ];
-
-    for file in files {
-        let full = root_path.join(file);
-        if let Some(parent) = full.parent() {
-            std::fs::create_dir_all(parent).unwrap();
-        }
-        std::fs::File::create(full).unwrap();
-    }
-
-    let cache = SearchCache::walk_fs(root_path.clone());
+    let (temp_dir, cache) = common::build_cache(
+        "parent_infolder_test",
+        &[
+            "file1.txt",
+            "src/main.rs",
+            "src/lib.rs",
+            "src/utils/helper.rs",
+            "src/utils/config.rs",
+            "tests/test1.rs",
+            "docs/readme.md",
+        ],
+    );
+    let root_path = temp_dir.path().to_path_buf();
+    // Leak the TempDir so files remain accessible.
+    std::mem::forget(temp_dir);
     (cache, root_path)
 }
 
@@ -369,3 +363,252 @@ fn test_infolder_with_negation() {
         assert_ne!(path.extension().and_then(|s| s.to_str()), Some("rs"));
     }
 }
+
+// root/
+//   top.txt
+//   music/
+//     song.mp3
+//     album/
+//       track.mp3
+//   music2/
+//     other.mp3
+fn build_music_cache() -> (TempDir, SearchCache) {
+    common::build_cache(
+        "scope_filters",
+        &[
+            "top.txt",
+            "music/song.mp3",
+            "music/album/track.mp3",
+            "music2/other.mp3",
+        ],
+    )
+}
+
+#[test]
+fn trailing_slashes_are_ignored() {
+    let (tmp, mut cache) = build_music_cache();
+    let music = tmp.path().join("music");
+    for suffix in ["/", "//"] {
+        assert_eq!(
+            relative_paths(
+                &mut cache,
+                tmp.path(),
+                &format!("parent:{}{suffix}", music.display())
+            ),
+            paths(&["music/album", "music/song.mp3"])
+        );
+        assert_eq!(
+            relative_paths(
+                &mut cache,
+                tmp.path(),
+                &format!("infolder:{}{suffix}", music.display())
+            ),
+            paths(&["music/album", "music/album/track.mp3", "music/song.mp3"])
+        );
+    }
+}
+
+#[test]
+fn sibling_with_common_prefix_is_not_in_scope() {
+    let (tmp, mut cache) = build_music_cache();
+    let music = tmp.path().join("music");
+    let found = relative_paths(
+        &mut cache,
+        tmp.path(),
+        &format!("infolder:{} ext:mp3", music.display()),
+    );
+    assert!(!found.contains(&PathBuf::from("music2/other.mp3")));
+
+    let partial = tmp.path().join("mus");
+    assert!(
+        cache
+            .query_files(
+                format!("parent:{}", partial.display()),
+                CancellationToken::noop()
+            )
+            .is_err()
+    );
+}
+
+#[test]
+fn nosubfolders_limits_to_files_directly_in_the_folder() {
+    let (tmp, mut cache) = build_music_cache();
+    let music = tmp.path().join("music");
+    assert_eq!(
+        relative_paths(
+            &mut cache,
+            tmp.path(),
+            &format!("nosubfolders:{}", music.display())
+        ),
+        paths(&["music/song.mp3"])
+    );
+}
+
+#[test]
+fn nosubfolders_keeps_earlier_terms_when_they_match_more_than_the_folder() {
+    let (tmp, mut cache) = build_music_cache();
+    let music = tmp.path().join("music");
+    std::fs::File::create(music.join("cover.jpg")).unwrap();
+    std::fs::File::create(tmp.path().join("extra.mp3")).unwrap();
+    cache.rescan();
+    // `mp3` matches more nodes than `music` has children.
+    assert_eq!(
+        relative_paths(
+            &mut cache,
+            tmp.path(),
+            &format!("mp3 nosubfolders:{}", music.display())
+        ),
+        paths(&["music/song.mp3"])
+    );
+}
+
+fn relative_paths_excluding(
+    cache: &mut SearchCache,
+    root: &Path,
+    query: &str,
+    excluded: &[PathBuf],
+) -> Vec<PathBuf> {
+    let options = SearchOptions {
+        excluded_prefixes: excluded.to_vec(),
+        ..Default::default()
+    };
+    let mut paths: Vec<PathBuf> = cache
+        .query_files_with_options(query.to_string(), &options, CancellationToken::noop())
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|node| node.path.strip_prefix(root).unwrap().to_path_buf())
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn excluded_prefixes_drop_results_below_them() {
+    let (tmp, mut cache) = build_music_cache();
+    let excluded = [tmp.path().join("music")];
+    assert_eq!(
+        relative_paths_excluding(&mut cache, tmp.path(), "mp3", &excluded),
+        paths(&["music2/other.mp3"])
+    );
+    let options = SearchOptions {
+        excluded_prefixes: excluded.to_vec(),
+        ..Default::default()
+    };
+    let nodes = cache
+        .search_with_options("mp3", &options, CancellationToken::noop())
+        .unwrap()
+        .nodes
+        .unwrap();
+    let found: Vec<PathBuf> = cache
+        .expand_file_nodes(&nodes)
+        .into_iter()
+        .map(|node| node.path)
+        .collect();
+    assert_eq!(found, [tmp.path().join("music2/other.mp3")]);
+    // Matching is per component, the prefix folder itself is excluded too.
+    assert_eq!(
+        relative_paths_excluding(&mut cache, tmp.path(), "music", &excluded),
+        paths(&["music2"])
+    );
+    // Unknown prefixes exclude nothing.
+    assert_eq!(
+        relative_paths_excluding(&mut cache, tmp.path(), "mp3", &[tmp.path().join("missing")]),
+        relative_paths(&mut cache, tmp.path(), "mp3")
+    );
+}
+
+#[test]
+fn explicit_scope_into_excluded_prefix_wins() {
+    let (tmp, mut cache) = build_music_cache();
+    let excluded = [tmp.path().join("music"), tmp.path().join("music2")];
+    let album = tmp.path().join("music/album");
+    assert_eq!(
+        relative_paths_excluding(
+            &mut cache,
+            tmp.path(),
+            &format!("infolder:{} mp3", album.display()),
+            &excluded
+        ),
+        paths(&["music/album/track.mp3"])
+    );
+    // Scoping to a folder above the prefix doesn't lift it, nor does a negated scope.
+    assert_eq!(
+        relative_paths_excluding(
+            &mut cache,
+            tmp.path(),
+            &format!("infolder:{} mp3", tmp.path().display()),
+            &excluded
+        ),
+        Vec::<PathBuf>::new()
+    );
+    assert_eq!(
+        relative_paths_excluding(
+            &mut cache,
+            tmp.path(),
+            &format!("!infolder:{} txt", album.display()),
+            &excluded
+        ),
+        paths(&["top.txt"])
+    );
+}
+
+#[test]
+fn test_path_filter_matches_full_path_substring() {
+    let (mut cache, root) = build_nested_cache();
+
+    // The fragment spans a separator, no single name contains it.
+    assert_eq!(
+        relative_paths(&mut cache, &root, "path:utils/h"),
+        [PathBuf::from("src/utils/helper.rs")]
+    );
+    assert_eq!(
+        relative_paths(&mut cache, &root, "path:src/utils"),
+        [
+            PathBuf::from("src/utils"),
+            PathBuf::from("src/utils/config.rs"),
+            PathBuf::from("src/utils/helper.rs"),
+        ]
+    );
+    // Narrowing other terms, and negated.
+    assert_eq!(
+        relative_paths(&mut cache, &root, "ext:rs path:src/"),
+        [
+            PathBuf::from("src/lib.rs"),
+            PathBuf::from("src/main.rs"),
+            PathBuf::from("src/utils/config.rs"),
+            PathBuf::from("src/utils/helper.rs"),
+        ]
+    );
+    assert_eq!(
+        relative_paths(&mut cache, &root, "ext:rs !path:src/"),
+        [PathBuf::from("tests/test1.rs")]
+    );
+    // Unlike infolder:, a fragment that isn't a folder just matches nothing.
+    assert!(relative_paths(&mut cache, &root, "path:missing/dir").is_empty());
+    assert!(
+        cache
+            .query_files("path:".to_string(), CancellationToken::noop())
+            .is_err()
+    );
+}
+
+#[test]
+fn test_path_filter_follows_case_sensitivity() {
+    let (mut cache, _root) = build_nested_cache();
+    let mut count = |query: &str, case_insensitive| {
+        let options = SearchOptions {
+            case_insensitive,
+            ..Default::default()
+        };
+        cache
+            .search_with_options(query, &options, CancellationToken::noop())
+            .unwrap()
+            .nodes
+            .unwrap()
+            .len()
+    };
+    assert_eq!(count("path:SRC/UTILS", false), 0);
+    assert_eq!(count("path:SRC/UTILS", true), 3);
+    assert_eq!(count("ext:rs path:SRC/UTILS", true), 2);
+}
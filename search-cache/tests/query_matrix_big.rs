@@ -163,18 +163,20 @@ fn case_insensitive_option_matrix() {
     let mut cache = build_cache();
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let insensitive = cache
-        .search_with_options("readme.md", opts, CancellationToken::noop())
+        .search_with_options("readme.md", &opts, CancellationToken::noop())
         .unwrap()
         .nodes
         .unwrap()
         .len();
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let sensitive = cache
-        .search_with_options("readme.md", opts, CancellationToken::noop())
+        .search_with_options("readme.md", &opts, CancellationToken::noop())
         .unwrap()
         .nodes
         .unwrap()
@@ -188,7 +190,7 @@ fn cancellation_large_iteration() {
     let token = CancellationToken::new(9999);
     let _later = CancellationToken::new(10000); // cancel token
     let result = cache
-        .search_with_options("src lib tests", SearchOptions::default(), token)
+        .search_with_options("src lib tests", &SearchOptions::default(), token)
         .unwrap();
     assert!(
         result.nodes.is_none(),
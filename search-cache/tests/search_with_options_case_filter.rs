@@ -0,0 +1,104 @@
+use search_cache::{SearchCache, SearchOptions};
+use search_cancel::CancellationToken;
+use std::fs;
+use tempdir::TempDir;
+
+fn search_names(cache: &mut SearchCache, query: &str, case_insensitive: bool) -> Vec<String> {
+    let indices = cache
+        .search_with_options(
+            query,
+            &SearchOptions {
+                case_insensitive,
+                ..Default::default()
+            },
+            CancellationToken::noop(),
+        )
+        .expect("search should succeed")
+        .nodes
+        .expect("noop cancellation token should not cancel");
+    let mut names: Vec<String> = cache
+        .expand_file_nodes(&indices)
+        .into_iter()
+        .map(|node| {
+            node.path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+fn fixture(name: &str) -> (TempDir, SearchCache) {
+    let temp_dir = TempDir::new(name).unwrap();
+    let dir = temp_dir.path();
+    fs::File::create(dir.join("Report.txt")).unwrap();
+    fs::File::create(dir.join("report.txt")).unwrap();
+    fs::File::create(dir.join("my_report.md")).unwrap();
+    fs::File::create(dir.join("REPORT.pdf")).unwrap();
+    let cache = SearchCache::walk_fs(dir.to_path_buf());
+    (temp_dir, cache)
+}
+
+#[test]
+fn case_filter_argument_matches_exact_case() {
+    let (_tmp, mut cache) = fixture("case_filter_argument");
+    assert_eq!(
+        search_names(&mut cache, "report", true),
+        ["REPORT.pdf", "Report.txt", "my_report.md", "report.txt"]
+    );
+    assert_eq!(
+        search_names(&mut cache, "case:Report", true),
+        ["Report.txt"]
+    );
+    assert_eq!(
+        search_names(&mut cache, "case:report", true),
+        ["my_report.md", "report.txt"]
+    );
+}
+
+#[test]
+fn case_filter_switches_rest_of_query() {
+    let (_tmp, mut cache) = fixture("case_filter_toggle");
+    assert_eq!(
+        search_names(&mut cache, "case: report", true),
+        ["my_report.md", "report.txt"]
+    );
+    assert_eq!(
+        search_names(&mut cache, "Report case: my", true),
+        ["my_report.md"]
+    );
+    assert!(search_names(&mut cache, "Report case: MY", true).is_empty());
+}
+
+#[test]
+fn case_filter_toggle_only_covers_terms_after_it() {
+    let (_tmp, mut cache) = fixture("case_filter_trailing");
+    let all = ["REPORT.pdf", "Report.txt", "my_report.md", "report.txt"];
+    assert_eq!(search_names(&mut cache, "REPORT case:", true), all);
+    assert_eq!(search_names(&mut cache, "Report | case:REPORT", true), all);
+}
+
+#[test]
+fn negated_case_toggle_does_nothing() {
+    let (_tmp, mut cache) = fixture("case_filter_negated");
+    let all = ["REPORT.pdf", "Report.txt", "my_report.md", "report.txt"];
+    assert_eq!(search_names(&mut cache, "report !case:", true), all);
+    assert_eq!(search_names(&mut cache, "!case: report", true), all);
+    assert_eq!(search_names(&mut cache, "report | case:", true), all);
+    // Alone it leaves an empty query, which matches everything.
+    let everything = search_names(&mut cache, "", true);
+    assert_eq!(search_names(&mut cache, "!case:", true), everything);
+}
+
+#[test]
+fn case_filter_combines_with_other_terms() {
+    let (_tmp, mut cache) = fixture("case_filter_combined");
+    assert_eq!(
+        search_names(&mut cache, "case:Report ext:txt", true),
+        ["Report.txt"]
+    );
+    assert!(search_names(&mut cache, "case:Report ext:pdf", true).is_empty());
+}
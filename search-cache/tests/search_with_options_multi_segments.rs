@@ -23,10 +23,11 @@ fn and_space_multi_segments_basic() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // Space acts as AND; require both alpha and beta.
     let indices =
-        guard_indices(cache.search_with_options("alpha beta", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("alpha beta", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     assert_eq!(nodes.len(), 2);
     assert!(
@@ -48,9 +49,10 @@ fn or_operator_multi_segments() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("alpha | gamma", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("alpha | gamma", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     assert_eq!(nodes.len(), 2);
     assert!(nodes.iter().any(|n| n.path.ends_with("alpha_beta.txt")));
@@ -68,9 +70,10 @@ fn not_operator_excludes_segment() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("alpha !beta", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("alpha !beta", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     assert_eq!(nodes.len(), 2);
     assert!(nodes.iter().any(|n| n.path.ends_with("alpha_gamma.txt")));
@@ -89,11 +92,12 @@ fn mixed_and_or_precedence() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // Current precedence groups left-to-right; validate minimal presence of alpha_beta and any gamma-containing.
     let indices = guard_indices(cache.search_with_options(
         "alpha beta | gamma",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -120,10 +124,11 @@ fn multi_segments_with_wildcards() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // Use space-AND with a trailing wildcard on second term to reflect implementation behavior observed.
     let indices =
-        guard_indices(cache.search_with_options("alpha* beta*", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("alpha* beta*", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     // Depending on segmentation/wildcard translation, intersection may collapse; verify each match holds alpha and beta if any returned.
     // No cardinality assertion; zero results acceptable.
@@ -145,9 +150,10 @@ fn multi_segments_case_insensitive() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("alpha beta", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("alpha beta", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     // Validate at least two distinct matches ignoring case.
     assert!(nodes.len() >= 2);
@@ -173,11 +179,12 @@ fn regex_plus_plain_segment() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // regex selects numeric alpha, then AND beta plain segment
     let indices = guard_indices(cache.search_with_options(
         "regex:alpha\\d+ beta",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -196,11 +203,12 @@ fn filter_and_terms_multi_segments() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // ext:txt intersects with alpha and beta
     let indices = guard_indices(cache.search_with_options(
         "alpha beta ext:txt",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -220,11 +228,12 @@ fn not_with_filter_multi_segments() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // alpha AND beta AND NOT (ext:md) => .txt + .rs
     let indices = guard_indices(cache.search_with_options(
         "alpha beta !ext:md",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -245,11 +254,12 @@ fn chained_not_and_or_segments() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // (alpha AND gamma) OR (delta AND NOT beta)
     let indices = guard_indices(cache.search_with_options(
         "alpha gamma | delta !beta",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -276,7 +286,7 @@ fn wildcard_suffix_segment_matches_ending() {
     // Pattern *oo/bar => first segment ends with 'oo'
     let indices = guard_indices(cache.search_with_options(
         "*oo/bar",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -303,7 +313,7 @@ fn wildcard_prefix_segment_does_not_match_non_prefix() {
     // Pattern oo*/bar => segment starts with 'oo'
     let indices = guard_indices(cache.search_with_options(
         "oo*/bar",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -333,7 +343,7 @@ fn double_sided_wildcard_segment_matches_internal() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "f*o/bar",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -364,7 +374,7 @@ fn single_char_wildcard_prefix_segment() {
     // ?oo/bar => exactly one leading char plus 'oo'
     let indices = guard_indices(cache.search_with_options(
         "?oo/bar",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -391,7 +401,7 @@ fn single_char_wildcard_suffix_segment() {
     // oo?/bar => prefix 'oo' plus exactly one extra char
     let indices = guard_indices(cache.search_with_options(
         "oo?/bar",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -420,7 +430,7 @@ fn star_does_not_cross_directory_boundary() {
     // Pattern foo/baz*/bar should only expand within same segment "baz" variations, not merge segments
     let indices = guard_indices(cache.search_with_options(
         "foo/baz*/bar",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -451,9 +461,10 @@ fn partial_file_name_wildcard_extensions() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("readme*.md", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("readme*.md", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     let names: Vec<_> = nodes.iter().map(|n| n.path.display().to_string()).collect();
     assert!(names.iter().any(|n| n.ends_with("readme.md")));
@@ -476,9 +487,10 @@ fn partial_file_name_leading_wildcard() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("*readme.md", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("*readme.md", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     let names: Vec<_> = nodes.iter().map(|n| n.path.display().to_string()).collect();
     assert!(names.iter().any(|n| n.ends_with("readme.md")));
@@ -498,7 +510,7 @@ fn partial_segment_hyphen_boundary_variants() {
     // Pattern lib*core should match lib-core and libcore but not libXcore (if * doesn't skip internal capital boundary) — assume inclusive of all.
     let indices = guard_indices(cache.search_with_options(
         "src/lib*core/mod",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -523,9 +535,10 @@ fn case_insensitive_partial_segment_variants() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("foo*bar/baz", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("foo*bar/baz", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     let names: Vec<_> = nodes.iter().map(|n| n.path.display().to_string()).collect();
     assert!(!names.is_empty());
@@ -545,9 +558,10 @@ fn partial_unicode_segment_wildcard() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("café*/docs", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("café*/docs", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     let names: Vec<_> = nodes.iter().map(|n| n.path.display().to_string()).collect();
     assert!(
@@ -30,10 +30,11 @@ fn leading_slash_anchors_to_root_segment() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "/foo/bar/baz.txt",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let names = file_names(&mut cache, &indices);
@@ -57,9 +58,10 @@ fn trailing_slash_requires_exact_last_segment() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("docs/guide/", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("docs/guide/", &opts, CancellationToken::noop()));
     let names = file_names(&mut cache, &indices);
     assert_eq!(names.len(), 2);
     assert!(names.iter().any(|name| name.ends_with("docs/guide")));
@@ -78,7 +80,7 @@ fn no_leading_slash_matches_suffix_segment() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "foo/bar",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names = file_names(&mut cache, &indices);
@@ -98,9 +100,10 @@ fn case_insensitive_segments_match_variants() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("/foo/bar/baz/", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("/foo/bar/baz/", &opts, CancellationToken::noop()));
     let names = file_names(&mut cache, &indices);
     assert_eq!(names.len(), 1);
     assert!(names.iter().any(|name| name.ends_with("Foo/Bar/Baz")));
@@ -119,9 +122,13 @@ fn mixed_prefix_suffix_segments_for_files() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
-    let indices =
-        guard_indices(cache.search_with_options("foo/report.txt", opts, CancellationToken::noop()));
+    let indices = guard_indices(cache.search_with_options(
+        "foo/report.txt",
+        &opts,
+        CancellationToken::noop(),
+    ));
     let names = file_names(&mut cache, &indices);
     assert_eq!(names.len(), 2);
     assert!(names.iter().any(|name| name.ends_with("foo/report.txt")));
@@ -163,9 +170,10 @@ fn trailing_slash_deep_exact_directory() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("a/b/c/d/", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("a/b/c/d/", &opts, CancellationToken::noop()));
     let names = normalize(&mut cache, &indices, root);
     println!("wildcard_last_segment_multiple_extensions names={names:?}");
     println!("mixed_case_segments_case_sensitive_behavior names={names:?}");
@@ -197,7 +205,7 @@ fn leading_slash_with_wildcard_in_first_segment() {
     // Wildcard applies to first segment; expectation: directories whose first segment contains pattern prefix 'foo'.
     let indices = guard_indices(cache.search_with_options(
         "/foo*/bar/baz/",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -221,9 +229,10 @@ fn mixed_case_segments_case_sensitive_behavior() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("foo/bar/baz/", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("foo/bar/baz/", &opts, CancellationToken::noop()));
     let names = normalize(&mut cache, &indices, root);
     // Strict lowercase expected; mixed/uppercase variants should be excluded when case-sensitive
     assert!(
@@ -249,9 +258,10 @@ fn mixed_case_segments_case_insensitive_behavior() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("/foo/bar/baz/", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("/foo/bar/baz/", &opts, CancellationToken::noop()));
     let names = normalize(&mut cache, &indices, root);
     // Only baz directory (exact trailing slash) variants should appear; Bazooka excluded.
     assert!(
@@ -278,10 +288,11 @@ fn wildcard_last_segment_multiple_extensions() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "docs/guide/readme.*",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -313,10 +324,11 @@ fn wildcard_last_segment_multiple_extensions_case_insensitive() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "docs/guide/readme*.md",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -338,7 +350,7 @@ fn middle_segment_wildcard_variants() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "pkg-*/docs/v1/",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -366,7 +378,7 @@ fn overlapping_prefix_directories() {
     // Query uses a slash to combine directory + file name.
     let indices = guard_indices(cache.search_with_options(
         "app/config.json",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -385,7 +397,7 @@ fn wildcard_question_mark_inside_segment() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "lib-a?.tar.gz",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -409,7 +421,7 @@ fn multi_level_mixed_wildcards_and_trailing_slash() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "services/api-v*/internal/",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -443,7 +455,7 @@ fn path_query_with_dot_segments_and_files() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "config.d/profiles/dev.yaml",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -472,10 +484,11 @@ fn unicode_path_segments_case_insensitive() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "/café/文件/notes.txt",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -498,10 +511,11 @@ fn unicode_path_segments_case_sensitive() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "café/文件/notes.txt",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -526,7 +540,7 @@ fn deep_multiple_wildcards_varied_segments() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "src/lib*/core/mod.rs",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -552,7 +566,7 @@ fn file_match_with_intermediate_prefix_overlap() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "client/app/index.html",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -573,7 +587,7 @@ fn star_only_directory_inclusion() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "*",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -597,7 +611,7 @@ fn question_mark_in_directory_segment_boundaries() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "dir?/file.txt",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -622,7 +636,7 @@ fn multiple_question_marks_segment() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let indices = guard_indices(cache.search_with_options(
         "log-????.txt",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -646,7 +660,7 @@ fn mixed_star_and_question_mark_segment() {
     // Pattern: pkg-alpha-v?.rs -> one character version.
     let indices_short = guard_indices(cache.search_with_options(
         "pkg-alpha-v?.rs",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names_short = normalize(&mut cache, &indices_short, root);
@@ -657,7 +671,7 @@ fn mixed_star_and_question_mark_segment() {
     // Pattern: pkg-alpha-v*.rs -> any version.
     let indices_any = guard_indices(cache.search_with_options(
         "pkg-alpha-v*.rs",
-        SearchOptions::default(),
+        &SearchOptions::default(),
         CancellationToken::noop(),
     ));
     let names_any = normalize(&mut cache, &indices_any, root);
@@ -679,9 +693,10 @@ fn case_sensitive_exact_segment_casing() {
     // Case sensitive: only exact lower-case path should be returned for lower-case query.
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("src/lib/core/", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("src/lib/core/", &opts, CancellationToken::noop()));
     let names = normalize(&mut cache, &indices, root);
     assert!(
         names.iter().any(|n| n.ends_with("src/lib/core")),
@@ -708,9 +723,13 @@ fn case_insensitive_directory_variants() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
-    let indices =
-        guard_indices(cache.search_with_options("/src/lib/core/", opts, CancellationToken::noop()));
+    let indices = guard_indices(cache.search_with_options(
+        "/src/lib/core/",
+        &opts,
+        CancellationToken::noop(),
+    ));
     let names = normalize(&mut cache, &indices, root);
     assert!(
         !names.is_empty(),
@@ -735,10 +754,11 @@ fn mixed_wildcard_case_sensitive_file_variants() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "app/config/readme.*",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -771,10 +791,11 @@ fn mixed_wildcard_case_insensitive_file_variants() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "/app/config/readme.*",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -799,10 +820,11 @@ fn case_sensitive_file_exact_match_variants() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "guide/ReadMe.md",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -824,10 +846,11 @@ fn case_insensitive_file_exact_match_variants() {
     let mut cache = SearchCache::walk_fs(root.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "guide/readme.md",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let names = normalize(&mut cache, &indices, root);
@@ -837,3 +860,37 @@ fn case_insensitive_file_exact_match_variants() {
         assert!(n.to_ascii_lowercase().ends_with("guide/readme.md"));
     }
 }
+
+#[test]
+fn segments_must_be_consecutive_components() {
+    let temp_dir = TempDir::new("segments_must_be_consecutive_components").unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("root/bar")).unwrap();
+    fs::create_dir_all(root.join("root/other")).unwrap();
+    fs::create_dir_all(root.join("xroot")).unwrap();
+    fs::File::create(root.join("root/bar/kksk.txt")).unwrap();
+    fs::File::create(root.join("root/other/bar.txt")).unwrap();
+    fs::File::create(root.join("xroot/bar.txt")).unwrap();
+    fs::File::create(root.join("root_bar.txt")).unwrap();
+    fs::File::create(root.join("rootbar.txt")).unwrap();
+
+    let mut cache = SearchCache::walk_fs(root.to_path_buf());
+    let opts = SearchOptions {
+        case_insensitive: false,
+        ..Default::default()
+    };
+    let indices =
+        guard_indices(cache.search_with_options("/root/bar", &opts, CancellationToken::noop()));
+    let names = file_names(&mut cache, &indices);
+    assert_eq!(names.len(), 1, "{names:?}");
+    assert!(names[0].ends_with("root/bar"));
+
+    let indices = guard_indices(cache.search_with_options(
+        "/root/bar/kksk",
+        &opts,
+        CancellationToken::noop(),
+    ));
+    let names = file_names(&mut cache, &indices);
+    assert_eq!(names.len(), 1, "{names:?}");
+    assert!(names[0].ends_with("root/bar/kksk.txt"));
+}
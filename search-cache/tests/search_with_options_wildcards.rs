@@ -22,9 +22,13 @@ fn single_segment_wildcard_complex_pattern_case_sensitive() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
-    let indices =
-        guard_indices(cache.search_with_options("foo*alpha*.txt", opts, CancellationToken::noop()));
+    let indices = guard_indices(cache.search_with_options(
+        "foo*alpha*.txt",
+        &opts,
+        CancellationToken::noop(),
+    ));
     let nodes = cache.expand_file_nodes(&indices);
     // Should match only the exact lowercase path with the pattern anchored start 'foo'
     assert_eq!(nodes.len(), 1);
@@ -43,9 +47,10 @@ fn single_segment_wildcard_complex_pattern_case_insensitive() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("foo*bar*.txt", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("foo*bar*.txt", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     assert!(
         nodes.iter().any(|n| n.path.ends_with("foobar_bar.txt")),
@@ -66,9 +71,10 @@ fn leading_wildcard_matches_suffix() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("*beta.txt", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("*beta.txt", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     assert_eq!(nodes.len(), 2);
     assert!(nodes.iter().any(|n| n.path.ends_with("foo_beta.txt")));
@@ -86,9 +92,10 @@ fn trailing_wildcard_matches_prefix() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("alpha*", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("alpha*", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     // Matches names starting with 'alpha'
     assert_eq!(nodes.len(), 2);
@@ -107,9 +114,10 @@ fn question_mark_single_character() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     let indices =
-        guard_indices(cache.search_with_options("file?.txt", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("file?.txt", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     // file1.txt and file2.txt match, file10.txt does not
     assert_eq!(nodes.len(), 2);
@@ -128,8 +136,9 @@ fn star_only_matches_all_files() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
-    let indices = guard_indices(cache.search_with_options("*", opts, CancellationToken::noop()));
+    let indices = guard_indices(cache.search_with_options("*", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     // May include root directory; ensure at least the three files are present.
     let file_hits: Vec<_> = nodes
@@ -156,11 +165,12 @@ fn multi_segment_wildcard_intersection_case_sensitive() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // Both segments must match: alpha* AND *beta*.txt (beta can appear later)
     let indices = guard_indices(cache.search_with_options(
         "alpha* *beta*.txt",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -184,10 +194,11 @@ fn multi_segment_wildcard_intersection_case_insensitive() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: true,
+        ..Default::default()
     };
     let indices = guard_indices(cache.search_with_options(
         "alpha* *beta*.txt",
-        opts,
+        &opts,
         CancellationToken::noop(),
     ));
     let nodes = cache.expand_file_nodes(&indices);
@@ -211,10 +222,11 @@ fn complex_mixed_wildcards_and_question_mark() {
     let mut cache = SearchCache::walk_fs(dir.to_path_buf());
     let opts = SearchOptions {
         case_insensitive: false,
+        ..Default::default()
     };
     // Pattern: a*b?c*.txt => a then any, b then any single char, c then any, .txt
     let indices =
-        guard_indices(cache.search_with_options("a*b?c*.txt", opts, CancellationToken::noop()));
+        guard_indices(cache.search_with_options("a*b?c*.txt", &opts, CancellationToken::noop()));
     let nodes = cache.expand_file_nodes(&indices);
     assert_eq!(nodes.len(), 2); // aXXbYcZ.txt (bY) and a_b_cx.txt (b_ cx) match, abYYc.txt missing "b?" single char separation before c?
     assert!(nodes.iter().any(|n| n.path.ends_with("aXXbYcZ.txt")));
@@ -52,17 +52,7 @@ impl<T> Builder<T> {
     }
 
     pub(crate) fn build(mut self) -> Slab<T> {
-        let mut next = self.slab.builder_slots();
-        for idx in (0..self.slab.builder_slots()).rev() {
-            let entry = self.slab.builder_entry_mut(idx);
-            if matches!(entry, Entry::Vacant(_)) {
-                // Reconstruct the freelist tail-first so that the next vacant
-                // insert reuses the smallest index, mirroring the runtime behaviour.
-                *entry = Entry::Vacant(next);
-                next = idx;
-            }
-        }
-        self.slab.builder_set_next(next);
+        self.slab.rebuild_freelist();
         self.slab
     }
 }
@@ -245,6 +245,51 @@ impl<T> Slab<T> {
             index: 0,
         }
     }
+
+    /// Overwrites (`Some`) or vacates (`None`) the given slots, growing the slab when an index
+    /// lies past its end.
+    ///
+    /// Meant for replaying a change log onto a deserialized slab.  Vacating an arbitrary slot
+    /// would otherwise need a walk of the freelist, so the freelist is rebuilt once at the end
+    /// instead, lowest free index first, the same shape deserialization produces.
+    pub fn patch<I>(&mut self, changes: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = (usize, Option<T>)>,
+    {
+        for (index, value) in changes {
+            self.builder_reserve_slot(index)?;
+            let entry = self.builder_entry_mut(index);
+            let occupied = matches!(entry, Entry::Occupied(_));
+            match value {
+                Some(value) => {
+                    *entry = Entry::Occupied(value);
+                    if !occupied {
+                        self.len += 1;
+                    }
+                }
+                None if occupied => {
+                    *entry = Entry::Vacant(0);
+                    self.len -= 1;
+                }
+                None => {}
+            }
+        }
+        self.rebuild_freelist();
+        Ok(())
+    }
+
+    /// Relinks every vacant slot, tail-first so that the next insert reuses the smallest index.
+    fn rebuild_freelist(&mut self) {
+        let mut next = self.entries_len;
+        for idx in (0..self.entries_len).rev() {
+            let entry = self.builder_entry_mut(idx);
+            if matches!(entry, Entry::Vacant(_)) {
+                *entry = Entry::Vacant(next);
+                next = idx;
+            }
+        }
+        self.next = next;
+    }
 }
 
 impl<T> Drop for Slab<T> {
@@ -335,14 +380,6 @@ impl<T> Slab<T> {
         self.entry_mut(index).expect("builder ensured slot exists")
     }
 
-    pub(crate) fn builder_slots(&self) -> usize {
-        self.entries_len
-    }
-
-    pub(crate) fn builder_set_next(&mut self, next: usize) {
-        self.next = next;
-    }
-
     pub(crate) fn builder_increment_len(&mut self) {
         self.len += 1;
     }
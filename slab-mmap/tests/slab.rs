@@ -51,3 +51,23 @@ fn iter_skips_holes_and_preserves_order() {
     let collected: Vec<_> = slab.iter().collect();
     assert_eq!(collected, vec![(a, &"a"), (c, &"c")]);
 }
+
+#[test]
+fn patch_overwrites_vacates_and_grows() {
+    let mut slab = Slab::new().unwrap();
+    for value in 0..4 {
+        slab.insert(value).unwrap();
+    }
+
+    slab.patch([(1, None), (2, Some(20)), (6, Some(60)), (5, None)])
+        .unwrap();
+    let entries: Vec<_> = slab.iter().map(|(key, &value)| (key, value)).collect();
+    assert_eq!(entries, [(0, 0), (2, 20), (3, 3), (6, 60)]);
+    assert_eq!(slab.len(), 4);
+
+    // Freed and skipped slots are handed out lowest first.
+    assert_eq!(slab.insert(100).unwrap(), 1);
+    assert_eq!(slab.insert(101).unwrap(), 4);
+    assert_eq!(slab.insert(102).unwrap(), 5);
+    assert_eq!(slab.insert(103).unwrap(), 7);
+}
@@ -19,7 +19,7 @@ fn main() {
             .to_string_lossy()
             .to_string()
     });
-    let (dev, event_stream) = EventWatcher::spawn(path, cli.since, 0.1);
+    let (dev, event_stream) = EventWatcher::spawn(vec![path], Vec::new(), cli.since, 0.1);
     let cache = &mut std::collections::HashMap::new();
     let mut history_done = false;
     let timezone = chrono::Local::now().timezone();